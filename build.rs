@@ -8,6 +8,7 @@ use anyhow::{bail, Result};
 use convert_case::{Case, Casing};
 use ron::de::from_reader;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
@@ -50,9 +51,13 @@ enum Units {
     Kilohertz,
     RPM,
     Milliohms,
+    Ohms,
     VoltsPerMicrosecond,
     VoltsPerMillisecond,
     Watts,
+    WattHours,
+    KilowattHours,
+    Joules,
     MillivoltsPerAmp,
     MillivoltsPerCelsius,
     Percent,
@@ -60,6 +65,22 @@ enum Units {
 }
 
 impl Units {
+    //
+    // Pairs of units whose product is itself a unit.
+    //
+    const PRODUCTS: &'static [(Units, Units, Units)] = &[
+        (Units::Volts, Units::Amperes, Units::Watts),
+        (Units::Amperes, Units::Ohms, Units::Volts),
+        (Units::Watts, Units::Seconds, Units::Joules),
+    ];
+
+    //
+    // Units that no command uses directly, but that arise from arithmetic on
+    // those that do; these are always generated.
+    //
+    const DERIVED: &'static [Units] =
+        &[Units::Ohms, Units::Joules, Units::WattHours, Units::Seconds];
+
     fn suffix(&self) -> &str {
         match self {
             Units::Nanoseconds => "ns",
@@ -68,10 +89,14 @@ impl Units {
             Units::Seconds => "s",
             Units::Amperes => "A",
             Units::Milliohms => "mΩ",
+            Units::Ohms => "Ω",
             Units::Volts => "V",
             Units::Celsius => "°C",
             Units::RPM => "RPM",
             Units::Watts => "W",
+            Units::WattHours => "Wh",
+            Units::KilowattHours => "kWh",
+            Units::Joules => "J",
             Units::Kilohertz => "kHz",
             Units::VoltsPerMillisecond => "V/ms",
             Units::VoltsPerMicrosecond => "V/μs",
@@ -81,6 +106,86 @@ impl Units {
             Units::Unitless => "",
         }
     }
+
+    ///
+    /// Returns the other units in which these units can be expressed:  for
+    /// each, the name (for the generated methods), a description, and the
+    /// scale and offset to apply to convert into it.
+    ///
+    fn conversions(&self) -> &[(&str, &str, f32, f32)] {
+        match self {
+            Units::Amperes => &[
+                ("milliamperes", "milliamperes", 1e3, 0.0),
+                ("microamperes", "microamperes", 1e6, 0.0),
+            ],
+            Units::Volts => &[
+                ("millivolts", "millivolts", 1e3, 0.0),
+                ("microvolts", "microvolts", 1e6, 0.0),
+            ],
+            Units::Watts => &[
+                ("milliwatts", "milliwatts", 1e3, 0.0),
+                ("kilowatts", "kilowatts", 1e-3, 0.0),
+            ],
+            Units::Milliohms => &[("ohms", "ohms", 1e-3, 0.0)],
+            Units::Ohms => &[("milliohms", "milliohms", 1e3, 0.0)],
+            Units::Joules => &[("watt_hours", "watt-hours", 1.0 / 3600.0, 0.0)],
+            Units::WattHours => &[
+                ("joules", "joules", 3600.0, 0.0),
+                ("kilowatt_hours", "kilowatt-hours", 1e-3, 0.0),
+            ],
+            Units::Kilohertz => &[("hertz", "hertz", 1e3, 0.0)],
+            Units::KilowattHours => &[("watt_hours", "watt-hours", 1e3, 0.0)],
+            Units::Celsius => &[
+                ("fahrenheit", "degrees Fahrenheit", 1.8, 32.0),
+                ("kelvin", "kelvins", 1.0, 273.15),
+            ],
+            _ => &[],
+        }
+    }
+
+    ///
+    /// Returns the uom quantity and unit (both relative to `uom::si`) that
+    /// correspond to these units, if any.
+    ///
+    fn uom(&self) -> Option<(&str, &str)> {
+        match self {
+            Units::Nanoseconds => Some(("Time", "time::nanosecond")),
+            Units::Microseconds => Some(("Time", "time::microsecond")),
+            Units::Milliseconds => Some(("Time", "time::millisecond")),
+            Units::Seconds => Some(("Time", "time::second")),
+            Units::Amperes => {
+                Some(("ElectricCurrent", "electric_current::ampere"))
+            }
+            Units::Milliohms | Units::MillivoltsPerAmp => Some((
+                "ElectricalResistance",
+                "electrical_resistance::milliohm",
+            )),
+            Units::Ohms => {
+                Some(("ElectricalResistance", "electrical_resistance::ohm"))
+            }
+            Units::Volts => {
+                Some(("ElectricPotential", "electric_potential::volt"))
+            }
+            Units::Celsius => Some((
+                "ThermodynamicTemperature",
+                "thermodynamic_temperature::degree_celsius",
+            )),
+            Units::RPM => Some((
+                "AngularVelocity",
+                "angular_velocity::revolution_per_minute",
+            )),
+            Units::Watts => Some(("Power", "power::watt")),
+            Units::WattHours => Some(("Energy", "energy::watt_hour")),
+            Units::KilowattHours => Some(("Energy", "energy::kilowatt_hour")),
+            Units::Joules => Some(("Energy", "energy::joule")),
+            Units::Kilohertz => Some(("Frequency", "frequency::kilohertz")),
+            Units::Percent => Some(("Ratio", "ratio::percent")),
+            Units::VoltsPerMillisecond
+            | Units::VoltsPerMicrosecond
+            | Units::MillivoltsPerCelsius
+            | Units::Unitless => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -109,11 +214,22 @@ struct Coefficients {
     R: i8,
 }
 
+#[derive(Copy, Clone, Debug, Deserialize)]
+enum VidCode {
+    Vr11,
+    Vr12,
+    Vr13,
+    Imvp9,
+    Amd625mV,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 enum Format {
     Linear11,
     ULinear16,
     SLimear16,
+    Half,
+    Vid(VidCode),
     Direct(Coefficients),
     RuntimeDirect,
     VOutMode(Sign),
@@ -128,11 +244,23 @@ enum Bits {
     Bit(u8),
 }
 
+//
+// The order of the bytes within a multi-byte field; this must match
+// crate::ByteOrder.  Fields are little-endian unless otherwise specified.
+//
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+    WordSwapped,
+}
+
 #[derive(Debug, Deserialize)]
 struct Field {
     name: String,
     bits: Bits,
     values: Values<HashMap<String, Value>>,
+    byte_order: Option<ByteOrder>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -162,6 +290,68 @@ struct CommandNumericFormat(String, Format, Units);
 #[derive(Debug, Deserialize)]
 struct CommandSynonym(String, String);
 
+//
+// Other names by which a command is known (e.g., in earlier revisions of a
+// datasheet or of the specification).  The command's own name remains its
+// canonical name; aliases are accepted only in looking up a command by name.
+//
+#[derive(Debug, Deserialize)]
+struct CommandAlias(String, Vec<String>);
+
+//
+// The accuracy of a measurement, expressed as a percentage of its value.
+//
+#[derive(Debug, Deserialize)]
+struct CommandAccuracy(String, f32);
+
+//
+// The time that must be allowed to elapse after a command before the device
+// can be expected to accept another (e.g., for a store to nonvolatile
+// memory to complete).
+//
+#[derive(Copy, Clone, Debug, Deserialize)]
+enum Delay {
+    Microseconds(u64),
+    Milliseconds(u64),
+}
+
+impl Delay {
+    fn micros(&self) -> u64 {
+        match self {
+            Delay::Microseconds(us) => *us,
+            Delay::Milliseconds(ms) => ms * 1000,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CommandTiming(String, Delay);
+
+//
+// The revision of the PMBus specification that introduced a command; this
+// must match crate::SpecRevision.
+//
+#[derive(Copy, Clone, Debug, Deserialize)]
+enum SpecRevision {
+    Rev1_0,
+    Rev1_1,
+    Rev1_2,
+    Rev1_3,
+    Rev1_3_1,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommandIntroduced(String, SpecRevision);
+
+//
+// A command that is part of a device's configuration (as opposed to its
+// status or telemetry), along with the names of any of its fields that are
+// volatile -- that is, that change in normal operation rather than as a
+// result of the device being configured differently.
+//
+#[derive(Debug, Deserialize)]
+struct CommandConfiguration(String, Vec<String>);
+
 #[derive(Clone, Debug, Deserialize)]
 struct Auxiliary(String, Operation);
 
@@ -183,6 +373,57 @@ struct Auxiliaries {
     structured: HashMap<String, Fields>,
 }
 
+//
+// Extended commands are those that live behind the MFR_SPECIFIC_COMMAND_EXT
+// (0xFE) prefix:  the command code on the wire is the prefix followed by the
+// extended command code.  These have their own code space, and are therefore
+// emitted in their own module.
+//
+#[derive(Debug, Deserialize)]
+struct Extended {
+    all: Vec<Command>,
+    numerics: Vec<CommandNumericFormat>,
+    #[serde(with = "::serde_with::rust::maps_duplicate_key_is_error")]
+    structured: HashMap<String, Fields>,
+}
+
+//
+// Some devices change the meaning of registers between silicon (or
+// firmware) revisions.  A revision specifies structured definitions that
+// replace those of the device for revisions in [since, until); revisions
+// are numbered as the device numbers them, and it is up to the consumer to
+// derive this number from IC_DEVICE_REV (or elsewhere).
+//
+#[derive(Debug, Deserialize)]
+struct Revision {
+    since: u32,
+    until: Option<u32>,
+    #[serde(with = "::serde_with::rust::maps_duplicate_key_is_error")]
+    structured: HashMap<String, Fields>,
+}
+
+//
+// The DIRECT coefficients that a datasheet gives for a device's
+// RuntimeDirect numerics, as selected by the values of fields in a
+// configuration command.  Each set applies to the numerics in the given
+// units; if the set is scaled, its slope is per milliohm of sense
+// resistance.
+//
+#[derive(Debug, Deserialize)]
+struct CoefficientSet {
+    units: Units,
+    when: Vec<(String, String)>,
+    coefficients: Coefficients,
+    #[serde(default)]
+    scaled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoefficientSets {
+    configuration: String,
+    sets: Vec<CoefficientSet>,
+}
+
 #[derive(Debug, Deserialize)]
 struct Commands {
     all: Vec<Command>,
@@ -190,9 +431,22 @@ struct Commands {
     #[serde(with = "::serde_with::rust::maps_duplicate_key_is_error")]
     structured: HashMap<String, Fields>,
     synonyms: Option<Vec<CommandSynonym>>,
+    aliases: Option<Vec<CommandAlias>>,
+    coefficients: Option<CoefficientSets>,
     auxiliaries: Option<Auxiliaries>,
+    extended: Option<Extended>,
+    accuracy: Option<Vec<CommandAccuracy>>,
+    timing: Option<Vec<CommandTiming>>,
+    introduced: Option<Vec<CommandIntroduced>>,
+    configuration: Option<Vec<CommandConfiguration>>,
+    revisions: Option<Vec<Revision>>,
 }
 
+//
+// A device may be based on another (e.g., a member of the same family), in
+// which case it inherits the commands, numerics and structured definitions
+// of its base that it doesn't itself define.
+//
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
 struct Device {
@@ -200,10 +454,12 @@ struct Device {
     part: String,
     description: String,
     coefficients: Option<Coefficients>,
+    base: Option<String>,
 }
 
 enum OutputCommand<'a> {
-    PMBus(&'a str),
+    /// A PMBus command, and whether it is read as a block
+    PMBus(&'a str, bool),
     Auxiliary(&'a str),
 }
 
@@ -256,6 +512,7 @@ fn aux_sizes(auxs: &Vec<Auxiliary>) -> Result<HashMap<String, Option<usize>>> {
 }
 
 #[rustfmt::skip::macros(writeln)]
+#[rustfmt::skip::macros(write)]
 fn output_commands(
     cmds: &Commands,
     shadowing: Option<&Commands>,
@@ -278,6 +535,7 @@ use crate::Error;"##)?;
     writeln!(&mut s, r##"
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug, PartialEq, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CommandCode {{"##)?;
 
@@ -285,10 +543,16 @@ pub enum CommandCode {{"##)?;
         writeln!(&mut s, "    {} = 0x{:x},", cmd.1, cmd.0)?;
     }
 
+    //
+    // The name and operations of a command are emitted as const functions
+    // (allowing them to be used in constant lookup tables and compile-time
+    // assertions), to which the implementation of Command defers.
+    //
     writeln!(&mut s, r##"}}
 
-impl crate::Command for CommandCode {{
-    fn name(&self) -> &'static str {{
+impl CommandCode {{
+    /// Returns the name of this command
+    pub const fn name(&self) -> &'static str {{
         match self {{"##)?;
 
     for cmd in &cmds.all {
@@ -299,7 +563,8 @@ impl crate::Command for CommandCode {{
     writeln!(&mut s, "        }}\n    }}")?;
 
     writeln!(&mut s, r##"
-    fn read_op(&self) -> Operation {{
+    /// Returns the operation for reading data with this command, if any
+    pub const fn read_op(&self) -> Operation {{
         match self {{"##)?;
 
     for cmd in &cmds.all {
@@ -310,7 +575,8 @@ impl crate::Command for CommandCode {{
     writeln!(&mut s, "        }}\n    }}")?;
 
     writeln!(&mut s, r##"
-    fn write_op(&self) -> Operation {{
+    /// Returns the operation for writing data with this command, if any
+    pub const fn write_op(&self) -> Operation {{
         match self {{"##)?;
 
     for cmd in &cmds.all {
@@ -318,169 +584,546 @@ impl crate::Command for CommandCode {{
             "            CommandCode::{} => Operation::{:?},", cmd.1, cmd.2)?;
     }
 
-    writeln!(&mut s, "        }}\n    }}\n}}")?;
+    writeln!(&mut s, r##"        }}
+    }}
+}}
 
-    let mut numerics = HashSet::new();
-    let mut synonyms = HashSet::new();
+impl crate::Command for CommandCode {{
+    fn name(&self) -> &'static str {{
+        CommandCode::name(self)
+    }}
 
-    for cmd in &cmds.numerics {
-        numerics.insert(&cmd.0);
-    }
+    fn read_op(&self) -> Operation {{
+        CommandCode::read_op(self)
+    }}
 
-    if let Some(ref syn) = cmds.synonyms {
-        for cmd in syn {
-            synonyms.insert(&cmd.0);
-        }
-    }
+    fn write_op(&self) -> Operation {{
+        CommandCode::write_op(self)
+    }}"##)?;
 
     //
-    // If we are a device, we need to go through any numerics that we're
-    // shadowing as well, as they will have a device-local definition.
+    // Any delays specified by the device override those that it shadows;
+    // shadowed delays for commands that the device has redefined are
+    // dropped.
     //
-    if let Some(shadowing) = shadowing {
-        for cmd in &shadowing.numerics {
-            numerics.insert(&cmd.0);
+    let mut delays = BTreeMap::new();
+    let known = |cmd: &String| cmds.all.iter().any(|c| &c.1 == cmd);
+
+    if let Some(timing) = shadowing.and_then(|s| s.timing.as_ref()) {
+        for CommandTiming(cmd, delay) in timing.iter().filter(|t| known(&t.0)) {
+            delays.insert(cmd, delay.micros());
         }
     }
 
-    writeln!(&mut s, r##"
-impl CommandCode {{
-    pub fn interpret(
-        &self,
-        payload: &[u8],
-        mode: impl Fn() -> VOutModeCommandData,
-        iter: impl FnMut(&dyn Field, &dyn Value)
-    ) -> Result<(), Error> {{
-        match self {{"##)?;
+    if let Some(ref timing) = cmds.timing {
+        for CommandTiming(cmd, delay) in timing {
+            if !known(cmd) {
+                bail!("timing specified for unknown command {}", cmd);
+            }
 
-    for cmd in &cmds.all {
-        if cmds.structured.get(&cmd.1).is_none()
-            && numerics.get(&cmd.1).is_none()
-            && synonyms.get(&cmd.1).is_none()
-        {
-            continue;
+            delays.insert(cmd, delay.micros());
         }
-
-        writeln!(&mut s, r##"            CommandCode::{} => {{
-                use {}::CommandData;
-                if let Some(data) = CommandData::from_slice(payload) {{
-                    data.interpret(mode, iter)
-                }} else {{
-                    Err(Error::ShortData)
-                }}
-            }}"##, cmd.1, cmd.1)?;
     }
 
-    if shadowing.is_some() {
-        //
-        // For devices, we want to fallback to calling the common data
-        // method.
-        //
-        writeln!(&mut s, r##"            _ => {{
-                let code = *self as u8;
-                match super::CommandCode::from_u8(code) {{
-                    Some(cmd) => cmd.interpret(payload, mode, iter),
-                    None => Ok(())
-                }}
-            }}"##)?;
+    writeln!(&mut s, r##"
+    fn delay_after(&self) -> Option<core::time::Duration> {{"##)?;
+
+    if delays.is_empty() {
+        writeln!(&mut s, "        None")?;
     } else {
-        writeln!(&mut s, "            _ => Ok(()),")?;
+        writeln!(&mut s, "        match self {{")?;
+
+        for (cmd, us) in &delays {
+            writeln!(&mut s, "            CommandCode::{} => \
+                Some(core::time::Duration::from_micros({})),", cmd, us)?;
+        }
+
+        writeln!(&mut s, "            _ => None,\n        }}")?;
     }
 
-    writeln!(&mut s, r##"        }}
-    }}
+    writeln!(&mut s, "    }}")?;
 
-    pub fn mutate(
-        &self,
-        payload: &mut [u8],
-        mode: impl Fn() -> VOutModeCommandData,
-        iter: impl FnMut(&dyn Field, &dyn Value) -> Option<Replacement>
-    ) -> Result<(), Error> {{
-        match self {{"##)?;
+    //
+    // As with delays, the revisions that introduced shadowed commands apply
+    // only to those commands that the device hasn't redefined.
+    //
+    let mut introduced = BTreeMap::new();
 
-    for cmd in &cmds.all {
-        if cmds.structured.get(&cmd.1).is_none()
-            && numerics.get(&cmd.1).is_none()
-            && synonyms.get(&cmd.1).is_none()
-        {
-            continue;
+    if let Some(intro) = shadowing.and_then(|s| s.introduced.as_ref()) {
+        for CommandIntroduced(cmd, rev) in intro {
+            if known(cmd) {
+                introduced.insert(cmd, *rev);
+            }
         }
+    }
 
-        writeln!(&mut s, r##"            CommandCode::{} => {{
-                use {}::CommandData;
-                if let Some(mut data) = CommandData::from_slice(payload) {{
-                    data.mutate(mode, iter)?;
-                    data.to_slice(payload);
-                    Ok(())
-                }} else {{
-                    Err(Error::ShortData)
-                }}
-            }}"##, cmd.1, cmd.1)?;
+    if let Some(ref intro) = cmds.introduced {
+        for CommandIntroduced(cmd, rev) in intro {
+            if !known(cmd) {
+                bail!("revision specified for unknown command {}", cmd);
+            }
+
+            introduced.insert(cmd, *rev);
+        }
     }
 
-    if shadowing.is_some() {
-        //
-        // For devices, we want to fallback to calling the common mutate
-        // method.
-        //
-        writeln!(&mut s, r##"            _ => {{
-                let code = *self as u8;
-                match super::CommandCode::from_u8(code) {{
-                    Some(cmd) => cmd.mutate(payload, mode, iter),
-                    None => Ok(())
-                }}
-            }}"##)?;
+    writeln!(&mut s, r##"
+    fn introduced(&self) -> Option<crate::SpecRevision> {{"##)?;
+
+    if introduced.is_empty() {
+        writeln!(&mut s, "        None")?;
     } else {
-        writeln!(&mut s, "            _ => Ok(()),")?;
+        writeln!(&mut s, "        match self {{")?;
+
+        for (cmd, rev) in &introduced {
+            writeln!(&mut s, "            CommandCode::{} => \
+                Some(crate::SpecRevision::{:?}),", cmd, rev)?;
+        }
+
+        writeln!(&mut s, "            _ => None,\n        }}")?;
     }
 
-    writeln!(&mut s, r##"        }}
-    }}
+    writeln!(&mut s, "    }}")?;
 
-    pub fn fields(
-        &self,
-        iter: impl FnMut(&dyn Field)
-    ) -> Result<(), Error> {{
-        match self {{"##)?;
+    //
+    // Configuration commands are inherited as the other annotations are.
+    // Volatile fields are resolved against the structured definition that
+    // the device actually uses; shadowed volatile fields that the device's
+    // own definition lacks are dropped.
+    //
+    let mut configuration = BTreeMap::new();
+    let structured = |cmd: &String| {
+        cmds.structured
+            .get(cmd)
+            .or_else(|| shadowing.and_then(|s| s.structured.get(cmd)))
+    };
 
-    for cmd in &cmds.all {
-        if cmds.structured.get(&cmd.1).is_none()
-            && numerics.get(&cmd.1).is_none()
-            && synonyms.get(&cmd.1).is_none()
-        {
-            continue;
+    if let Some(config) = shadowing.and_then(|s| s.configuration.as_ref()) {
+        for CommandConfiguration(cmd, volatile) in config {
+            if known(cmd) {
+                let mask =
+                    volatile_mask(cmd, structured(cmd), volatile, false)?;
+                configuration.insert(cmd, mask);
+            }
         }
+    }
 
-        writeln!(&mut s, r##"            CommandCode::{} => {{
-                {}::CommandData::fields(iter)
-            }}"##, cmd.1, cmd.1)?;
+    if let Some(ref config) = cmds.configuration {
+        for CommandConfiguration(cmd, volatile) in config {
+            if !known(cmd) {
+                bail!("configuration specified for unknown command {}", cmd);
+            }
+
+            let mask = volatile_mask(cmd, structured(cmd), volatile, true)?;
+            configuration.insert(cmd, mask);
+        }
     }
 
-    if shadowing.is_some() {
-        //
-        // For devices, we want to fallback to calling the common fields
-        // method.
-        //
-        writeln!(&mut s, r##"            _ => {{
-                let code = *self as u8;
-                match super::CommandCode::from_u8(code) {{
-                    Some(cmd) => cmd.fields(iter),
-                    None => Ok(())
-                }}
-            }}"##)?;
+    writeln!(&mut s, r##"
+    fn configuration(&self) -> bool {{"##)?;
+
+    if configuration.is_empty() {
+        writeln!(&mut s, "        false")?;
     } else {
-        writeln!(&mut s, "            _ => Ok(()),")?;
+        writeln!(&mut s, "        matches!(\n            self,")?;
+
+        for (i, cmd) in configuration.keys().enumerate() {
+            writeln!(&mut s, "            {}CommandCode::{}",
+                if i == 0 { "" } else { "| " }, cmd)?;
+        }
+
+        writeln!(&mut s, "        )")?;
     }
 
-    writeln!(&mut s, r##"        }}
-    }}
+    writeln!(&mut s, r##"    }}
 
-    pub fn sentinels(
-        &self,
-        field: Bitpos,
-        iter: impl FnMut(&dyn Value)
-    ) -> Result<(), Error> {{
-        match self {{"##)?;
+    fn volatile(&self) -> u32 {{"##)?;
+
+    if configuration.values().all(|&mask| mask == 0) {
+        writeln!(&mut s, "        0")?;
+    } else {
+        writeln!(&mut s, "        match self {{")?;
+
+        for (cmd, mask) in configuration.iter().filter(|(_, &m)| m != 0) {
+            writeln!(&mut s,
+                "            CommandCode::{} => 0x{:x},", cmd, mask)?;
+        }
+
+        writeln!(&mut s, "            _ => 0,\n        }}")?;
+    }
+
+    writeln!(&mut s, "    }}")?;
+
+    //
+    // Aliases are inherited for the commands that the device knows, unless
+    // the device specifies its own aliases for the command.  An alias may
+    // not collide with the name of a command or with another alias; an
+    // inherited alias that collides with the name of a device's command is
+    // dropped.
+    //
+    let mut aliases = BTreeMap::new();
+
+    if let Some(shadowed) = shadowing.and_then(|s| s.aliases.as_ref()) {
+        for CommandAlias(cmd, names) in shadowed.iter().filter(|a| known(&a.0))
+        {
+            let names = names.iter().filter(|n| !known(n)).collect::<Vec<_>>();
+            aliases.insert(cmd, names);
+        }
+    }
+
+    if let Some(ref local) = cmds.aliases {
+        for CommandAlias(cmd, names) in local {
+            if !known(cmd) {
+                bail!("aliases specified for unknown command {}", cmd);
+            }
+
+            if let Some(name) = names.iter().find(|n| known(n)) {
+                bail!("alias {} for {} is the name of a command", name, cmd);
+            }
+
+            aliases.insert(cmd, names.iter().collect());
+        }
+    }
+
+    let mut seen = HashSet::new();
+
+    for names in aliases.values() {
+        for name in names {
+            if !seen.insert(name) {
+                bail!("alias {} is specified for multiple commands", name);
+            }
+        }
+    }
+
+    writeln!(&mut s, r##"
+    fn aliases(&self) -> &'static [&'static str] {{"##)?;
+
+    if aliases.values().all(|names| names.is_empty()) {
+        writeln!(&mut s, "        &[]")?;
+    } else {
+        writeln!(&mut s, "        match self {{")?;
+
+        for (cmd, names) in aliases.iter().filter(|(_, n)| !n.is_empty()) {
+            let names = names
+                .iter()
+                .map(|n| format!("\"{}\"", n))
+                .collect::<Vec<_>>();
+
+            writeln!(&mut s, "            CommandCode::{} => &[{}],",
+                cmd, names.join(", "))?;
+        }
+
+        writeln!(&mut s, "            _ => &[],\n        }}")?;
+    }
+
+    writeln!(&mut s, r##"    }}
+}}
+
+impl CommandCode {{
+    /// Returns the command with the specified name, which may be either
+    /// its canonical name or one of its aliases
+    pub fn from_name(name: &str) -> Option<Self> {{
+        match name {{"##)?;
+
+    for cmd in &cmds.all {
+        write!(&mut s, "            \"{}\"", cmd.1)?;
+
+        for alias in aliases.get(&cmd.1).into_iter().flatten() {
+            write!(&mut s, "\n            | \"{}\"", alias)?;
+        }
+
+        writeln!(&mut s, " => Some(CommandCode::{}),", cmd.1)?;
+    }
+
+    writeln!(&mut s, "            _ => None,\n        }}\n    }}\n}}")?;
+
+    //
+    // The format and units of a command's payload are determined by its
+    // numeric or structured definition -- falling back, for a device, to
+    // the common command at the same code.  A command that has a payload
+    // but no definition is raw; one without a payload has no format.
+    //
+    let definition = |c: &Commands, name: &String| {
+        if let Some(n) = c.numerics.iter().find(|n| &n.0 == name) {
+            let units = match n.1 {
+                Format::Raw => None,
+                _ => Some(n.2),
+            };
+
+            Some((runtime_format(&n.1), units))
+        } else if c.structured.contains_key(name)
+            || c.synonyms.iter().flatten().any(|syn| &syn.0 == name)
+        {
+            Some(("Bitfield".to_string(), None))
+        } else {
+            None
+        }
+    };
+
+    let payload = |op: &Operation| {
+        !matches!(
+            op,
+            Operation::SendByte
+                | Operation::Extended
+                | Operation::Illegal
+                | Operation::Unknown
+        )
+    };
+
+    let mut formats = vec![];
+
+    for cmd in cmds.all.iter().filter(|c| payload(&c.2) || payload(&c.3)) {
+        let common = shadowing.and_then(|shadowing| {
+            definition(shadowing, &cmd.1).or_else(|| {
+                let c = shadowing.all.iter().find(|c| c.0 == cmd.0)?;
+                definition(shadowing, &c.1)
+            })
+        });
+
+        let format = match definition(cmds, &cmd.1).or(common) {
+            Some(format) => format,
+            None => ("Raw".to_string(), None),
+        };
+
+        formats.push((&cmd.1, format));
+    }
+
+    writeln!(&mut s, r##"
+impl CommandCode {{
+    /// Returns the format of the command's data payload, or `None` if the
+    /// command has no payload
+    pub fn format(&self) -> Option<crate::Format> {{
+        match self {{"##)?;
+
+    for (cmd, (format, _)) in &formats {
+        writeln!(&mut s,
+            "            CommandCode::{} => Some(crate::Format::{}),",
+            cmd, format)?;
+    }
+
+    writeln!(&mut s, r##"            _ => None,
+        }}
+    }}
+
+    /// Returns the units of the command's value, or `None` if the command
+    /// does not have a value in units
+    pub fn units(&self) -> Option<crate::units::Units> {{
+        match self {{"##)?;
+
+    for (cmd, (_, units)) in &formats {
+        if let Some(units) = units {
+            writeln!(&mut s, "            CommandCode::{} => \
+                Some(crate::units::Units::{:?}),", cmd, units)?;
+        }
+    }
+
+    writeln!(&mut s, "            _ => None,\n        }}\n    }}\n}}")?;
+
+    let mut numerics = HashSet::new();
+    let mut synonyms = HashSet::new();
+
+    for cmd in &cmds.numerics {
+        numerics.insert(&cmd.0);
+    }
+
+    if let Some(ref syn) = cmds.synonyms {
+        for cmd in syn {
+            synonyms.insert(&cmd.0);
+        }
+    }
+
+    //
+    // If we are a device, we need to go through any numerics that we're
+    // shadowing as well, as they will have a device-local definition.
+    //
+    if let Some(shadowing) = shadowing {
+        for cmd in &shadowing.numerics {
+            numerics.insert(&cmd.0);
+        }
+    }
+
+    //
+    // Rather than having interpretation be a match on the command code, we
+    // generate a table of interpreters indexed by code:  an entry of `None`
+    // denotes an invalid code, and valid codes that have no data map to an
+    // interpreter that does nothing.  For devices, codes that have no
+    // device-specific data fall back to the common interpreter.
+    //
+    let data = |cmd: &String| {
+        cmds.structured.contains_key(cmd)
+            || numerics.contains(cmd)
+            || synonyms.contains(cmd)
+    };
+
+    writeln!(&mut s, r##"
+#[allow(dead_code)]
+fn interpret_nodata(
+    _payload: &[u8],
+    _mode: &dyn Fn() -> VOutModeCommandData,
+    _iter: &mut dyn FnMut(&dyn Field, &dyn Value),
+) -> Result<(), Error> {{
+    Ok(())
+}}"##)?;
+
+    for cmd in cmds.all.iter().filter(|cmd| data(&cmd.1)) {
+        writeln!(&mut s, r##"
+#[allow(non_snake_case)]
+fn interpret_{}(
+    payload: &[u8],
+    mode: &dyn Fn() -> VOutModeCommandData,
+    iter: &mut dyn FnMut(&dyn Field, &dyn Value),
+) -> Result<(), Error> {{
+    use {}::CommandData;
+    if let Some(data) = CommandData::from_slice(payload) {{
+        data.interpret(mode, iter)
+    }} else if payload.len() < CommandData::len() {{
+        Err(Error::ShortData)
+    }} else {{
+        Err(Error::InvalidLength)
+    }}
+}}"##, cmd.1, cmd.1)?;
+    }
+
+    let mut table: Vec<Option<String>> = vec![None; 256];
+
+    for cmd in &cmds.all {
+        table[cmd.0 as usize] = Some(if data(&cmd.1) {
+            format!("Some(interpret_{})", cmd.1)
+        } else {
+            match shadowing {
+                Some(shadowing)
+                    if shadowing.all.iter().any(|c| c.0 == cmd.0) =>
+                {
+                    format!("super::INTERPRETERS[0x{:02x}]", cmd.0)
+                }
+                _ => "Some(interpret_nodata)".to_string(),
+            }
+        });
+    }
+
+    writeln!(&mut s, r##"
+/// Interpreters for each command code, indexed by code; see
+/// [`crate::Interpreter`].  An entry of `None` denotes an invalid code.
+pub const INTERPRETERS: [Option<crate::Interpreter>; 256] = ["##)?;
+
+    for (code, entry) in table.iter().enumerate() {
+        writeln!(&mut s, "    /* 0x{:02x} */ {},", code, match entry {
+            Some(entry) => entry,
+            None => "None",
+        })?;
+    }
+
+    writeln!(&mut s, r##"];
+
+impl CommandCode {{
+    pub fn interpret(
+        &self,
+        payload: &[u8],
+        mode: impl crate::VOutMode,
+        mut iter: impl FnMut(&dyn Field, &dyn Value)
+    ) -> Result<(), Error> {{
+        match INTERPRETERS[*self as usize] {{
+            Some(interpret) => {{
+                interpret(payload, &|| mode.vout_mode(), &mut iter)
+            }}
+            None => Ok(()),
+        }}
+    }}"##)?;
+
+    writeln!(&mut s, r##"
+    pub fn mutate(
+        &self,
+        payload: &mut [u8],
+        mode: impl crate::VOutMode,
+        iter: impl FnMut(&dyn Field, &dyn Value) -> Option<Replacement>
+    ) -> Result<(), Error> {{
+        match self {{"##)?;
+
+    for cmd in &cmds.all {
+        if cmds.structured.get(&cmd.1).is_none()
+            && numerics.get(&cmd.1).is_none()
+            && synonyms.get(&cmd.1).is_none()
+        {
+            continue;
+        }
+
+        writeln!(&mut s, r##"            CommandCode::{} => {{
+                use {}::CommandData;
+                if let Some(mut data) = CommandData::from_slice(payload) {{
+                    data.mutate(mode, iter)?;
+                    data.to_slice(payload);
+                    Ok(())
+                }} else if payload.len() < CommandData::len() {{
+                    Err(Error::ShortData)
+                }} else {{
+                    Err(Error::InvalidLength)
+                }}
+            }}"##, cmd.1, cmd.1)?;
+    }
+
+    if shadowing.is_some() {
+        //
+        // For devices, we want to fallback to calling the common mutate
+        // method.
+        //
+        writeln!(&mut s, r##"            _ => {{
+                let code = *self as u8;
+                match super::CommandCode::from_u8(code) {{
+                    Some(cmd) => cmd.mutate(payload, mode, iter),
+                    None => Ok(())
+                }}
+            }}"##)?;
+    } else {
+        writeln!(&mut s, "            _ => Ok(()),")?;
+    }
+
+    writeln!(&mut s, r##"        }}
+    }}
+
+    pub fn fields(
+        &self,
+        iter: impl FnMut(&dyn Field)
+    ) -> Result<(), Error> {{
+        match self {{"##)?;
+
+    for cmd in &cmds.all {
+        if cmds.structured.get(&cmd.1).is_none()
+            && numerics.get(&cmd.1).is_none()
+            && synonyms.get(&cmd.1).is_none()
+        {
+            continue;
+        }
+
+        writeln!(&mut s, r##"            CommandCode::{} => {{
+                {}::CommandData::fields(iter)
+            }}"##, cmd.1, cmd.1)?;
+    }
+
+    if shadowing.is_some() {
+        //
+        // For devices, we want to fallback to calling the common fields
+        // method.
+        //
+        writeln!(&mut s, r##"            _ => {{
+                let code = *self as u8;
+                match super::CommandCode::from_u8(code) {{
+                    Some(cmd) => cmd.fields(iter),
+                    None => Ok(())
+                }}
+            }}"##)?;
+    } else {
+        writeln!(&mut s, "            _ => Ok(()),")?;
+    }
+
+    writeln!(&mut s, r##"        }}
+    }}
+
+    pub fn sentinels(
+        &self,
+        field: Bitpos,
+        iter: impl FnMut(&dyn Value)
+    ) -> Result<(), Error> {{
+        match self {{"##)?;
 
     for cmd in &cmds.all {
         if cmds.structured.get(&cmd.1).is_none()
@@ -523,13 +1166,46 @@ fn bitrange(bits: &Bits) -> (u8, u8) {
     }
 }
 
+//
+// Returns the mask of the bits covered by the named volatile fields of the
+// specified command.  If strict, a named field that isn't defined is an
+// error; otherwise it is ignored.
+//
+fn volatile_mask(
+    cmd: &str,
+    fields: Option<&Fields>,
+    volatile: &[String],
+    strict: bool,
+) -> Result<u32> {
+    let mut mask = 0u64;
+
+    for name in volatile {
+        match fields.and_then(|f| f.0.get(name)) {
+            Some(field) => {
+                let (high, low) = bitrange(&field.bits);
+                mask |= ((1u64 << (high - low + 1)) - 1) << low;
+            }
+            None if strict => {
+                bail!("volatile field {} not defined for {}", name, cmd);
+            }
+            None => {}
+        }
+    }
+
+    if mask > u32::MAX as u64 {
+        bail!("volatile fields of {} exceed 32 bits", cmd);
+    }
+
+    Ok(mask as u32)
+}
+
 #[rustfmt::skip::macros(bail)]
 fn validate(
     cmd: &str,
     fields: &Fields,
     sizes: &HashMap<String, Option<usize>>,
     units: &mut HashSet<Units>,
-) -> Result<(usize, usize)> {
+) -> Result<(usize, usize, bool)> {
     let mut highest = 0;
     let fields = &fields.0;
 
@@ -561,6 +1237,20 @@ fn validate(
             highest = high;
         }
 
+        let width = high - low + 1;
+
+        match field.byte_order {
+            Some(ByteOrder::BigEndian) if low % 8 != 0 || width % 8 != 0 => {
+                bail!("{}: big-endian field \"{}\" is not byte-aligned",
+                    cmd, f);
+            }
+            Some(ByteOrder::WordSwapped) if low % 8 != 0 || width % 16 != 0 => {
+                bail!("{}: word-swapped field \"{}\" is not word-sized",
+                    cmd, f);
+            }
+            _ => {}
+        }
+
         for bit in low..=high {
             match v[bit as usize] {
                 None => {
@@ -589,16 +1279,16 @@ fn validate(
     //
     if bits == 128 {
         let bits = (highest + 1).next_power_of_two();
-        Ok((bits.into(), ((highest + 7) / 8).into()))
+        Ok((bits.into(), ((highest + 7) / 8).into(), true))
     } else {
-        Ok((bits, size))
+        Ok((bits, size, false))
     }
 }
 
 #[rustfmt::skip::macros(writeln)]
 fn output_scalar(name: &str, width: usize) -> Result<String> {
     let mut s = String::new();
-    let bits = ((width + 7) / 8) * 8;
+    let bits = (((width + 7) / 8) * 8).next_power_of_two();
 
     writeln!(&mut s, r##"
     #[derive(Copy, Clone, Debug, PartialEq, FromPrimitive, ToPrimitive)]
@@ -682,6 +1372,7 @@ fn output_value(
 
 #[rustfmt::skip::macros(writeln)]
 fn output_command(
+    scope: &str,
     cmd: OutputCommand,
     fields: &Fields,
     bits: usize,
@@ -690,9 +1381,9 @@ fn output_command(
     let mut s = String::new();
     let fields = &fields.0;
 
-    let (cmd, auxiliary) = match cmd {
-        OutputCommand::PMBus(str) => (str, false),
-        OutputCommand::Auxiliary(str) => (str, true),
+    let (cmd, auxiliary, block) = match cmd {
+        OutputCommand::PMBus(str, block) => (str, false, block),
+        OutputCommand::Auxiliary(str) => (str, true, false),
     };
 
     writeln!(&mut s, r##"
@@ -703,7 +1394,6 @@ pub mod {} {{
     use crate::Bitpos;
     use crate::Bitwidth;
     use crate::Error;
-    use crate::VOutModeCommandData;
     use crate::Replacement;
 
     use num_derive::FromPrimitive;
@@ -781,13 +1471,26 @@ pub mod {} {{
         )?;
     }
 
-    writeln!(&mut s, "            }}\n        }}\n    }}")?;
+    writeln!(&mut s, "            }}\n        }}")?;
 
     writeln!(&mut s, r##"
-    impl Field {{
-        #[allow(unused_variables)]
-        #[allow(unused_mut)]
-        fn sentinels(&self, mut sentinel: impl FnMut(&dyn crate::Value)) {{
+        fn id(&self) -> u32 {{
+            match self {{"##)?;
+
+    for f in fields.keys() {
+        writeln!(
+            &mut s, "                Field::{} => {:#010x},",
+            f, field_id(scope, cmd, f)
+        )?;
+    }
+
+    writeln!(&mut s, "            }}\n        }}\n    }}")?;
+
+    writeln!(&mut s, r##"
+    impl Field {{
+        #[allow(unused_variables)]
+        #[allow(unused_mut)]
+        fn sentinels(&self, mut sentinel: impl FnMut(&dyn crate::Value)) {{
             match self {{"##)?;
 
     for (f, field) in fields {
@@ -906,6 +1609,74 @@ pub mod {} {{
     }
 
     writeln!(&mut s, "                Value::Unknown(v) => *v as u32,")?;
+    writeln!(&mut s, "            }}\n        }}")?;
+
+    writeln!(&mut s, r##"
+        fn as_float(&self) -> Option<f32> {{"##)?;
+
+    let real = |v: &Values<_>| {
+        matches!(v, Values::FixedPointUnits(..) | Values::LogFactorUnits(..))
+    };
+
+    if fields.iter().any(|(_, field)| real(&field.values)) {
+        writeln!(&mut s, r##"
+            let raw = crate::Value::raw(self);
+"##)?;
+    }
+
+    writeln!(&mut s, "            match self {{")?;
+
+    for (f, field) in fields {
+        match &field.values {
+            Values::FixedPointUnits(Factor(factor), _) => {
+                writeln!(&mut s, "                Value::{}(_) => \
+                    Some(raw as f32 / ({} as f32)),", f, factor)?;
+            }
+
+            Values::LogFactorUnits(Base(base), Factor(factor), _) => {
+                writeln!(&mut s, "                Value::{}(_) => \
+                    Some(crate::FloatCore::powi({} as f32, raw as i32) \
+                    / ({} as f32)),",
+                    f, base, factor)?;
+            }
+
+            _ => {}
+        }
+    }
+
+    writeln!(&mut s, "                _ => None,")?;
+    writeln!(&mut s, "            }}\n        }}")?;
+
+    writeln!(&mut s, r##"
+        fn units(&self) -> &'static str {{
+            match self {{"##)?;
+
+    for (f, field) in fields {
+        match &field.values {
+            Values::FixedPointUnits(_, u) | Values::LogFactorUnits(_, _, u) => {
+                writeln!(&mut s, "                Value::{}(_) => \"{}\",",
+                    f, u.suffix())?;
+            }
+
+            _ => {}
+        }
+    }
+
+    writeln!(&mut s, "                _ => \"\",")?;
+    writeln!(&mut s, "            }}\n        }}")?;
+
+    writeln!(&mut s, r##"
+        fn sentinel_name(&self) -> Option<&'static str> {{
+            match self {{"##)?;
+
+    for (f, field) in fields {
+        if let Values::Sentinels(_) = field.values {
+            writeln!(&mut s,
+                "                Value::{}(v) => Some(v.name()),", f)?;
+        }
+    }
+
+    writeln!(&mut s, "                _ => None,")?;
     writeln!(&mut s, "            }}\n        }}\n    }}")?;
 
     writeln!(&mut s, r##"
@@ -929,7 +1700,7 @@ pub mod {} {{
                 writeln!(&mut s, r##"
                 Value::{}(_) => {{
                     write!(
-                        f, "{{:.2}}{}",
+                        f, "{{:.*}}{}", f.precision().unwrap_or(2),
                         crate::Value::raw(self) as f32 / ({} as f32)
                     )
                 }}"##, f, u.suffix(), factor)?;
@@ -939,9 +1710,10 @@ pub mod {} {{
                 writeln!(&mut s, r##"
                 Value::{}(_) => {{
                     write!(
-                        f, "{{:.2}}{}",
-                        ({} as f32).powi(crate::Value::raw(self) as i32) /
-                        ({} as f32)
+                        f, "{{:.*}}{}", f.precision().unwrap_or(2),
+                        crate::FloatCore::powi(
+                            {} as f32, crate::Value::raw(self) as i32
+                        ) / ({} as f32)
                     )
                 }}"##, f, u.suffix(), base, factor)?;
             }
@@ -974,6 +1746,16 @@ pub mod {} {{
         }}"##, cmd)?;
     }
 
+    //
+    // A payload of the wrong size is rejected -- save that the structure of
+    // a block need only describe a prefix of it, in which case the
+    // remainder of the block is ignored.
+    //
+    let (slice, cmp) = match block {
+        true => (format!("slice.get(0..{})?", bytes), "<"),
+        false => ("slice".to_string(), "!="),
+    };
+
     writeln!(&mut s, r##"
         pub fn from_slice(slice: &[u8]) -> Option<Self> {{"##)?;
 
@@ -981,14 +1763,20 @@ pub mod {} {{
         writeln!(&mut s, r##"
             use core::convert::TryInto;
 
-            let v: Result<&[u8; {}], _> = slice[0..{}].try_into();
+            let v: Result<&[u8; {}], _> = {}.try_into();
 
             match v {{
                 Ok(v) => Some(Self(u{}::from_le_bytes(*v))),
                 Err(_) => None,
             }}
-        }}"##, bytes, bytes, bits)?;
+        }}"##, bytes, slice, bits)?;
     } else {
+        writeln!(&mut s, r##"
+            if slice.len() {} {} {{
+                return None;
+            }}
+"##, cmp, bytes)?;
+
         writeln!(&mut s, "            let v: u{} = ", bits)?;
 
         for i in 0..bytes {
@@ -1036,16 +1824,27 @@ pub mod {} {{
     writeln!(&mut s, "            }}\n        }}")?;
 
     writeln!(&mut s, r##"
+        fn mask(width: Bitwidth) -> u{} {{
+            //
+            // A field may span the entire payload, in which case we can't
+            // shift our way to its mask.
+            //
+            match (1 as u{}).checked_shl(width.0 as u32) {{
+                Some(bit) => bit - 1,
+                None => u{}::MAX,
+            }}
+        }}
+
         pub fn get_val(&self, field: Field) -> u{} {{
             use crate::Field;
             let (pos, width) = field.bits();
-            (self.0 >> pos.0) & ((1 << width.0) - 1)
+            Self::order(field, (self.0 >> pos.0) & Self::mask(width))
         }}
         
         pub fn get(&self, field: Field) -> Result<Value, Error> {{
             let raw = self.get_val(field);
 
-            match field {{"##, bits)?;
+            match field {{"##, bits, bits, bits, bits)?;
 
     for (f, _) in fields {
         writeln!(&mut s, r##"
@@ -1059,18 +1858,54 @@ pub mod {} {{
 
     writeln!(&mut s, "            }}\n        }}")?;
 
+    //
+    // Fields that aren't little-endian are converted to (and from) native
+    // order as they are read (and written); as every conversion is its own
+    // inverse, the same function serves both directions.
+    //
+    writeln!(&mut s, r##"
+        #[allow(unused_variables)]
+        fn order(field: Field, raw: u{}) -> u{} {{"##, bits, bits)?;
+
+    let ordered = fields
+        .iter()
+        .filter_map(|(f, field)| match field.byte_order {
+            Some(ByteOrder::LittleEndian) | None => None,
+            Some(order) => Some((f, order)),
+        })
+        .collect::<Vec<_>>();
+
+    if ordered.is_empty() {
+        writeln!(&mut s, "            raw")?;
+    } else {
+        writeln!(&mut s,
+            "            let (_, width) = crate::Field::bits(&field);\n")?;
+        writeln!(&mut s, "            match field {{")?;
+
+        for (f, order) in ordered {
+            writeln!(&mut s, "                Field::{} => \
+                crate::ByteOrder::{:?}.convert(raw as u128, width) as u{},",
+                f, order, bits)?;
+        }
+
+        writeln!(&mut s, "                _ => raw,\n            }}")?;
+    }
+
+    writeln!(&mut s, "        }}")?;
+
     writeln!(&mut s, r##"
         #[allow(dead_code)]
         fn set_val(&mut self, field: Field, raw: u{}) -> Result<(), Error> {{
             use crate::Field;
             let (pos, width) = field.bits();
-            let mask = (1 << width.0) - 1;
+            let mask = Self::mask(width);
 
             if width.0 < {} && raw > mask {{
                 Err(Error::ValueOutOfRange)
             }} else {{
+                let raw = Self::order(field, raw & mask);
                 self.0 &= !(mask << pos.0);
-                self.0 |= (raw & mask) << pos.0;
+                self.0 |= raw << pos.0;
                 Ok(())
             }}
         }}
@@ -1083,15 +1918,16 @@ pub mod {} {{
         ) -> Result<(), Error> {{
             use crate::Field;
             let (pos, width) = field.bits();
-            let mask = (1 << width.0) - 1;
+            let mask = Self::mask(width);
             let max = (mask >> 1) as i{};
             let min = !(max as u{}) as i{};
 
             if width.0 < {} && (raw > max || raw < min) {{
                 Err(Error::ValueOutOfRange)
             }} else {{
+                let raw = Self::order(field, (raw as u{}) & mask);
                 self.0 &= !(mask << pos.0);
-                self.0 |= ((raw as u{}) & mask) << pos.0;
+                self.0 |= raw << pos.0;
                 Ok(())
             }}
         }}
@@ -1115,8 +1951,8 @@ pub mod {} {{
             }
 
             Values::Scalar(Sign::Signed) => {
-                let (high, _) = bitrange(&field.bits);
-                let shift = bits - (high + 1) as usize;
+                let (high, low) = bitrange(&field.bits);
+                let shift = bits - (high - low + 1) as usize;
 
                 writeln!(&mut s, r##"
         pub fn get_{}(&self) -> i{} {{
@@ -1150,7 +1986,9 @@ pub mod {} {{
                 writeln!(&mut s, r##"
         pub fn get_{}(&self) -> crate::units::{:?} {{
             crate::units::{:?}(
-                ({} as f32).powi(self.get_val(Field::{}) as i32) / ({} as f32)
+                crate::FloatCore::powi(
+                    {} as f32, self.get_val(Field::{}) as i32
+                ) / ({} as f32)
             )
         }}"##, method, unit, unit, base, f, factor)?;
 
@@ -1190,7 +2028,7 @@ pub mod {} {{
     impl crate::CommandData for CommandData {{
         fn interpret(
             &self,
-            _mode: impl Fn() -> VOutModeCommandData,
+            _mode: impl crate::VOutMode,
             mut iter: impl FnMut(&dyn crate::Field, &dyn crate::Value)
         ) -> Result<(), Error> {{
             let mut pos: u8 = {};
@@ -1212,7 +2050,7 @@ pub mod {} {{
 
         fn mutate(
             &mut self,
-            _mode: impl Fn() -> VOutModeCommandData,
+            _mode: impl crate::VOutMode,
             mut iter: impl FnMut(
                 &dyn crate::Field, &dyn crate::Value
             ) -> Option<Replacement>
@@ -1311,25 +2149,69 @@ pub mod {} {{
 }
 
 fn output_command_data(
+    scope: &str,
     cmd: &str,
     fields: &Fields,
     bits: usize,
     bytes: usize,
+    block: bool,
 ) -> Result<String> {
-    output_command(OutputCommand::PMBus(cmd), fields, bits, bytes)
+    let cmd = OutputCommand::PMBus(cmd, block);
+    output_command(scope, cmd, fields, bits, bytes)
 }
 
 fn output_aux_data(
+    scope: &str,
     aux: &str,
     fields: &Fields,
     bits: usize,
     bytes: usize,
 ) -> Result<String> {
-    output_command(OutputCommand::Auxiliary(aux), fields, bits, bytes)
+    output_command(scope, OutputCommand::Auxiliary(aux), fields, bits, bytes)
+}
+
+//
+// Returns the type that implements DIRECT for a numeric of the specified
+// width:  DIRECT data are ordinarily words, but some (e.g., READ_KWH_IN) are
+// 32 bits wide.
+//
+fn direct(cmd: &str, bits: usize) -> Result<&'static str> {
+    match bits {
+        16 => Ok("Direct"),
+        32 => Ok("Direct32"),
+        _ => bail!("{} has {} bits, but Direct must have 16 or 32", cmd, bits),
+    }
+}
+
+//
+// Returns the run-time counterpart (that is, the crate::Format variant) of
+// a numeric format.
+//
+fn runtime_format(format: &Format) -> String {
+    match format {
+        Format::Linear11 => "Linear11".to_string(),
+        Format::ULinear16 => "ULinear16".to_string(),
+        Format::SLimear16 => "SLinear16".to_string(),
+        Format::Half => "Half".to_string(),
+        Format::Vid(code) => format!("Vid(crate::VidCode::{:?})", code),
+        Format::Direct(c) => format!(
+            "Direct(crate::Coefficients {{ m: {}, b: {}, R: {} }})",
+            c.m, c.b, c.R
+        ),
+        Format::RuntimeDirect => "RuntimeDirect".to_string(),
+        Format::VOutMode(Sign::Unsigned) => "VOutMode".to_string(),
+        Format::VOutMode(Sign::Signed) => "SignedVOutMode".to_string(),
+        Format::FixedPoint(Factor(f)) => format!("FixedPoint({:?})", f),
+        Format::SignedFixedPoint(Factor(f)) => {
+            format!("SignedFixedPoint({:?})", f)
+        }
+        Format::Raw => "Raw".to_string(),
+    }
 }
 
 #[rustfmt::skip::macros(writeln)]
 fn output_command_numeric(
+    scope: &str,
     cmd: OutputCommand,
     format: &Format,
     u: &Units,
@@ -1337,12 +2219,13 @@ fn output_command_numeric(
     coeff: Option<Coefficients>,
 ) -> Result<String> {
     let (cmd, auxiliary) = match cmd {
-        OutputCommand::PMBus(str) => (str, false),
+        OutputCommand::PMBus(str, _) => (str, false),
         OutputCommand::Auxiliary(str) => (str, true),
     };
 
     let mut s = String::new();
     let bits = bytes * 8;
+    let id = field_id(scope, cmd, "scalar");
 
     let units = &format!("crate::units::{:?}", u);
 
@@ -1358,7 +2241,6 @@ pub mod {} {{
     pub struct CommandData(pub u{});
 
     use crate::Error;
-    use crate::VOutModeCommandData;
     use crate::Replacement;
 
     #[allow(unused_imports)]
@@ -1375,13 +2257,16 @@ pub mod {} {{
     pub struct CommandData(pub u{});
 
     use crate::Error;
-    use crate::VOutModeCommandData;
     use crate::Replacement;
 
     #[allow(unused_imports)]
     use crate::Coefficients;"##, cmd, cmd, cmd, bits)?;
     }
 
+    if let Format::VOutMode(_) = format {
+        writeln!(&mut s, "    use crate::VOutMode;")?;
+    }
+
     if let Format::Raw = format {
         writeln!(&mut s, r##"
     #[derive(Copy, Clone, Debug, PartialEq)]
@@ -1409,6 +2294,10 @@ pub mod {} {{
         fn raw(&self) -> u32 {{
             self.0
         }}
+
+        fn as_float(&self) -> Option<f32> {{
+            None
+        }}
     }}"##, cmd, cmd)?;
     } else {
         writeln!(&mut s, r##"
@@ -1417,7 +2306,7 @@ pub mod {} {{
 
     impl core::fmt::Display for Value {{
         fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {{
-            write!(f, "{{:.2}}{}", self.0.0)
+            write!(f, "{{:.*}}{}", f.precision().unwrap_or(2), self.0.0)
         }}
     }}
 
@@ -1437,7 +2326,15 @@ pub mod {} {{
         fn raw(&self) -> u32 {{
             self.1
         }}
-    }}"##, units, u.suffix(), cmd, cmd)?;
+
+        fn as_float(&self) -> Option<f32> {{
+            Some(self.0.0)
+        }}
+
+        fn units(&self) -> &'static str {{
+            "{}"
+        }}
+    }}"##, units, u.suffix(), cmd, cmd, u.suffix())?;
     }
 
     writeln!(&mut s, r##"
@@ -1449,13 +2346,13 @@ pub mod {} {{
         pub fn from_slice(slice: &[u8]) -> Option<Self> {{
             use core::convert::TryInto;
 
-            let v: Result<&[u8; {}], _> = slice[0..{}].try_into();
+            let v: Result<&[u8; {}], _> = slice.try_into();
 
             match v {{
                 Ok(v) => Some(Self(u{}::from_le_bytes(*v))),
                 Err(_) => None,
             }}
-        }}"##, bytes, bytes, bytes, bits)?;
+        }}"##, bytes, bytes, bits)?;
 
     if !auxiliary {
         writeln!(&mut s, r##"
@@ -1489,21 +2386,124 @@ pub mod {} {{
             }} else {{
                 Err(Error::ValueOutOfRange)
             }}
-        }}"##, units, units, units)?;
+        }}
+
+        pub fn set_rounded(
+            &mut self,
+            val: {},
+            rounding: crate::Rounding
+        ) -> Result<(), Error> {{
+            match crate::Linear11::from_real_rounded(val.0, rounding) {{
+                Some(lin) => {{
+                    self.0 = lin.0;
+                    Ok(())
+                }}
+                None => Err(Error::ValueOutOfRange),
+            }}
+        }}
+
+        /// Sets the value, clamping it to the range that can be represented
+        pub fn set_saturating(&mut self, val: {}) -> Result<(), Error> {{
+            let saturation = crate::Saturation::Clamp;
+            self.0 = crate::Linear11::try_from_real(val.0, saturation)?.0;
+            Ok(())
+        }}"##, units, units, units, units, units)?;
+        }
+
+        Format::Half => {
+            if bits != 16 {
+                bail!("{} has {} bits, but Half must have 16", cmd, bits);
+            }
+
+            writeln!(&mut s, r##"
+        pub fn get(&self) -> Result<{}, Error> {{
+            Ok({}(crate::Half(self.0).to_real()))
+        }}
+
+        pub fn set(&mut self, val: {}) -> Result<(), Error> {{
+            if let Some(half) = crate::Half::from_real(val.0) {{
+                self.0 = half.0;
+                Ok(())
+            }} else {{
+                Err(Error::ValueOutOfRange)
+            }}
+        }}
+
+        pub fn set_rounded(
+            &mut self,
+            val: {},
+            rounding: crate::Rounding
+        ) -> Result<(), Error> {{
+            match crate::Half::from_real_rounded(val.0, rounding) {{
+                Some(half) => {{
+                    self.0 = half.0;
+                    Ok(())
+                }}
+                None => Err(Error::ValueOutOfRange),
+            }}
+        }}
+
+        /// Sets the value, clamping it to the range that can be represented
+        pub fn set_saturating(&mut self, val: {}) -> Result<(), Error> {{
+            let saturation = crate::Saturation::Clamp;
+            self.0 = crate::Half::try_from_real(val.0, saturation)?.0;
+            Ok(())
+        }}"##, units, units, units, units, units)?;
+        }
+
+        Format::Vid(code) => {
+            if bits != 16 {
+                bail!("{} has {} bits, but Vid must have 16", cmd, bits);
+            }
+
+            writeln!(&mut s, r##"
+        pub fn get(&self) -> Result<{}, Error> {{
+            let code = crate::VidCode::{:?};
+            Ok({}(crate::Vid(self.0, code).to_real()))
+        }}
+
+        pub fn set(&mut self, val: {}) -> Result<(), Error> {{
+            match crate::Vid::from_real(val.0, crate::VidCode::{:?}) {{
+                Some(vid) => {{
+                    self.0 = vid.0;
+                    Ok(())
+                }}
+                None => Err(Error::ValueOutOfRange),
+            }}
+        }}
+
+        /// Sets the value, clamping it to the range that can be represented
+        pub fn set_saturating(&mut self, val: {}) -> Result<(), Error> {{
+            let code = crate::VidCode::{:?};
+            let saturation = crate::Saturation::Clamp;
+            self.0 = crate::Vid::try_from_real(val.0, code, saturation)?.0;
+            Ok(())
+        }}"##, units, code, units, units, code, units, code)?;
         }
 
-        Format::VOutMode(_) => {
+        Format::VOutMode(sign) => {
+            //
+            // In its ULINEAR16 mode, VOUT_MODE dictates the exponent; signed
+            // values have a two's complement mantissa (SLINEAR16).
+            //
+            let linear = match sign {
+                Sign::Unsigned => "ULinear16",
+                Sign::Signed => "SLinear16",
+            };
+
             writeln!(&mut s, r##"
-        pub fn get(&self, mode: VOutModeCommandData) -> Result<{}, Error> {{
+        pub fn get(&self, mode: impl VOutMode) -> Result<{}, Error> {{
+            let mode = mode.vout_mode();
+
             match mode.get_mode() {{
                 Some(crate::commands::VOUT_MODE::Mode::ULINEAR16) => {{
-                    let exp = crate::ULinear16Exponent(mode.get_parameter());
+                    let exp = mode.exponent();
                     Ok({}(
-                        crate::ULinear16(self.0, exp).to_real()
+                        crate::{}(self.0, exp).to_real()
                     ))
                 }}
                 Some(crate::commands::VOUT_MODE::Mode::Direct) => {{"##,
-                units, units)?;
+                units, units, linear)?;
 
             match coeff {
                 Some(coeff) => {
@@ -1524,6 +2524,9 @@ pub mod {} {{
             }
 
             writeln!(&mut s, r##"                }}
+                Some(crate::commands::VOUT_MODE::Mode::IEEEHalf) => {{
+                    Ok({}(crate::Half(self.0).to_real()))
+                }}
                 _ => {{
                     Err(Error::InvalidMode)
                 }}
@@ -1531,21 +2534,50 @@ pub mod {} {{
         }}
 
         pub fn set(
-            &mut self, mode: VOutModeCommandData, val: {}
+            &mut self, mode: impl VOutMode, val: {}
+        ) -> Result<(), Error> {{
+            self.set_rounded(mode, val, crate::Rounding::Nearest)
+        }}
+
+        pub fn set_rounded(
+            &mut self,
+            mode: impl VOutMode,
+            val: {},
+            rounding: crate::Rounding
+        ) -> Result<(), Error> {{
+            let saturation = crate::Saturation::Fail;
+            self.encode(mode, val, rounding, saturation)
+        }}
+
+        /// Sets the value, clamping it to the range that can be represented
+        pub fn set_saturating(
+            &mut self, mode: impl VOutMode, val: {}
+        ) -> Result<(), Error> {{
+            let saturation = crate::Saturation::Clamp;
+            self.encode(mode, val, crate::Rounding::Nearest, saturation)
+        }}
+
+        fn encode(
+            &mut self,
+            mode: impl VOutMode,
+            val: {},
+            rounding: crate::Rounding,
+            saturation: crate::Saturation
         ) -> Result<(), Error> {{
+            let mode = mode.vout_mode();
+
             match mode.get_mode() {{
                 Some(crate::commands::VOUT_MODE::Mode::ULINEAR16) => {{
-                    let exp = crate::ULinear16Exponent(mode.get_parameter());
+                    let exp = mode.exponent();
 
-                    self.0 = match crate::ULinear16::from_real(val.0, exp) {{
-                        Some(val) => val.0,
-                        None => return Err(Error::ValueOutOfRange)
-                    }};
+                    self.0 = crate::{}::try_from_real_rounded(
+                        val.0, exp, saturation, rounding
+                    )?.0;
 
                     Ok(())
                 }}
                 Some(crate::commands::VOUT_MODE::Mode::Direct) => {{"##,
-                units)?;
+                units, units, units, units, units, linear)?;
 
             match coeff {
                 Some(coeff) => {
@@ -1554,7 +2586,9 @@ pub mod {} {{
                         m: {}, R: {}, b: {},
                     }};
 
-                    self.0 = crate::Direct::from_real(val.0, coefficients).0;
+                    self.0 = crate::Direct::from_real_rounded(
+                        val.0, coefficients, rounding
+                    ).0;
 
                     Ok(())"##, coeff.m, coeff.R, coeff.b)?;
                 }
@@ -1566,6 +2600,13 @@ pub mod {} {{
             }
 
             writeln!(&mut s, r##"                }}
+                Some(crate::commands::VOUT_MODE::Mode::IEEEHalf) => {{
+                    self.0 = crate::Half::try_from_real_rounded(
+                        val.0, saturation, rounding
+                    )?.0;
+
+                    Ok(())
+                }}
                 _ => {{
                     Err(Error::InvalidMode)
                 }}
@@ -1574,9 +2615,7 @@ pub mod {} {{
         }
 
         Format::Direct(c) => {
-            if bits > 16 {
-                bail!("{} has {} bits, but Direct can only have 16", cmd, bits);
-            }
+            let direct = direct(cmd, bits)?;
 
             writeln!(&mut s, r##"
         pub fn get(&self) -> Result<{}, Error> {{
@@ -1584,7 +2623,7 @@ pub mod {} {{
                 m: {}, R: {}, b: {},
             }};
 
-            Ok({}(crate::Direct(self.0, coefficients).to_real()))
+            Ok({}(crate::{}(self.0, coefficients).to_real()))
         }}
 
         pub fn set(&mut self, val: {}) -> Result<(), Error> {{
@@ -1592,16 +2631,35 @@ pub mod {} {{
                 m: {}, R: {}, b: {},
             }};
 
-            self.0 = crate::Direct::from_real(val.0, coefficients).0;
+            self.0 = crate::{}::from_real(val.0, coefficients).0;
+
+            Ok(())
+        }}
+
+        pub fn set_rounded(
+            &mut self,
+            val: {},
+            rounding: crate::Rounding
+        ) -> Result<(), Error> {{
+            let coefficients = Coefficients {{
+                m: {}, R: {}, b: {},
+            }};
+
+            self.0 = crate::{}::from_real_rounded(
+                val.0, coefficients, rounding
+            ).0;
 
             Ok(())
-        }}"##, units, c.m, c.R, c.b, units, units, c.m, c.R, c.b)?;
+        }}"##, units, c.m, c.R, c.b, units, direct, units, c.m, c.R, c.b,
+            direct, units, c.m, c.R, c.b, direct)?;
         }
 
         Format::RuntimeDirect => {
+            let direct = direct(cmd, bits)?;
+
             writeln!(&mut s, r##"
         pub fn get(&self, coefficients: &Coefficients) -> Result<{}, Error> {{
-            Ok({}(crate::Direct(self.0, *coefficients).to_real()))
+            Ok({}(crate::{}(self.0, *coefficients).to_real()))
         }}
 
         pub fn set(
@@ -1609,12 +2667,31 @@ pub mod {} {{
             coefficients: &Coefficients,
             val: {}
         ) -> Result<(), Error> {{
-            self.0 = crate::Direct::from_real(val.0, *coefficients).0;
+            self.0 = crate::{}::from_real(val.0, *coefficients).0;
+
+            Ok(())
+        }}
+
+        pub fn set_rounded(
+            &mut self,
+            coefficients: &Coefficients,
+            val: {},
+            rounding: crate::Rounding
+        ) -> Result<(), Error> {{
+            self.0 = crate::{}::from_real_rounded(
+                val.0, *coefficients, rounding
+            ).0;
 
             Ok(())
-        }}"##, units, units, units)?;
+        }}"##, units, units, direct, units, direct, units, direct)?;
         }
 
+        //
+        // A fixed point value has always been set by truncating it toward
+        // zero; set() continues to do so (as opposed to rounding to the
+        // nearest value, as for other formats), while set_rounded() allows
+        // any rounding to be specified.
+        //
         Format::FixedPoint(Factor(factor)) => {
             writeln!(&mut s, r##"
         pub fn get(&self) -> Result<{}, Error> {{
@@ -1622,9 +2699,17 @@ pub mod {} {{
         }}
 
         pub fn set(&mut self, val: {}) -> Result<(), Error> {{
-            self.0 = (val.0 * ({} as f32)) as u{};
+            self.set_rounded(val, crate::Rounding::TowardZero)
+        }}
+
+        pub fn set_rounded(
+            &mut self,
+            val: {},
+            rounding: crate::Rounding
+        ) -> Result<(), Error> {{
+            self.0 = rounding.round(val.0 * ({} as f32)) as u{};
             Ok(())
-        }}"##, units, units, factor, units, factor, bits)?;
+        }}"##, units, units, factor, units, units, factor, bits)?;
         }
 
         Format::SignedFixedPoint(Factor(factor)) => {
@@ -1634,9 +2719,17 @@ pub mod {} {{
         }}
 
         pub fn set(&mut self, val: {}) -> Result<(), Error> {{
-            self.0 = (val.0 * ({} as f32)) as u{};
+            self.set_rounded(val, crate::Rounding::TowardZero)
+        }}
+
+        pub fn set_rounded(
+            &mut self,
+            val: {},
+            rounding: crate::Rounding
+        ) -> Result<(), Error> {{
+            self.0 = rounding.round(val.0 * ({} as f32)) as i{} as u{};
             Ok(())
-        }}"##, units, units, bits, factor, units, factor, bits)?;
+        }}"##, units, units, bits, factor, units, units, factor, bits, bits)?;
         }
 
         Format::Raw => {
@@ -1656,6 +2749,67 @@ pub mod {} {{
         }
     }
 
+    let checked = match format {
+        Format::VOutMode(_) => Some(("mode: impl VOutMode, ", "mode")),
+        Format::RuntimeDirect => {
+            Some(("coefficients: &Coefficients, ", "coefficients"))
+        }
+        Format::Raw => None,
+        _ => Some(("", "")),
+    };
+
+    if let Some((param, arg)) = checked {
+        let sep = if arg.is_empty() { "" } else { ", " };
+
+        //
+        // VOUT_MODE is needed for both the encoding and the decoding.
+        //
+        let prelude = match format {
+            Format::VOutMode(_) => "let mode = mode.vout_mode();\n            ",
+            _ => "",
+        };
+
+        writeln!(&mut s, r##"
+        /// Sets the value, returning the quantization error:  the value
+        /// that was encoded less the value that was specified.
+        pub fn set_checked(
+            &mut self, {}val: {}
+        ) -> Result<{}, Error> {{
+            {}self.set({}{}val)?;
+            Ok({}(self.get({})?.0 - val.0))
+        }}"##, param, units, units, prelude, arg, sep, units, arg)?;
+    }
+
+    //
+    // The DIRECT and fixed point setters cast the real value straight to an
+    // integer of the datum's width (a signed one for the signed encodings,
+    // which is then reinterpreted as unsigned at the same width).  It is
+    // only float-to-integer casts that saturate at the bounds of the integer
+    // -- a cast to a narrower integer wraps -- so it is by virtue of there
+    // being no such narrowing that setting these values is already
+    // saturating.
+    //
+    let saturating = match format {
+        Format::Direct(_) | Format::FixedPoint(_) => Some(("", "")),
+        Format::SignedFixedPoint(_) => Some(("", "")),
+        Format::RuntimeDirect => {
+            Some(("coefficients: &Coefficients, ", "coefficients"))
+        }
+        _ => None,
+    };
+
+    if let Some((param, arg)) = saturating {
+        let sep = if arg.is_empty() { "" } else { ", " };
+
+        writeln!(&mut s, r##"
+        /// Sets the value, clamping it to the range that can be represented
+        pub fn set_saturating(
+            &mut self, {}val: {}
+        ) -> Result<(), Error> {{
+            self.set({}{}val)
+        }}"##, param, units, arg, sep)?;
+    }
+
     writeln!(&mut s, "    }}")?;
 
     writeln!(&mut s, r##"
@@ -1665,29 +2819,31 @@ pub mod {} {{
         writeln!(&mut s, r##"
         fn interpret(
             &self,
-            mode: impl Fn() -> VOutModeCommandData,
+            mode: impl crate::VOutMode,
             mut iter: impl FnMut(&dyn crate::Field, &dyn crate::Value)
         ) -> Result<(), Error> {{
-            let field = crate::WholeField("{} measurement", Bitwidth({}));
-            iter(&field, &Value(self.get(mode())?, self.0.into()));
+            let field = crate::WholeField(
+                "{} measurement", Bitwidth({}), {:#010x}
+            );
+            iter(&field, &Value(self.get(mode.vout_mode())?, self.0.into()));
             Ok(())
-        }}"##, cmd, bits)?;
+        }}"##, cmd, bits, id)?;
     } else if let Format::Raw = format {
         writeln!(&mut s, r##"
         fn interpret(
             &self,
-            _mode: impl Fn() -> VOutModeCommandData,
+            _mode: impl crate::VOutMode,
             mut iter: impl FnMut(&dyn crate::Field, &dyn crate::Value)
         ) -> Result<(), Error> {{
-            let field = crate::WholeField("{} value", Bitwidth({}));
+            let field = crate::WholeField("{} value", Bitwidth({}), {:#010x});
             iter(&field, &Value(self.get()?.into()));
             Ok(())
-        }}"##, cmd, bits)?;
+        }}"##, cmd, bits, id)?;
     } else if let Format::RuntimeDirect = format {
         writeln!(&mut s, r##"
         fn interpret(
             &self,
-            _mode: impl Fn() -> VOutModeCommandData,
+            _mode: impl crate::VOutMode,
             mut _iter: impl FnMut(&dyn crate::Field, &dyn crate::Value)
         ) -> Result<(), Error> {{
             Ok(())
@@ -1696,27 +2852,31 @@ pub mod {} {{
         writeln!(&mut s, r##"
         fn interpret(
             &self,
-            _mode: impl Fn() -> VOutModeCommandData,
+            _mode: impl crate::VOutMode,
             mut iter: impl FnMut(&dyn crate::Field, &dyn crate::Value)
         ) -> Result<(), Error> {{
-            let field = crate::WholeField("{} measurement", Bitwidth({}));
+            let field = crate::WholeField(
+                "{} measurement", Bitwidth({}), {:#010x}
+            );
             iter(&field, &Value(self.get()?, self.0.into()));
             Ok(())
-        }}"##, cmd, bits)?;
+        }}"##, cmd, bits, id)?;
     }
 
     if let Format::VOutMode(_) = format {
         writeln!(&mut s, r##"
         fn mutate(
             &mut self,
-            mode: impl Fn() -> VOutModeCommandData,
+            mode: impl crate::VOutMode,
             mut iter: impl FnMut(
                 &dyn crate::Field, &dyn crate::Value
             ) -> Option<Replacement>
         ) -> Result<(), Error> {{
-            let field = crate::WholeField("{} measurement", Bitwidth({}));
+            let field = crate::WholeField(
+                "{} measurement", Bitwidth({}), {:#010x}
+            );
 
-            let mode = mode();
+            let mode = mode.vout_mode();
             let val = Value(self.get(mode)?, self.0.into());
 
             if let Some(replacement) = iter(&field, &val) {{
@@ -1727,6 +2887,9 @@ pub mod {} {{
                     Replacement::Integer(i) => {{
                         self.set(mode, {}(i as f32))
                     }}
+                    Replacement::SaturatingFloat(f) => {{
+                        self.set_saturating(mode, {}(f))
+                    }}
                     _ => {{
                         Err(Error::InvalidReplacement)
                     }}
@@ -1734,17 +2897,17 @@ pub mod {} {{
             }} else {{
                 Ok(())
             }}
-        }}"##, cmd, bits, units, units)?;
+        }}"##, cmd, bits, id, units, units, units)?;
     } else if let Format::Raw = format {
         writeln!(&mut s, r##"
         fn mutate(
             &mut self,
-            _mode: impl Fn() -> VOutModeCommandData,
+            _mode: impl crate::VOutMode,
             mut iter: impl FnMut(
                 &dyn crate::Field, &dyn crate::Value
             ) -> Option<Replacement>
         ) -> Result<(), Error> {{
-            let field = crate::WholeField("{} value", Bitwidth({}));
+            let field = crate::WholeField("{} value", Bitwidth({}), {:#010x});
             let val = Value(self.get()?.into());
 
             if let Some(replacement) = iter(&field, &val) {{
@@ -1761,12 +2924,12 @@ pub mod {} {{
             }} else {{
                 Ok(())
             }}
-        }}"##, cmd, bits, bits)?;
+        }}"##, cmd, bits, id, bits)?;
     } else if let Format::RuntimeDirect = format {
         writeln!(&mut s, r##"
         fn mutate(
             &mut self,
-            _mode: impl Fn() -> VOutModeCommandData,
+            _mode: impl crate::VOutMode,
             mut _iter: impl FnMut(
                 &dyn crate::Field, &dyn crate::Value
             ) -> Option<Replacement>
@@ -1777,31 +2940,35 @@ pub mod {} {{
         writeln!(&mut s, r##"
         fn mutate(
             &mut self,
-            _mode: impl Fn() -> VOutModeCommandData,
+            _mode: impl crate::VOutMode,
             mut iter: impl FnMut(
                 &dyn crate::Field, &dyn crate::Value
             ) -> Option<Replacement>
         ) -> Result<(), Error> {{
-            let field = crate::WholeField("{} measurement", Bitwidth({}));
+            let field = crate::WholeField(
+                "{} measurement", Bitwidth({}), {:#010x}
+            );
             let val = Value(self.get()?, self.0.into());
 
             if let Some(replacement) = iter(&field, &val) {{
-                if let Replacement::Float(f) = replacement {{
-                    self.set({}(f))
-                }} else {{
-                    Err(Error::InvalidReplacement)
+                match replacement {{
+                    Replacement::Float(f) => self.set({}(f)),
+                    Replacement::SaturatingFloat(f) => {{
+                        self.set_saturating({}(f))
+                    }}
+                    _ => Err(Error::InvalidReplacement),
                 }}
             }} else {{
                 Ok(())
             }}
-        }}"##, cmd, bits, units)?;
+        }}"##, cmd, bits, id, units, units)?;
     }
 
     writeln!(&mut s, r##"
         fn fields(
             mut iter: impl FnMut(&dyn crate::Field) 
         ) -> Result<(), Error> {{
-            iter(&crate::WholeField("{} measurement", Bitwidth({})));
+            iter(&crate::WholeField("{} measurement", Bitwidth({}), {:#010x}));
 
             Ok(())
         }}
@@ -1815,7 +2982,7 @@ pub mod {} {{
 
         fn raw(&self) -> (u32, Bitwidth) {{
             (self.0 as u32, Bitwidth({}))
-        }}"##, cmd, bits, bits)?;
+        }}"##, cmd, bits, id, bits)?;
 
     if !auxiliary {
         writeln!(&mut s, r##"
@@ -1842,7 +3009,8 @@ pub mod {} {{
 
 #[rustfmt::skip::macros(writeln)]
 fn output_numerics(
-    cmds: &Vec<CommandNumericFormat>,
+    scope: &str,
+    cmds: &[CommandNumericFormat],
     sizes: &HashMap<String, Option<usize>>,
     units: &mut HashSet<Units>,
     coeff: Option<Coefficients>,
@@ -1862,7 +3030,8 @@ fn output_numerics(
 
         units.insert(cmd.2);
         out.push_str(&output_command_numeric(
-            OutputCommand::PMBus(&cmd.0),
+            scope,
+            OutputCommand::PMBus(&cmd.0, false),
             &cmd.1,
             &cmd.2,
             bytes,
@@ -1873,27 +3042,166 @@ fn output_numerics(
     Ok(out)
 }
 
-fn output_aux_numerics(
-    auxs: &Vec<AuxiliaryNumericFormat>,
-    sizes: &HashMap<String, Option<usize>>,
+#[rustfmt::skip::macros(writeln)]
+fn output_coefficient_sets(
+    sets: &CoefficientSets,
+    numerics: &[CommandNumericFormat],
+    structured: &HashMap<String, Fields>,
     units: &mut HashSet<Units>,
-    coeff: Option<Coefficients>,
 ) -> Result<String> {
-    let mut out = String::new();
+    let mut s = String::new();
+    let config = &sets.configuration;
+
+    let fields = match structured.get(config) {
+        Some(fields) => fields,
+        None => bail!(
+            "coefficients selected by {}, which lacks a \
+            structured definition",
+            config
+        ),
+    };
 
-    for aux in auxs {
-        let bytes = match sizes.get(&aux.0) {
-            Some(Some(size)) => *size,
-            Some(None) => {
-                bail!("auxiliary {} does not allow a value", aux.0);
-            }
-            None => {
-                bail!("auxiliary {} does not exist", aux.0);
+    //
+    // Each condition is a test of a sentinel field of the configuration.
+    //
+    let mut conditions = vec![];
+
+    for set in &sets.sets {
+        let mut tests = vec![];
+
+        for (field, value) in &set.when {
+            let valid = match fields.0.get(field).map(|f| &f.values) {
+                Some(Values::Sentinels(values)) => values.contains_key(value),
+                _ => bail!(
+                    "coefficients selected by {}.{}, which is not \
+                    a sentinel field",
+                    config,
+                    field
+                ),
+            };
+
+            if !valid {
+                bail!(
+                    "coefficients selected by unknown value {} of {}.{}",
+                    value,
+                    config,
+                    field
+                );
+            }
+
+            let method = field.from_case(Case::Camel).to_case(Case::Snake);
+
+            tests.push(format!(
+                "config.get_{}() == Some({}::{}::{})",
+                method, config, field, value
+            ));
+        }
+
+        conditions.push(tests.join("\n                && "));
+    }
+
+    for cmd in numerics {
+        if !matches!(cmd.1, Format::RuntimeDirect) {
+            continue;
+        }
+
+        let applicable: Vec<_> = sets
+            .sets
+            .iter()
+            .zip(conditions.iter())
+            .filter(|(set, _)| set.units == cmd.2)
+            .collect();
+
+        if applicable.is_empty() {
+            continue;
+        }
+
+        let scaled = applicable[0].0.scaled;
+
+        if applicable.iter().any(|(set, _)| set.scaled != scaled) {
+            bail!(
+                "coefficient sets for {:?} must all be scaled (or not)",
+                cmd.2
+            );
+        }
+
+        let (note, param, desc, scale) = if scaled {
+            units.insert(Units::Milliohms);
+            (
+                " (with a slope\n    /// per milliohm of sense resistance)",
+                ",\n        sense: crate::units::Milliohms",
+                " and\n    /// the sense resistance",
+                " / sense.0",
+            )
+        } else {
+            ("", "", "", "")
+        };
+
+        writeln!(&mut s, r##"
+impl {}::CommandData {{
+    /// Returns the DIRECT coefficients that the datasheet gives for `{}`,
+    /// as selected by the contents of `{}`{}
+    pub fn coefficients(
+        config: &{}::CommandData,
+    ) -> Option<crate::Coefficients> {{"##,
+            cmd.0, cmd.0, config, note, config)?;
+
+        for (set, condition) in &applicable {
+            let c = &set.coefficients;
+
+            writeln!(&mut s, r##"
+        if {} {{
+            return Some(crate::Coefficients {{ m: {}, b: {}, R: {} }});
+        }}"##, condition, c.m, c.b, c.R)?;
+        }
+
+        writeln!(&mut s, r##"
+        None
+    }}
+
+    /// Returns the value, as converted with the coefficients that the
+    /// datasheet gives for the contents of `{}`{}
+    pub fn get_configured(
+        &self,
+        config: &{}::CommandData{},
+    ) -> Result<crate::units::{:?}, Error> {{
+        let coefficients = match Self::coefficients(config) {{
+            Some(coefficients) => coefficients,
+            None => return Err(Error::MissingCoefficients),
+        }};
+
+        let val = self.get(&coefficients)?;
+        Ok(crate::units::{:?}(val.0{}))
+    }}
+}}"##, config, desc, config, param, cmd.2, cmd.2, scale)?;
+    }
+
+    Ok(s)
+}
+
+fn output_aux_numerics(
+    scope: &str,
+    auxs: &Vec<AuxiliaryNumericFormat>,
+    sizes: &HashMap<String, Option<usize>>,
+    units: &mut HashSet<Units>,
+    coeff: Option<Coefficients>,
+) -> Result<String> {
+    let mut out = String::new();
+
+    for aux in auxs {
+        let bytes = match sizes.get(&aux.0) {
+            Some(Some(size)) => *size,
+            Some(None) => {
+                bail!("auxiliary {} does not allow a value", aux.0);
+            }
+            None => {
+                bail!("auxiliary {} does not exist", aux.0);
             }
         };
 
         units.insert(aux.2);
         out.push_str(&output_command_numeric(
+            scope,
             OutputCommand::Auxiliary(&aux.0),
             &aux.1,
             &aux.2,
@@ -1905,15 +3213,272 @@ fn output_aux_numerics(
     Ok(out)
 }
 
+#[rustfmt::skip::macros(writeln)]
+fn output_revisions(
+    device: &str,
+    all: &[Command],
+    revisions: &Option<Vec<Revision>>,
+    sizes: &HashMap<String, Option<usize>>,
+    units: &mut HashSet<Units>,
+) -> Result<String> {
+    let mut s = String::new();
+    let mut lookup = String::new();
+    let empty = vec![];
+
+    for (i, rev) in revisions.as_ref().unwrap_or(&empty).iter().enumerate() {
+        if let Some(until) = rev.until {
+            if until <= rev.since {
+                bail!("revision {:#x} of {} is empty", rev.since, device);
+            }
+        }
+
+        let scope = format!("{}@{:#x}", device, rev.since);
+
+        writeln!(&mut s, r##"
+/// Definitions specific to revisions {:#x} through {} of the device
+#[allow(non_snake_case)]
+pub mod revision{} {{
+    use super::CommandCode;
+    use crate::CommandData;
+    use crate::Error;
+    use crate::Field;
+    use crate::VOutModeCommandData;
+    use crate::Value;"##, rev.since, match rev.until {
+            Some(until) => format!("{:#x} (exclusive)", until),
+            None => "the latest".to_string(),
+        }, i)?;
+
+        let cond = match rev.until {
+            Some(until) => {
+                format!("({:#x}..{:#x}).contains(&rev)", rev.since, until)
+            }
+            None => format!("rev >= {:#x}", rev.since),
+        };
+
+        writeln!(&mut lookup, "    if {} {{\n        match code {{", cond)?;
+
+        let mut cmds: Vec<_> = rev.structured.iter().collect();
+        cmds.sort_by_key(|(cmd, _)| cmd.to_string());
+
+        for (cmd, fields) in cmds {
+            let code = match all.iter().find(|c| c.1 == *cmd) {
+                Some(c) => c.0,
+                None => bail!(
+                    "revision {:#x} of {} specifies unknown \
+                    command {}",
+                    rev.since,
+                    device,
+                    cmd
+                ),
+            };
+
+            let (bits, bytes, block) = validate(cmd, fields, sizes, units)?;
+            s.push_str(&output_command_data(
+                &scope, cmd, fields, bits, bytes, block,
+            )?);
+
+            writeln!(&mut s, r##"
+    pub fn interpret_{}(
+        payload: &[u8],
+        mode: &dyn Fn() -> VOutModeCommandData,
+        iter: &mut dyn FnMut(&dyn Field, &dyn Value),
+    ) -> Result<(), Error> {{
+        use {}::CommandData;
+
+        match CommandData::from_slice(payload) {{
+            Some(data) => data.interpret(mode, iter),
+            None if payload.len() < CommandData::len() => {{
+                Err(Error::ShortData)
+            }}
+            None => Err(Error::InvalidLength),
+        }}
+    }}"##, cmd, cmd)?;
+
+            writeln!(&mut lookup, "            {:#04x} => \
+                return Some(revision{}::interpret_{}),", code, i, cmd)?;
+        }
+
+        writeln!(&mut s, "}}")?;
+        writeln!(&mut lookup, "            _ => {{}}\n        }}\n    }}\n")?;
+    }
+
+    writeln!(&mut s, r##"
+/// Returns the function that interprets data for the given command code on
+/// the given revision of the device, or `None` if the code is invalid
+#[allow(unused_variables)]
+pub fn revision_interpreter(
+    rev: u32,
+    code: u8,
+) -> Option<crate::Interpreter> {{
+{}    INTERPRETERS[code as usize]
+}}"##, lookup)?;
+
+    Ok(s)
+}
+
+#[rustfmt::skip::macros(writeln)]
+fn output_reexport(cmd: &str) -> Result<String> {
+    let mut s = String::new();
+
+    writeln!(&mut s, r##"
+/// Types and structures associated with the `{}` PMBus command, which
+/// are as they are for the common definition
+pub use super::{};"##, cmd, cmd)?;
+
+    Ok(s)
+}
+
+#[rustfmt::skip::macros(writeln)]
+fn output_base_reexport(base: &str, cmd: &str) -> Result<String> {
+    let mut s = String::new();
+
+    writeln!(&mut s, r##"
+/// Types and structures associated with the `{}` PMBus command, which
+/// are as they are for the `{}` device on which this device is based
+pub use crate::commands::{}::{};"##, cmd, base, base, cmd)?;
+
+    Ok(s)
+}
+
+//
+// Merges the definitions of a base device into those of a device, returning
+// the names of the commands whose payload definitions are inherited as they
+// are (and can therefore be re-exported from the base rather than emitted
+// again).  A command that the device redefines (or whose code the device
+// uses for a different command) is not inherited.  Numerics that depend on
+// device-specific coefficients or accuracy are inherited, but must be
+// emitted anew, as are any auxiliary structures (which are inherited only
+// if the device defines none of its own).
+//
+fn inherit(
+    dcmds: &mut Commands,
+    base: Commands,
+    coeff: bool,
+) -> HashSet<String> {
+    let mut inherited = HashSet::new();
+    let codes: HashSet<u8> = dcmds.all.iter().map(|cmd| cmd.0).collect();
+
+    let overridden: HashSet<String> = base
+        .all
+        .iter()
+        .filter(|cmd| codes.contains(&cmd.0))
+        .map(|cmd| cmd.1.clone())
+        .collect();
+
+    let accurate: HashSet<&String> = match dcmds.accuracy {
+        Some(ref accuracy) => accuracy.iter().map(|a| &a.0).collect(),
+        None => HashSet::new(),
+    };
+
+    let mut numerics = vec![];
+
+    for numeric in base.numerics {
+        if overridden.contains(&numeric.0)
+            || dcmds.numerics.iter().any(|n| n.0 == numeric.0)
+        {
+            continue;
+        }
+
+        if !coeff
+            && !accurate.contains(&numeric.0)
+            && !matches!(numeric.1, Format::RuntimeDirect)
+        {
+            inherited.insert(numeric.0.clone());
+        }
+
+        numerics.push(numeric);
+    }
+
+    dcmds.numerics.extend(numerics);
+
+    for (cmd, fields) in base.structured {
+        if overridden.contains(&cmd) || dcmds.structured.contains_key(&cmd) {
+            continue;
+        }
+
+        inherited.insert(cmd.clone());
+        dcmds.structured.insert(cmd, fields);
+    }
+
+    for cmd in base.all {
+        if !codes.contains(&cmd.0) {
+            dcmds.all.push(cmd);
+        }
+    }
+
+    if dcmds.auxiliaries.is_none() {
+        dcmds.auxiliaries = base.auxiliaries;
+    }
+
+    inherited
+}
+
+#[rustfmt::skip::macros(writeln)]
+fn output_accuracy(
+    accuracy: &Vec<CommandAccuracy>,
+    numerics: &[&Vec<CommandNumericFormat>],
+    units: &mut HashSet<Units>,
+) -> Result<String> {
+    let mut s = String::new();
+
+    for CommandAccuracy(cmd, percent) in accuracy {
+        let numeric =
+            numerics.iter().flat_map(|n| n.iter()).find(|n| n.0 == *cmd);
+
+        let (format, u) = match numeric {
+            Some(CommandNumericFormat(_, format, u)) => (format, u),
+            None => {
+                bail!("accuracy specified for {}, which isn't numeric", cmd);
+            }
+        };
+
+        if percent.is_nan() || *percent < 0.0 {
+            bail!("accuracy for {} must be non-negative", cmd);
+        }
+
+        let (param, arg) = match format {
+            Format::VOutMode(_) => (", mode: VOutModeCommandData", "mode"),
+            Format::RuntimeDirect => {
+                (", coefficients: &crate::Coefficients", "coefficients")
+            }
+            Format::Raw => {
+                bail!("accuracy specified for {}, which is raw", cmd);
+            }
+            _ => ("", ""),
+        };
+
+        units.insert(Units::Percent);
+
+        writeln!(&mut s, r##"
+impl {}::CommandData {{
+    /// Returns the accuracy of the `{}` measurement, as a percentage of
+    /// the measured value
+    pub const fn accuracy() -> crate::units::Percent {{
+        crate::units::Percent({:?})
+    }}
+
+    /// Returns the measurement along with its accuracy
+    pub fn reading(
+        &self{}
+    ) -> Result<crate::Reading<crate::units::{:?}>, Error> {{
+        Ok(crate::Reading::new(self.get({})?, Some(Self::accuracy())))
+    }}
+}}"##, cmd, cmd, percent, param, u, arg)?;
+    }
+
+    Ok(s)
+}
+
 #[rustfmt::skip::macros(writeln)]
 #[rustfmt::skip::macros(write)]
-fn output_devices(devices: &HashMap<String, Device>) -> Result<String> {
+fn output_devices(devices: &BTreeMap<String, Device>) -> Result<String> {
     let mut s = String::new();
 
     let name = |str: &str| str.to_case(Case::UpperCamel);
 
     writeln!(&mut s, r##"
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Device {{
     Common,"##)?;
 
@@ -1976,32 +3541,157 @@ impl Device {{
         &self,
         code: u8,
         payload: &[u8],
-        mode: impl Fn() -> VOutModeCommandData,
-        iter: impl FnMut(&dyn Field, &dyn Value)
+        mode: impl crate::VOutMode,
+        mut iter: impl FnMut(&dyn Field, &dyn Value)
     ) -> Result<(), Error> {{
+        match self.interpreter(code) {{
+            Some(interpret) => {{
+                interpret(payload, &|| mode.vout_mode(), &mut iter)
+            }}
+            None => Err(Error::InvalidCode),
+        }}
+    }}
+
+    /// For this device, returns the function that interprets data for the
+    /// given command code, or `None` if the code is invalid.  This allows
+    /// for consumers that are interpreting many payloads for the same code
+    /// to look up the interpreter only once.
+    pub fn interpreter(&self, code: u8) -> Option<crate::Interpreter> {{
+        let table = match self {{
+            Device::Common => &INTERPRETERS,"##)?;
+
+    for dev in devices {
+        writeln!(&mut s, "            Device::{} => &{}::INTERPRETERS,",
+            name(&dev.0), dev.0)?;
+    }
+
+    writeln!(&mut s, r##"        }};
+
+        table[code as usize]
+    }}
+
+    /// As [`Device::interpreter`], but for the specified revision of the
+    /// device, as numbered by the device (and generally derived from
+    /// IC_DEVICE_REV).  Definitions that are specific to the revision
+    /// take precedence over those of the device.
+    pub fn interpreter_rev(
+        &self,
+        rev: u32,
+        code: u8
+    ) -> Option<crate::Interpreter> {{
         match self {{
-            Device::Common => match CommandCode::from_u8(code) {{
-                Some(cmd) => {{
-                    cmd.interpret(payload, mode, iter)
-                }}
-                None => {{
-                    Err(Error::InvalidCode)
-                }}
-            }},"##)?;
+            Device::Common => INTERPRETERS[code as usize],"##)?;
 
     for dev in devices {
-        writeln!(&mut s, r##"
-            Device::{} => match {}::CommandCode::from_u8(code) {{
-                Some(cmd) => {{
-                    cmd.interpret(payload, mode, iter)
-                }}
-                None => {{
-                    Err(Error::InvalidCode)
-                }}
-            }},"##, name(&dev.0), dev.0)?;
+        writeln!(&mut s,
+            "            Device::{} => {}::revision_interpreter(rev, code),",
+            name(&dev.0), dev.0)?;
     }
 
-    writeln!(&mut s, "        }}\n    }}\n")?;
+    writeln!(&mut s, r##"        }}
+    }}
+
+    /// As [`Device::interpret`], but for the specified revision of the
+    /// device; see [`Device::interpreter_rev`].
+    pub fn interpret_rev(
+        &self,
+        rev: u32,
+        code: u8,
+        payload: &[u8],
+        mode: impl crate::VOutMode,
+        mut iter: impl FnMut(&dyn Field, &dyn Value)
+    ) -> Result<(), Error> {{
+        match self.interpreter_rev(rev, code) {{
+            Some(interpret) => {{
+                interpret(payload, &|| mode.vout_mode(), &mut iter)
+            }}
+            None => Err(Error::InvalidCode),
+        }}
+    }}
+
+    /// Returns true if the given command code is defined for this device
+    /// by the specified revision of the PMBus specification (or is not
+    /// known to have been introduced by a particular revision).  The
+    /// revision is generally as reported by PMBUS_REVISION; see
+    /// [`crate::SpecRevision::from_pmbus_revision`] and
+    /// [`crate::SpecRevision::admits`].
+    pub fn supports(&self, code: u8, rev: crate::SpecRevision) -> bool {{
+        let mut supported = false;
+
+        self.command(code, |cmd| {{
+            supported = match cmd.introduced() {{
+                Some(introduced) => rev.admits(introduced),
+                None => true,
+            }};
+        }});
+
+        supported
+    }}
+
+    /// As [`Device::interpret`], but fails with
+    /// [`Error::UnsupportedRevision`] if the command is newer than the
+    /// specified revision of the PMBus specification; see
+    /// [`Device::supports`].
+    pub fn interpret_spec(
+        &self,
+        rev: crate::SpecRevision,
+        code: u8,
+        payload: &[u8],
+        mode: impl crate::VOutMode,
+        iter: impl FnMut(&dyn Field, &dyn Value)
+    ) -> Result<(), Error> {{
+        if self.interpreter(code).is_none() {{
+            return Err(Error::InvalidCode);
+        }}
+
+        if !self.supports(code, rev) {{
+            return Err(Error::UnsupportedRevision);
+        }}
+
+        self.interpret(code, payload, mode, iter)
+    }}
+
+    /// For this device, interprets a batch of command codes and their
+    /// payloads, calling the specified function with each code and each
+    /// of its fields and values.  VOUT_MODE is determined at most once for
+    /// the entire batch:  if the batch itself contains VOUT_MODE, it is
+    /// used; otherwise, `mode` is called only if (and when) first needed.
+    /// Interpretation stops at the first error, which is returned.
+    pub fn interpret_all(
+        &self,
+        batch: &[(u8, &[u8])],
+        mode: impl crate::VOutMode,
+        mut iter: impl FnMut(u8, &dyn Field, &dyn Value)
+    ) -> Result<(), Error> {{
+        let vout_mode = CommandCode::VOUT_MODE as u8;
+
+        let cached = core::cell::Cell::new(
+            batch.iter().find(|(code, _)| *code == vout_mode).and_then(
+                |(_, payload)| VOutModeCommandData::from_slice(payload)
+            )
+        );
+
+        let mode = || match cached.get() {{
+            Some(m) => m,
+            None => {{
+                let m = mode.vout_mode();
+                cached.set(Some(m));
+                m
+            }}
+        }};
+
+        for (code, payload) in batch {{
+            match self.interpreter(*code) {{
+                Some(interpret) => {{
+                    interpret(payload, &mode, &mut |f, v| iter(*code, f, v))?
+                }}
+                None => return Err(Error::InvalidCode),
+            }}
+        }}
+
+        Ok(())
+    }}
+"##)?;
 
     writeln!(&mut s, r##"
     /// For this device and the given command code, iterates over the fields
@@ -2018,7 +3708,7 @@ impl Device {{
         &self,
         code: u8,
         payload: &mut [u8],
-        mode: impl Fn() -> VOutModeCommandData,
+        mode: impl crate::VOutMode,
         iter: impl FnMut(&dyn Field, &dyn Value) -> Option<Replacement>
     ) -> Result<(), Error> {{
         match self {{
@@ -2136,6 +3826,55 @@ impl Device {{
             }},"##, name(&dev.0), dev.0)?;
     }
 
+    writeln!(&mut s, r##"        }}
+    }}
+
+    /// For this device, returns the code of the command with the specified
+    /// name, which may be either the command's canonical name (as returned
+    /// by [`Command::name`]) or one of its aliases.
+    pub fn code(&self, name: &str) -> Option<u8> {{
+        match self {{
+            Device::Common => CommandCode::from_name(name)
+                .map(|c| c as u8),"##)?;
+
+    for dev in devices {
+        writeln!(&mut s,
+            "            Device::{} => {}::CommandCode::from_name(name)\
+            .map(|c| c as u8),", name(dev.0), dev.0)?;
+    }
+
+    writeln!(&mut s, r##"        }}
+    }}
+
+    /// For this device, returns the format of the data payload of the
+    /// command with the specified code, or `None` if the code is invalid or
+    /// the command has no payload.
+    pub fn format(&self, code: u8) -> Option<crate::Format> {{
+        match self {{
+            Device::Common => CommandCode::from_u8(code)?.format(),"##)?;
+
+    for dev in devices {
+        writeln!(&mut s,
+            "            Device::{} => {}::CommandCode::from_u8(code)?\
+            .format(),", name(dev.0), dev.0)?;
+    }
+
+    writeln!(&mut s, r##"        }}
+    }}
+
+    /// For this device, returns the units of the value of the command with
+    /// the specified code, or `None` if the code is invalid or the command
+    /// does not have a value in units.
+    pub fn units(&self, code: u8) -> Option<crate::units::Units> {{
+        match self {{
+            Device::Common => CommandCode::from_u8(code)?.units(),"##)?;
+
+    for dev in devices {
+        writeln!(&mut s,
+            "            Device::{} => {}::CommandCode::from_u8(code)?\
+            .units(),", name(dev.0), dev.0)?;
+    }
+
     writeln!(&mut s, "        }}\n    }}\n}}")?;
 
     writeln!(&mut s, r##"
@@ -2149,6 +3888,169 @@ pub fn devices(mut dev: impl FnMut(Device)) {{"##)?;
     Ok(s)
 }
 
+#[rustfmt::skip::macros(writeln)]
+fn output_extended(
+    scope: &str,
+    ext: Extended,
+    units: &mut HashSet<Units>,
+    coeff: Option<Coefficients>,
+) -> Result<String> {
+    let mut s = String::new();
+
+    let cmds = Commands {
+        all: ext.all,
+        numerics: ext.numerics,
+        structured: ext.structured,
+        synonyms: None,
+        aliases: None,
+        coefficients: None,
+        auxiliaries: None,
+        extended: None,
+        accuracy: None,
+        timing: None,
+        introduced: None,
+        configuration: None,
+        revisions: None,
+    };
+
+    let sizes = reg_sizes(&cmds.all)?;
+
+    writeln!(&mut s, r##"
+pub mod extended {{
+    use crate::Bitpos;
+    use crate::CommandData;
+    use crate::Field;
+    use crate::Operation;
+    use crate::Replacement;
+    use crate::VOutModeCommandData;
+    use crate::Value;
+
+    /// The command code that prefixes each extended command on the wire
+    pub const PREFIX: u8 = 0xfe;"##)?;
+
+    s.push_str(&output_commands(&cmds, None)?);
+
+    for (cmd, fields) in &cmds.structured {
+        let (bits, bytes, block) = validate(cmd, fields, &sizes, units)?;
+        s.push_str(&output_command_data(
+            scope, cmd, fields, bits, bytes, block,
+        )?);
+    }
+
+    s.push_str(&output_numerics(
+        scope,
+        &cmds.numerics,
+        &sizes,
+        units,
+        coeff,
+    )?);
+
+    writeln!(&mut s, "}}")?;
+
+    Ok(s)
+}
+
+#[rustfmt::skip::macros(writeln)]
+fn output_devices_extended(
+    devices: &BTreeMap<String, Device>,
+    extended: &HashSet<String>,
+) -> Result<String> {
+    let mut s = String::new();
+
+    let name = |str: &str| str.to_case(Case::UpperCamel);
+
+    writeln!(&mut s, r##"
+impl Device {{
+    /// For this device and the given extended command code (that is, the
+    /// code that follows the MFR_SPECIFIC_COMMAND_EXT prefix), iterates over
+    /// the fields in the structured register (if any), calling the specified
+    /// function for each field and its value.  This is the extended analog
+    /// to [`Device::interpret`].
+    #[allow(unused_variables)]
+    pub fn interpret_extended(
+        &self,
+        code: u8,
+        payload: &[u8],
+        mode: impl crate::VOutMode,
+        iter: impl FnMut(&dyn Field, &dyn Value)
+    ) -> Result<(), Error> {{
+        match self {{"##)?;
+
+    for dev in devices.keys().filter(|d| extended.contains(*d)) {
+        writeln!(&mut s, r##"
+            Device::{} => match {}::extended::CommandCode::from_u8(code) {{
+                Some(cmd) => {{
+                    cmd.interpret(payload, mode, iter)
+                }}
+                None => {{
+                    Err(Error::InvalidCode)
+                }}
+            }},"##, name(dev), dev)?;
+    }
+
+    writeln!(&mut s, r##"
+            _ => Err(Error::InvalidCode),
+        }}
+    }}
+
+    /// For this device and the given extended command code, iterates over
+    /// the fields in the structured register (if any) for the purpose of
+    /// mutating some individual field.  This is the extended analog to
+    /// [`Device::mutate`].
+    #[allow(unused_variables)]
+    pub fn mutate_extended(
+        &self,
+        code: u8,
+        payload: &mut [u8],
+        mode: impl crate::VOutMode,
+        iter: impl FnMut(&dyn Field, &dyn Value) -> Option<Replacement>
+    ) -> Result<(), Error> {{
+        match self {{"##)?;
+
+    for dev in devices.keys().filter(|d| extended.contains(*d)) {
+        writeln!(&mut s, r##"
+            Device::{} => match {}::extended::CommandCode::from_u8(code) {{
+                Some(cmd) => {{
+                    cmd.mutate(payload, mode, iter)
+                }}
+                None => {{
+                    Err(Error::InvalidCode)
+                }}
+            }},"##, name(dev), dev)?;
+    }
+
+    writeln!(&mut s, r##"
+            _ => Err(Error::InvalidCode),
+        }}
+    }}
+
+    #[allow(unused_variables, unused_mut)]
+    pub fn command_extended(
+        &self,
+        code: u8,
+        mut cb: impl FnMut(&dyn Command)
+    ) {{
+        match self {{"##)?;
+
+    for dev in devices.keys().filter(|d| extended.contains(*d)) {
+        writeln!(&mut s, r##"
+            Device::{} => match {}::extended::CommandCode::from_u8(code) {{
+                Some(cmd) => {{
+                    cb(&cmd);
+                }}
+                None => {{}}
+            }},"##, name(dev), dev)?;
+    }
+
+    writeln!(&mut s, r##"
+            _ => {{}}
+        }}
+    }}
+}}"##)?;
+
+    Ok(s)
+}
+
 #[rustfmt::skip::macros(writeln)]
 fn output_device(device: &str) -> Result<String> {
     let mut s = String::new();
@@ -2170,19 +4072,423 @@ pub mod {} {{
     Ok(s)
 }
 
+#[rustfmt::skip::macros(writeln)]
+fn output_facade(
+    device: &str,
+    info: &Device,
+    dcmds: &Commands,
+) -> Result<String> {
+    let mut s = String::new();
+    let name = device.to_case(Case::UpperCamel);
+
+    //
+    // Our telemetry consists of the numeric READ_ commands, in code order.
+    // The common READ_ commands (e.g., READ_DUTY_CYCLE, READ_KWH_IN) are
+    // optional, and are telemetry only if the device defines them itself.
+    //
+    let mut telemetry = BTreeMap::new();
+
+    for numeric in &dcmds.numerics {
+        if !numeric.0.starts_with("READ_") {
+            continue;
+        }
+
+        if let Some(cmd) = dcmds.all.iter().find(|cmd| cmd.1 == numeric.0) {
+            telemetry.insert(cmd.0, &cmd.1);
+        }
+    }
+
+    writeln!(&mut s, r##"
+/// The {} {} as a type, for use with code that is generic over devices
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct {};
+
+impl crate::PmbusDevice for {} {{
+    type CommandCode = CommandCode;
+    const DEVICE: super::Device = super::Device::{};
+    const NAME: &'static str = "{}";
+    const DESC: &'static str = "{}";
+    const TELEMETRY: &'static [CommandCode] = &["##,
+        info.manufacturer, info.part, name, name, name, device,
+        info.description)?;
+
+    for cmd in telemetry.values() {
+        writeln!(&mut s, "        CommandCode::{},", cmd)?;
+    }
+
+    writeln!(&mut s, r##"    ];
+
+    fn command(code: u8) -> Option<CommandCode> {{
+        CommandCode::from_u8(code)
+    }}
+
+    fn code(command: CommandCode) -> u8 {{
+        command as u8
+    }}
+
+    fn interpret(
+        code: CommandCode,
+        payload: &[u8],
+        mode: impl crate::VOutMode,
+        iter: impl FnMut(&dyn Field, &dyn Value)
+    ) -> Result<(), Error> {{
+        code.interpret(payload, mode, iter)
+    }}
+}}"##)?;
+
+    Ok(s)
+}
+
 #[rustfmt::skip::macros(writeln)]
 fn output_units(units: &HashSet<Units>) -> Result<String> {
     let mut s = String::new();
 
-    for u in units {
+    //
+    // Our units are emitted in a stable order so that an unchanged set of
+    // units results in an unchanged file.
+    //
+    let mut units: Vec<_> = units.iter().collect();
+    units.sort_by_key(|u| format!("{:?}", u));
+
+    for u in &units {
         writeln!(&mut s, r##"
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub struct {:?}(pub f32);"##, u)?;
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct {:?}(pub f32);
+
+impl From<f32> for {:?} {{
+    fn from(val: f32) -> Self {{
+        Self(val)
+    }}
+}}
+
+impl From<{:?}> for f32 {{
+    fn from(val: {:?}) -> Self {{
+        val.0
+    }}
+}}"##, u, u, u, u)?;
+
+        if let Some((quantity, unit)) = u.uom() {
+            writeln!(&mut s, r##"
+#[cfg(feature = "uom")]
+impl From<{:?}> for uom::si::f32::{} {{
+    fn from(val: {:?}) -> Self {{
+        Self::new::<uom::si::{}>(val.0)
+    }}
+}}
+
+#[cfg(feature = "uom")]
+impl From<uom::si::f32::{}> for {:?} {{
+    fn from(val: uom::si::f32::{}) -> Self {{
+        Self(val.get::<uom::si::{}>())
+    }}
+}}"##, u, quantity, u, unit, quantity, u, quantity, unit)?;
+        }
+
+        let conversions = u.conversions();
+
+        if !conversions.is_empty() {
+            writeln!(&mut s, "\nimpl {:?} {{", u)?;
+
+            for (i, (name, desc, scale, offset)) in
+                conversions.iter().enumerate()
+            {
+                if i != 0 {
+                    writeln!(&mut s)?;
+                }
+
+                //
+                // To keep our conversions exact where they can be, we scale
+                // down by dividing by the (integral) reciprocal rather than by
+                // multiplying by the scale.
+                //
+                let (mul, div, k) = if *scale < 1.0 {
+                    ("/", "*", scale.recip().round())
+                } else {
+                    ("*", "/", *scale)
+                };
+
+                let (mut to, mut from) = if k == 1.0 {
+                    ("self.0".to_string(), "val".to_string())
+                } else {
+                    (
+                        format!("self.0 {} {:?}", mul, k),
+                        format!("val {} {:?}", div, k),
+                    )
+                };
+
+                if *offset != 0.0 {
+                    to = format!("{} + {:?}", to, offset);
+                    from = if k == 1.0 {
+                        format!("val - {:?}", offset)
+                    } else {
+                        format!("(val - {:?}) {} {:?}", offset, div, k)
+                    };
+                }
+
+                writeln!(&mut s, r##"    /// Returns the value in {}
+    pub fn as_{}(&self) -> f32 {{
+        {}
+    }}
+
+    /// Returns the value that corresponds to the given {}
+    pub fn from_{}(val: f32) -> Self {{
+        Self({})
+    }}"##, desc, name, to, desc, name, from)?;
+            }
+
+            writeln!(&mut s, "}}")?;
+        }
+
+        //
+        // Quantities of like units can be added and subtracted, scaled, and
+        // divided to yield their (unitless) ratio.
+        //
+        writeln!(&mut s, r##"
+impl core::ops::Add for {0:?} {{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {{
+        Self(self.0 + rhs.0)
+    }}
+}}
+
+impl core::ops::Sub for {0:?} {{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {{
+        Self(self.0 - rhs.0)
+    }}
+}}
+
+impl core::ops::Neg for {0:?} {{
+    type Output = Self;
+
+    fn neg(self) -> Self {{
+        Self(-self.0)
+    }}
+}}
+
+impl core::ops::Mul<f32> for {0:?} {{
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {{
+        Self(self.0 * rhs)
+    }}
+}}
+
+impl core::ops::Mul<{0:?}> for f32 {{
+    type Output = {0:?};
+
+    fn mul(self, rhs: {0:?}) -> {0:?} {{
+        {0:?}(self * rhs.0)
+    }}
+}}
+
+impl core::ops::Div<f32> for {0:?} {{
+    type Output = Self;
+
+    fn div(self, rhs: f32) -> Self {{
+        Self(self.0 / rhs)
+    }}
+}}
+
+impl core::ops::Div for {0:?} {{
+    type Output = f32;
+
+    fn div(self, rhs: Self) -> f32 {{
+        self.0 / rhs.0
+    }}
+}}"##, u)?;
     }
 
+    //
+    // And where the product of two units is itself a unit that we have, we
+    // allow for the multiplication (in either order) and the divisions that
+    // undo it.
+    //
+    for (a, b, product) in Units::PRODUCTS {
+        if ![a, b, product].iter().all(|u| units.contains(u)) {
+            continue;
+        }
+
+        writeln!(&mut s, r##"
+impl core::ops::Mul<{1:?}> for {0:?} {{
+    type Output = {2:?};
+
+    fn mul(self, rhs: {1:?}) -> {2:?} {{
+        {2:?}(self.0 * rhs.0)
+    }}
+}}
+
+impl core::ops::Mul<{0:?}> for {1:?} {{
+    type Output = {2:?};
+
+    fn mul(self, rhs: {0:?}) -> {2:?} {{
+        {2:?}(self.0 * rhs.0)
+    }}
+}}
+
+impl core::ops::Div<{0:?}> for {2:?} {{
+    type Output = {1:?};
+
+    fn div(self, rhs: {0:?}) -> {1:?} {{
+        {1:?}(self.0 / rhs.0)
+    }}
+}}
+
+impl core::ops::Div<{1:?}> for {2:?} {{
+    type Output = {0:?};
+
+    fn div(self, rhs: {1:?}) -> {0:?} {{
+        {0:?}(self.0 / rhs.0)
+    }}
+}}"##, a, b, product)?;
+    }
+
+    //
+    // Finally, the units themselves are enumerated, allowing the units of
+    // a command to be determined at run-time.
+    //
+    writeln!(&mut s, r##"
+/// The units in which a value is expressed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Units {{"##)?;
+
+    for u in &units {
+        writeln!(&mut s, "    {:?},", u)?;
+    }
+
+    writeln!(&mut s, r##"}}
+
+impl Units {{
+    /// Returns the suffix denoting these units (e.g., "V")
+    pub fn suffix(&self) -> &'static str {{
+        match self {{"##)?;
+
+    for u in &units {
+        writeln!(&mut s, "            Units::{:?} => \"{}\",", u, u.suffix())?;
+    }
+
+    writeln!(&mut s, "        }}\n    }}\n}}")?;
+
     Ok(s)
 }
 
+fn read_file(filename: &str) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut contents = vec![];
+    open_file(filename)?.read_to_end(&mut contents)?;
+
+    Ok(contents)
+}
+
+//
+// We use FNV-1a to hash our inputs, as (unlike the hasher in std) its output
+// is stable.
+//
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |hash, &b| (hash ^ b as u64).wrapping_mul(FNV_PRIME))
+}
+
+//
+// Each field has a numeric identifier that is derived from the device that
+// defines it (empty for a common definition), its command, and its name.
+// This is stable across builds and across changes to the field's
+// description; we fold the hash to 32 bits.
+//
+fn field_id(scope: &str, cmd: &str, field: &str) -> u32 {
+    let hash = fnv(
+        FNV_OFFSET,
+        format!("{}:{}:{}", scope, cmd, field).as_bytes(),
+    );
+    (hash ^ (hash >> 32)) as u32
+}
+
+//
+// For each file that we generate, we leave a record alongside it that has
+// the hash of the inputs that generated it, along with what we need to know
+// about the file to generate everything else (namely, the units it uses, and
+// whether it defines extended commands).  If the inputs are unchanged from a
+// previous build, we use that record rather than regenerating the file.
+//
+struct Generated {
+    hash: u64,
+    units: HashSet<Units>,
+    extended: bool,
+}
+
+impl Generated {
+    fn record(path: &Path) -> PathBuf {
+        path.with_extension("generated")
+    }
+
+    fn cached(path: &Path, hash: u64) -> Option<Self> {
+        if !path.exists() {
+            return None;
+        }
+
+        let record = std::fs::read_to_string(Self::record(path)).ok()?;
+        let mut lines = record.lines();
+
+        let recorded = lines.next()?.strip_prefix("hash ")?;
+
+        if u64::from_str_radix(recorded, 16).ok()? != hash {
+            return None;
+        }
+
+        let mut units = HashSet::new();
+
+        for unit in lines.next()?.strip_prefix("units")?.split_whitespace() {
+            units.insert(ron::de::from_str(unit).ok()?);
+        }
+
+        let extended = match lines.next()? {
+            "extended true" => true,
+            "extended false" => false,
+            _ => return None,
+        };
+
+        Some(Self {
+            hash,
+            units,
+            extended,
+        })
+    }
+
+    fn store(&self, path: &Path, contents: &[u8]) -> Result<()> {
+        std::fs::write(path, contents)?;
+
+        let mut record = format!("hash {:016x}\nunits", self.hash);
+
+        for unit in &self.units {
+            write!(&mut record, " {:?}", unit)?;
+        }
+
+        writeln!(&mut record, "\nextended {}", self.extended)?;
+        std::fs::write(Self::record(path), record)?;
+
+        Ok(())
+    }
+}
+
+fn write_if_changed(path: &Path, contents: &[u8]) -> Result<()> {
+    match std::fs::read(path) {
+        Ok(ref current) if current == contents => Ok(()),
+        _ => {
+            std::fs::write(path, contents)?;
+            Ok(())
+        }
+    }
+}
+
 fn open_file(filename: &str) -> Result<File> {
     let mut dir = PathBuf::from(&env::var("CARGO_MANIFEST_DIR").unwrap());
     dir.push("src");
@@ -2209,72 +4515,113 @@ fn codegen() -> Result<()> {
     let mut dir = PathBuf::from(&env::var("CARGO_MANIFEST_DIR")?);
     dir.push("src");
 
-    let f = open_file("commands.ron")?;
+    let cbytes = read_file("commands.ron")?;
 
-    let cmds: Commands = match from_reader(f) {
+    let cmds: Commands = match from_reader(&cbytes[..]) {
         Ok(cmds) => cmds,
         Err(e) => {
             bail!("failed to parse commands.ron: {}", e);
         }
     };
 
+    if cmds.extended.is_some() {
+        bail!("extended commands must be defined by a device");
+    }
+
     let sizes = reg_sizes(&cmds.all)?;
     let dbs = &cmds.structured;
 
     let out_dir = env::var("OUT_DIR")?;
     let dest_path = Path::new(&out_dir).join("commands.rs");
-    let mut file = File::create(&dest_path)?;
-    let mut units: HashSet<Units> = HashSet::new();
+    let mut units: HashSet<Units> = Units::DERIVED.iter().copied().collect();
 
-    let out = output_commands(&cmds, None)?;
-    file.write_all(out.as_bytes())?;
+    //
+    // Generating (and compiling) our output is expensive, so we only
+    // regenerate a file if its inputs -- including this build script --
+    // have changed since we last generated it.
+    //
+    let hash = fnv(fnv(FNV_OFFSET, include_bytes!("build.rs")), &cbytes);
+    let common = hash;
 
-    for (cmd, fields) in dbs {
-        let (bits, bytes) = validate(cmd, fields, &sizes, &mut units)?;
-        let out = output_command_data(cmd, fields, bits, bytes)?;
+    if let Some(generated) = Generated::cached(&dest_path, hash) {
+        units.extend(generated.units);
+    } else {
+        let mut file: Vec<u8> = vec![];
+        let mut cunits = HashSet::new();
+
+        let out = output_commands(&cmds, None)?;
         file.write_all(out.as_bytes())?;
-    }
 
-    if let Some(ref synonyms) = cmds.synonyms {
-        for synonym in synonyms {
-            let cmd = &synonym.0;
+        for (cmd, fields) in dbs {
+            let (bits, bytes, block) =
+                validate(cmd, fields, &sizes, &mut cunits)?;
+            let out = output_command_data("", cmd, fields, bits, bytes, block)?;
+            file.write_all(out.as_bytes())?;
+        }
 
-            //
-            // We must have a structured definition for the command for
-            // which we're a synonym.
-            //
-            if let Some(fields) = dbs.get(&synonym.1) {
-                let (bits, bytes) = validate(cmd, fields, &sizes, &mut units)?;
-                let out = output_command_data(cmd, fields, bits, bytes)?;
-                file.write_all(out.as_bytes())?;
-            } else {
-                bail!(
-                    "command {} is a synonym for {}, \
-                    but {} lacks a structured definition",
-                    cmd, synonym.1, synonym.1
-                );
+        if let Some(ref synonyms) = cmds.synonyms {
+            for synonym in synonyms {
+                let cmd = &synonym.0;
+
+                //
+                // We must have a structured definition for the command for
+                // which we're a synonym.
+                //
+                if let Some(fields) = dbs.get(&synonym.1) {
+                    let (bits, bytes, block) =
+                        validate(cmd, fields, &sizes, &mut cunits)?;
+                    let out = output_command_data(
+                        "", cmd, fields, bits, bytes, block,
+                    )?;
+                    file.write_all(out.as_bytes())?;
+                } else {
+                    bail!(
+                        "command {} is a synonym for {}, \
+                        but {} lacks a structured definition",
+                        cmd, synonym.1, synonym.1
+                    );
+                }
             }
         }
-    }
 
-    let out = output_numerics(&cmds.numerics, &sizes, &mut units, None)?;
-    file.write_all(out.as_bytes())?;
+        let out =
+            output_numerics("", &cmds.numerics, &sizes, &mut cunits, None)?;
+        file.write_all(out.as_bytes())?;
+
+        if let Some(ref accuracy) = cmds.accuracy {
+            let numerics = [&cmds.numerics];
+            let out = output_accuracy(accuracy, &numerics, &mut cunits)?;
+            file.write_all(out.as_bytes())?;
+        }
+
+        units.extend(cunits.iter().copied());
+
+        let generated = Generated {
+            hash,
+            units: cunits,
+            extended: false,
+        };
+
+        generated.store(&dest_path, &file)?;
+    }
 
-    let f = open_file("devices.ron")?;
+    let dbytes = read_file("devices.ron")?;
+    let common = fnv(common, &dbytes);
 
-    let devices: HashMap<String, Device> = match from_reader(f) {
+    let devices: BTreeMap<String, Device> = match from_reader(&dbytes[..]) {
         Ok(devices) => devices,
         Err(e) => {
             bail!("failed to parse devices.ron: {}", e);
         }
     };
 
-    let dest_path = Path::new(&out_dir).join("devices.rs");
-    let mut dfile = File::create(&dest_path)?;
+    let mut dfile: Vec<u8> = vec![];
 
     let out = output_devices(&devices)?;
     dfile.write_all(out.as_bytes())?;
 
+    let mut extended = HashSet::new();
+
     //
     // Now we need to iterate over our devices.  For each one, we'll generate
     // our flattened module, and then include it in our flattened file of
@@ -2282,18 +4629,73 @@ fn codegen() -> Result<()> {
     //
     for (name, device) in &devices {
         let dest_path = Path::new(&out_dir).join(format!("{}.rs", name));
-        let mut file = File::create(&dest_path)?;
 
         let fname = format!("{}.ron", &name);
-        let f = open_file(&fname)?;
+        let bytes = read_file(&fname)?;
+        let mut hash = fnv(common, &bytes);
+
+        //
+        // If we have a base, its definition is part of ours.
+        //
+        let base = match device.base {
+            Some(ref base) => {
+                let coeff = match devices.get(base) {
+                    Some(Device { base: Some(_), .. }) => {
+                        bail!(
+                            "{} is based on {}, which has a base",
+                            name,
+                            base
+                        );
+                    }
+                    Some(b) => b.coefficients.is_some(),
+                    None => bail!("{} is based on unknown {}", name, base),
+                };
+
+                let bbytes = read_file(&format!("{}.ron", base))?;
+                hash = fnv(hash, &bbytes);
+                Some((base, bbytes, coeff))
+            }
+            None => None,
+        };
+
+        let out = output_device(&name)?;
+        dfile.write_all(out.as_bytes())?;
+
+        if let Some(generated) = Generated::cached(&dest_path, hash) {
+            units.extend(generated.units);
+
+            if generated.extended {
+                extended.insert(name.clone());
+            }
+
+            continue;
+        }
+
+        let mut file: Vec<u8> = vec![];
+        let mut dunits = HashSet::new();
 
-        let mut dcmds: Commands = match from_reader(f) {
+        let mut dcmds: Commands = match from_reader(&bytes[..]) {
             Ok(dcmds) => dcmds,
             Err(e) => {
                 bail!("failed to parse {}: {}", fname, e);
             }
         };
 
+        let inherited = match base {
+            Some((base, ref bbytes, coeff)) => {
+                let bcmds: Commands = match from_reader(&bbytes[..]) {
+                    Ok(bcmds) => bcmds,
+                    Err(e) => {
+                        bail!("failed to parse {}.ron: {}", base, e);
+                    }
+                };
+
+                let coeff = coeff || device.coefficients.is_some();
+                inherit(&mut dcmds, bcmds, coeff)
+            }
+            None => HashSet::new(),
+        };
+
         //
         // Flatten our commands and output them
         //
@@ -2314,28 +4716,50 @@ fn codegen() -> Result<()> {
 
         let dsizes = reg_sizes(&dcmds.all)?;
 
+        let out = output_facade(name, device, &dcmds)?;
+        file.write_all(out.as_bytes())?;
+
         //
         // Now emit data payloads, allowing the device definition to
-        // override any common payload.
+        // override any common payload.  Any common payload that isn't
+        // overridden is identical to the common definition, so we re-export
+        // that rather than emitting a copy.
         //
-        for (cmd, fields) in dbs {
+        for cmd in dbs.keys() {
             if let Some(fields) = dcmds.structured.get(cmd) {
-                let (bits, bytes) =
-                    validate(&cmd, &fields, &dsizes, &mut units)?;
-                let out = output_command_data(cmd, fields, bits, bytes)?;
+                let (bits, bytes, block) =
+                    validate(&cmd, &fields, &dsizes, &mut dunits)?;
+
+                let out = match base {
+                    Some((base, ..)) if inherited.contains(cmd) => {
+                        output_base_reexport(base, cmd)?
+                    }
+                    _ => output_command_data(
+                        name, cmd, fields, bits, bytes, block,
+                    )?,
+                };
+
                 file.write_all(out.as_bytes())?;
                 dcmds.structured.remove(cmd);
             } else {
-                let (bits, bytes) =
-                    validate(&cmd, &fields, &sizes, &mut units)?;
-                let out = output_command_data(cmd, fields, bits, bytes)?;
+                let out = output_reexport(cmd)?;
                 file.write_all(out.as_bytes())?;
             }
         }
 
         for (cmd, fields) in &dcmds.structured {
-            let (bits, bytes) = validate(&cmd, &fields, &dsizes, &mut units)?;
-            let out = output_command_data(cmd, fields, bits, bytes)?;
+            let (bits, bytes, block) =
+                validate(&cmd, &fields, &dsizes, &mut dunits)?;
+
+            let out = match base {
+                Some((base, ..)) if inherited.contains(cmd) => {
+                    output_base_reexport(base, cmd)?
+                }
+                _ => {
+                    output_command_data(name, cmd, fields, bits, bytes, block)?
+                }
+            };
+
             file.write_all(out.as_bytes())?;
         }
 
@@ -2363,18 +4787,83 @@ fn codegen() -> Result<()> {
                     },
                 };
 
-                let (bits, bytes) = validate(cmd, fields, &s, &mut units)?;
-                let out = output_command_data(cmd, fields, bits, bytes)?;
+                let (bits, bytes, block) =
+                    validate(cmd, fields, &s, &mut dunits)?;
+                let out =
+                    output_command_data(name, cmd, fields, bits, bytes, block)?;
                 file.write_all(out.as_bytes())?;
             }
         }
 
         let coeff = device.coefficients;
-        let out = output_numerics(&dcmds.numerics, &dsizes, &mut units, coeff)?;
-        file.write_all(out.as_bytes())?;
 
-        let out = output_numerics(&cmds.numerics, &sizes, &mut units, coeff)?;
-        file.write_all(out.as_bytes())?;
+        for cmd in &dcmds.numerics {
+            let out = match base {
+                Some((base, ..)) if inherited.contains(&cmd.0) => {
+                    dunits.insert(cmd.2);
+                    output_base_reexport(base, &cmd.0)?
+                }
+                _ => output_numerics(
+                    name,
+                    core::slice::from_ref(cmd),
+                    &dsizes,
+                    &mut dunits,
+                    coeff,
+                )?,
+            };
+
+            file.write_all(out.as_bytes())?;
+        }
+
+        if let Some(ref sets) = dcmds.coefficients {
+            let out = output_coefficient_sets(
+                sets,
+                &dcmds.numerics,
+                &dcmds.structured,
+                &mut dunits,
+            )?;
+            file.write_all(out.as_bytes())?;
+        }
+
+        //
+        // The common numerics are only device-specific if the device has
+        // coefficients (or specifies an accuracy); otherwise, we re-export
+        // the common definition.  Any that the device defines itself have
+        // already been emitted above.
+        //
+        let mut accurate = HashSet::new();
+
+        if let Some(ref accuracy) = dcmds.accuracy {
+            for CommandAccuracy(cmd, _) in accuracy {
+                accurate.insert(cmd);
+            }
+        }
+
+        for cmd in &cmds.numerics {
+            if dcmds.numerics.iter().any(|n| n.0 == cmd.0) {
+                continue;
+            }
+
+            let out = if coeff.is_some() || accurate.contains(&cmd.0) {
+                output_numerics(
+                    name,
+                    core::slice::from_ref(cmd),
+                    &sizes,
+                    &mut dunits,
+                    coeff,
+                )?
+            } else {
+                output_reexport(&cmd.0)?
+            };
+
+            file.write_all(out.as_bytes())?;
+        }
+
+        if let Some(ref accuracy) = dcmds.accuracy {
+            let numerics = [&dcmds.numerics, &cmds.numerics];
+            let out = output_accuracy(accuracy, &numerics, &mut dunits)?;
+            file.write_all(out.as_bytes())?;
+        }
 
         //
         // If we have auxiliary structures, we emit each of those in its
@@ -2383,28 +4872,61 @@ fn codegen() -> Result<()> {
         if let Some(aux) = dcmds.auxiliaries {
             let sizes = aux_sizes(&aux.all)?;
 
-            let out =
-                output_aux_numerics(&aux.numerics, &sizes, &mut units, coeff)?;
+            let out = output_aux_numerics(
+                name,
+                &aux.numerics,
+                &sizes,
+                &mut dunits,
+                coeff,
+            )?;
             file.write_all(out.as_bytes())?;
 
             for (aux, fields) in &aux.structured {
-                let (bits, bytes) =
-                    validate(&aux, &fields, &sizes, &mut units)?;
+                let (bits, bytes, _) =
+                    validate(&aux, &fields, &sizes, &mut dunits)?;
 
-                let out = output_aux_data(aux, fields, bits, bytes)?;
+                let out = output_aux_data(name, aux, fields, bits, bytes)?;
                 file.write_all(out.as_bytes())?;
             }
         }
 
-        let out = output_device(&name)?;
-        dfile.write_all(out.as_bytes())?;
+        let out = output_revisions(
+            name,
+            &dcmds.all,
+            &dcmds.revisions,
+            &dsizes,
+            &mut dunits,
+        )?;
+        file.write_all(out.as_bytes())?;
+
+        let has_extended = dcmds.extended.is_some();
+
+        if let Some(ext) = dcmds.extended {
+            let out = output_extended(name, ext, &mut dunits, coeff)?;
+            file.write_all(out.as_bytes())?;
+            extended.insert(name.clone());
+        }
+
+        units.extend(dunits.iter().copied());
+
+        let generated = Generated {
+            hash,
+            units: dunits,
+            extended: has_extended,
+        };
+
+        generated.store(&dest_path, &file)?;
     }
 
-    let dest_path = Path::new(&out_dir).join("units.rs");
-    let mut ufile = File::create(&dest_path)?;
+    let out = output_devices_extended(&devices, &extended)?;
+    dfile.write_all(out.as_bytes())?;
 
+    let dest_path = Path::new(&out_dir).join("devices.rs");
+    write_if_changed(&dest_path, &dfile)?;
+
+    let dest_path = Path::new(&out_dir).join("units.rs");
     let out = output_units(&units)?;
-    ufile.write_all(out.as_bytes())?;
+    write_if_changed(&dest_path, out.as_bytes())?;
 
     Ok(())
 }
@@ -0,0 +1,85 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+/*
+ * C interface to the pmbus crate, as provided by its "ffi" feature.  Device
+ * names are NUL-terminated; the strings passed to callbacks are not.  All
+ * functions return 0 on success or a negative error code (PMBUS_ERR_*) on
+ * failure.
+ */
+
+#ifndef _PMBUS_H
+#define _PMBUS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Error codes, as returned by all functions on failure.
+ */
+#define	PMBUS_ERR_SHORT_DATA		(-1)
+#define	PMBUS_ERR_INVALID_CODE		(-2)
+#define	PMBUS_ERR_VALUE_OUT_OF_RANGE	(-3)
+#define	PMBUS_ERR_INVALID_MODE		(-4)
+#define	PMBUS_ERR_INVALID_SENTINEL	(-5)
+#define	PMBUS_ERR_MISSING_COEFFICIENTS	(-6)
+#define	PMBUS_ERR_INVALID_REPLACEMENT	(-7)
+#define	PMBUS_ERR_OVERFLOW_REPLACEMENT	(-8)
+#define	PMBUS_ERR_INVALID_FIELD		(-9)
+#define	PMBUS_ERR_INVALID_OPERATION	(-10)
+#define	PMBUS_ERR_INVALID_LENGTH	(-11)
+#define	PMBUS_ERR_BUFFER_TOO_SMALL	(-12)
+#define	PMBUS_ERR_UNSUPPORTED		(-13)
+#define	PMBUS_ERR_UNSUPPORTED_REVISION	(-14)
+#define	PMBUS_ERR_BAD_PEC		(-15)
+
+typedef struct pmbus_field {
+	const uint8_t *name;
+	size_t name_len;
+	const uint8_t *desc;
+	size_t desc_len;
+	uint8_t bitpos;
+	uint8_t width;
+	uint32_t raw;		/* 0 if there is no value */
+	const uint8_t *value;	/* NULL if there is no value */
+	size_t value_len;
+} pmbus_field_t;
+
+typedef void (*pmbus_field_cb_t)(void *, const pmbus_field_t *);
+
+extern int32_t pmbus_decode(const char *device, uint8_t code,
+    const uint8_t *payload, size_t len, uint8_t vout_mode,
+    pmbus_field_cb_t cb, void *arg);
+
+extern int32_t pmbus_fields(const char *device, uint8_t code,
+    pmbus_field_cb_t cb, void *arg);
+
+extern int32_t pmbus_get_field(const char *device, uint8_t code,
+    const uint8_t *payload, size_t len, uint8_t vout_mode,
+    const char *field, uint32_t *raw);
+
+extern int32_t pmbus_set_field_integer(const char *device, uint8_t code,
+    uint8_t *payload, size_t len, uint8_t vout_mode,
+    const char *field, uint32_t value);
+
+extern int32_t pmbus_set_field_float(const char *device, uint8_t code,
+    uint8_t *payload, size_t len, uint8_t vout_mode,
+    const char *field, float value);
+
+extern int32_t pmbus_encode(const char *device, uint8_t address,
+    uint8_t code, const uint8_t *payload, size_t len, bool pec,
+    uint8_t *buf, size_t buflen, size_t *out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* _PMBUS_H */
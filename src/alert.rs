@@ -0,0 +1,133 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! SMBALERT# servicing.  When SMBALERT# is asserted, the host determines
+//! which devices are asserting it by reading from the SMBus Alert Response
+//! Address (ARA):  each read is answered by one asserting device (which
+//! then deasserts), until no device responds.  For each responding device,
+//! [`service`] reads STATUS_WORD and then each of the STATUS_* registers
+//! that STATUS_WORD indicates are of interest, assembling a [`FaultReport`].
+//!
+
+use crate::commands::{
+    STATUS_CML, STATUS_INPUT, STATUS_IOUT, STATUS_OTHER, STATUS_TEMPERATURE,
+    STATUS_VOUT, STATUS_WORD,
+};
+use crate::CommandCode;
+
+/// The (7-bit) SMBus Alert Response Address
+pub const ALERT_RESPONSE_ADDRESS: u8 = 0x0c;
+
+/// Returns the (7-bit) address of a device given the byte it returned in
+/// response to a read from the Alert Response Address
+pub fn ara_address(response: u8) -> u8 {
+    response >> 1
+}
+
+///
+/// The fault status of a device that responded to the Alert Response
+/// Address.  Each STATUS_* register is present only if STATUS_WORD indicated
+/// that it should be consulted.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FaultReport {
+    /// The (7-bit) address of the device
+    pub address: u8,
+    pub status: STATUS_WORD::CommandData,
+    pub vout: Option<STATUS_VOUT::CommandData>,
+    pub iout: Option<STATUS_IOUT::CommandData>,
+    pub input: Option<STATUS_INPUT::CommandData>,
+    pub temperature: Option<STATUS_TEMPERATURE::CommandData>,
+    pub cml: Option<STATUS_CML::CommandData>,
+    pub other: Option<STATUS_OTHER::CommandData>,
+    /// The contents of STATUS_MFR_SPECIFIC, which is device-specific
+    pub mfr: Option<u8>,
+    /// The contents of STATUS_FANS_1_2
+    pub fans: Option<u8>,
+}
+
+///
+/// Reads the fault status of the device at the specified address via
+/// `read`, which is called with an address, a command code, and a buffer
+/// to read the command into.  For paged devices, status is that of the
+/// currently selected page.
+///
+pub fn triage<E>(
+    address: u8,
+    mut read: impl FnMut(u8, u8, &mut [u8]) -> Result<(), E>,
+) -> Result<FaultReport, E> {
+    let mut buf = [0u8; 2];
+
+    read(address, CommandCode::STATUS_WORD as u8, &mut buf)?;
+    let status = STATUS_WORD::CommandData(u16::from_le_bytes(buf));
+
+    let asserted = |bits: &[u8]| bits.iter().any(|b| status.0 & (1 << b) != 0);
+
+    let mut byte = |cond: bool, code: CommandCode| -> Result<Option<u8>, E> {
+        if cond {
+            let mut buf = [0u8; 1];
+            read(address, code as u8, &mut buf)?;
+            Ok(Some(buf[0]))
+        } else {
+            Ok(None)
+        }
+    };
+
+    //
+    // Each STATUS_* register is indicated by a bit in the high byte of
+    // STATUS_WORD, and (for some) by a bit in the low byte as well.
+    //
+    Ok(FaultReport {
+        address,
+        status,
+        vout: byte(asserted(&[15, 5]), CommandCode::STATUS_VOUT)?
+            .map(STATUS_VOUT::CommandData),
+        iout: byte(asserted(&[14, 4]), CommandCode::STATUS_IOUT)?
+            .map(STATUS_IOUT::CommandData),
+        input: byte(asserted(&[13, 3]), CommandCode::STATUS_INPUT)?
+            .map(STATUS_INPUT::CommandData),
+        temperature: byte(asserted(&[2]), CommandCode::STATUS_TEMPERATURE)?
+            .map(STATUS_TEMPERATURE::CommandData),
+        cml: byte(asserted(&[1]), CommandCode::STATUS_CML)?
+            .map(STATUS_CML::CommandData),
+        other: byte(asserted(&[9]), CommandCode::STATUS_OTHER)?
+            .map(STATUS_OTHER::CommandData),
+        mfr: byte(asserted(&[12]), CommandCode::STATUS_MFR_SPECIFIC)?,
+        fans: byte(asserted(&[10]), CommandCode::STATUS_FANS_1_2)?,
+    })
+}
+
+///
+/// Services an assertion of SMBALERT#.  The Alert Response Address is read
+/// via `ara`, which should return the byte read or `None` if no device
+/// responded; for each responding device, a [`FaultReport`] is assembled
+/// via [`triage`] and passed to `report`.  Servicing stops when no device
+/// responds, or after `max` devices have responded (guarding against a
+/// device that fails to deassert SMBALERT#).  Returns the number of devices
+/// that responded.  Faults are not cleared; it is up to the caller to
+/// determine whether and when to do so.
+///
+pub fn service<E>(
+    max: usize,
+    mut ara: impl FnMut() -> Result<Option<u8>, E>,
+    mut read: impl FnMut(u8, u8, &mut [u8]) -> Result<(), E>,
+    mut report: impl FnMut(FaultReport),
+) -> Result<usize, E> {
+    let mut count = 0;
+
+    while count < max {
+        let response = match ara()? {
+            Some(response) => response,
+            None => break,
+        };
+
+        report(triage(ara_address(response), &mut read)?);
+        count += 1;
+    }
+
+    Ok(count)
+}
@@ -0,0 +1,233 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Auditing a device.  Given a snapshot of a device -- the payloads of the
+//! commands read from it, as `(code, payload)` pairs -- [`audit`] decodes
+//! every command and reports everything that looks wrong with it:  commands
+//! that aren't defined or can't be interpreted, reserved bits that are set,
+//! limits that are inconsistent with one another, and (if a set of defaults
+//! is provided) commands that differ from their defaults.  The report is
+//! delivered as a sequence of [`Item`]s to a caller-provided closure, so
+//! that no allocation is required.
+//!
+
+use crate::commands::{CommandCode, VOUT_MODE};
+use crate::{Device, Error, Field, Value};
+use core::cell::Cell;
+
+///
+/// Pairs of limits that must be ordered:  the first command of each pair
+/// should not exceed the second.  These orderings follow from the
+/// definitions of the commands in the PMBus specification.
+///
+pub const LIMITS: &[(CommandCode, CommandCode)] = &[
+    (
+        CommandCode::VOUT_UV_FAULT_LIMIT,
+        CommandCode::VOUT_UV_WARN_LIMIT,
+    ),
+    (CommandCode::VOUT_UV_WARN_LIMIT, CommandCode::VOUT_COMMAND),
+    (CommandCode::VOUT_COMMAND, CommandCode::VOUT_OV_WARN_LIMIT),
+    (
+        CommandCode::VOUT_OV_WARN_LIMIT,
+        CommandCode::VOUT_OV_FAULT_LIMIT,
+    ),
+    (CommandCode::VOUT_COMMAND, CommandCode::VOUT_MAX),
+    (CommandCode::VOUT_MARGIN_LOW, CommandCode::VOUT_MARGIN_HIGH),
+    (
+        CommandCode::IOUT_OC_WARN_LIMIT,
+        CommandCode::IOUT_OC_FAULT_LIMIT,
+    ),
+    (CommandCode::OT_WARN_LIMIT, CommandCode::OT_FAULT_LIMIT),
+    (CommandCode::UT_FAULT_LIMIT, CommandCode::UT_WARN_LIMIT),
+    (
+        CommandCode::VIN_UV_FAULT_LIMIT,
+        CommandCode::VIN_UV_WARN_LIMIT,
+    ),
+    (
+        CommandCode::VIN_UV_WARN_LIMIT,
+        CommandCode::VIN_OV_WARN_LIMIT,
+    ),
+    (
+        CommandCode::VIN_OV_WARN_LIMIT,
+        CommandCode::VIN_OV_FAULT_LIMIT,
+    ),
+    (CommandCode::VIN_OFF, CommandCode::VIN_ON),
+    (CommandCode::POWER_GOOD_OFF, CommandCode::POWER_GOOD_ON),
+    (
+        CommandCode::IIN_OC_WARN_LIMIT,
+        CommandCode::IIN_OC_FAULT_LIMIT,
+    ),
+    (
+        CommandCode::POUT_OP_WARN_LIMIT,
+        CommandCode::POUT_OP_FAULT_LIMIT,
+    ),
+];
+
+/// Something that is wrong with a device
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Finding {
+    /// The command code is not defined for the device
+    Undefined(u8),
+    /// The payload for the command code could not be interpreted
+    Invalid(u8, Error),
+    /// The payload for the command code sets the indicated bits, which are
+    /// not covered by any field
+    Reserved(u8, u32),
+    /// The payload for the command code depends on VOUT_MODE, which is not
+    /// in the snapshot; its interpretation is suspect
+    MissingVOutMode(u8),
+    /// The limit for the first command code exceeds that for the second;
+    /// the real values of each are provided.
+    Limits(u8, f32, u8, f32),
+    /// The payload for the command code differs from its default
+    Default(u8),
+}
+
+/// An item in an audit report
+#[derive(Copy, Clone, Debug)]
+pub enum Item<'a> {
+    /// A field of a command code, and its value
+    Decoded(u8, &'a dyn Field, &'a dyn Value),
+    Finding(Finding),
+}
+
+/// A summary of an audit
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    /// The number of command codes that were decoded
+    pub decoded: usize,
+    /// The number of findings
+    pub findings: usize,
+}
+
+fn lookup<'a>(snapshot: &[(u8, &'a [u8])], code: u8) -> Option<&'a [u8]> {
+    snapshot.iter().find(|(c, _)| *c == code).map(|(_, p)| *p)
+}
+
+//
+// Returns the bits that are set in the payload but not covered by any field,
+// if the command is a bitfield.  Payloads wider than 32 bits aren't checked.
+//
+fn reserved(device: Device, code: u8, payload: &[u8]) -> u32 {
+    if payload.is_empty() || payload.len() > 4 {
+        return 0;
+    }
+
+    let mut covered = 0u64;
+    let mut bitfield = false;
+
+    let _ = device.fields(code, |f| {
+        if f.bitfield() {
+            let (pos, width) = f.bits();
+            covered |= ((1u64 << width.0) - 1) << pos.0;
+            bitfield = true;
+        }
+    });
+
+    if !bitfield {
+        return 0;
+    }
+
+    let mut raw = 0u32;
+
+    for (i, b) in payload.iter().enumerate() {
+        raw |= u32::from(*b) << (i * 8);
+    }
+
+    raw & !(covered as u32)
+}
+
+///
+/// Audits a snapshot of the specified device, calling `report` with each
+/// decoded field and with each finding.  VOUT_MODE is taken from the
+/// snapshot.  If `defaults` is provided, any command in the snapshot that
+/// is also in `defaults` but whose payload differs is reported with
+/// [`Finding::Default`].  Limits are checked for consistency as dictated
+/// by [`LIMITS`], for those limits that are in the snapshot and can be
+/// interpreted as real values.
+///
+pub fn audit(
+    device: Device,
+    snapshot: &[(u8, &[u8])],
+    defaults: Option<&[(u8, &[u8])]>,
+    mut report: impl FnMut(Item),
+) -> Summary {
+    let mut decoded = 0;
+    let mut findings = 0;
+    let vout_mode = lookup(snapshot, CommandCode::VOUT_MODE as u8)
+        .and_then(|p| p.first().copied());
+    let needed = Cell::new(false);
+
+    let mode = || {
+        needed.set(true);
+        VOUT_MODE::CommandData(vout_mode.unwrap_or(0))
+    };
+
+    let mut finding = |report: &mut dyn FnMut(Item), f| {
+        findings += 1;
+        report(Item::Finding(f));
+    };
+
+    for &(code, payload) in snapshot {
+        needed.set(false);
+
+        let rval = device.interpret(code, payload, mode, |f, v| {
+            report(Item::Decoded(code, f, v));
+        });
+
+        match rval {
+            Ok(()) => decoded += 1,
+            Err(Error::InvalidCode) => {
+                finding(&mut report, Finding::Undefined(code));
+                continue;
+            }
+            Err(err) => {
+                finding(&mut report, Finding::Invalid(code, err));
+                continue;
+            }
+        }
+
+        if needed.get() && vout_mode.is_none() {
+            finding(&mut report, Finding::MissingVOutMode(code));
+        }
+
+        let bits = reserved(device, code, payload);
+
+        if bits != 0 {
+            finding(&mut report, Finding::Reserved(code, bits));
+        }
+
+        if let Some(default) = defaults.and_then(|d| lookup(d, code)) {
+            if default != payload {
+                finding(&mut report, Finding::Default(code));
+            }
+        }
+    }
+
+    let real = |code: u8| {
+        let payload = lookup(snapshot, code)?;
+        let mut val = None;
+
+        device
+            .interpret(code, payload, mode, |_, v| val = v.as_float())
+            .ok()?;
+
+        val
+    };
+
+    for &(low, high) in LIMITS {
+        let (low, high) = (low as u8, high as u8);
+
+        if let (Some(l), Some(h)) = (real(low), real(high)) {
+            if l > h {
+                finding(&mut report, Finding::Limits(low, l, high, h));
+            }
+        }
+    }
+
+    Summary { decoded, findings }
+}
@@ -0,0 +1,125 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Brownout handling.  A regulator's reaction to a sagging input is dictated
+//! by its VIN_ON, VIN_OFF, VIN_UV_WARN_LIMIT and VIN_UV_FAULT_LIMIT
+//! settings; supervisory firmware that wishes to react to input voltage
+//! consistently with the regulator can construct a [`BrownoutProfile`] from
+//! these settings (as decoded by the device-specific command data) and then
+//! evaluate live READ_VIN readings against it with a [`Brownout`].
+//!
+
+use crate::units::Volts;
+use crate::Error;
+
+///
+/// The input voltage thresholds of a regulator.  Any threshold that a
+/// device does not support (or that has not been read) may be `None`, in
+/// which case it is not considered.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct BrownoutProfile {
+    /// The input voltage at which the regulator starts (VIN_ON)
+    pub vin_on: Option<Volts>,
+    /// The input voltage at which the regulator stops (VIN_OFF)
+    pub vin_off: Option<Volts>,
+    /// The input undervoltage warning limit (VIN_UV_WARN_LIMIT)
+    pub uv_warn: Option<Volts>,
+    /// The input undervoltage fault limit (VIN_UV_FAULT_LIMIT)
+    pub uv_fault: Option<Volts>,
+}
+
+impl BrownoutProfile {
+    /// Validates the profile, returning [`Error::ValueOutOfRange`] if VIN_OFF
+    /// is not below VIN_ON or if the fault limit is above the warning limit.
+    pub fn validate(&self) -> Result<(), Error> {
+        if let (Some(on), Some(off)) = (self.vin_on, self.vin_off) {
+            if off.0 >= on.0 {
+                return Err(Error::ValueOutOfRange);
+            }
+        }
+
+        if let (Some(warn), Some(fault)) = (self.uv_warn, self.uv_fault) {
+            if fault.0 > warn.0 {
+                return Err(Error::ValueOutOfRange);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The state of the input voltage relative to a [`BrownoutProfile`], in
+/// increasing order of severity
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub enum VinState {
+    /// The input voltage is above any limits
+    Normal,
+    /// The input voltage is below the warning limit
+    Warning,
+    /// The input voltage is below the fault limit
+    Fault,
+    /// The regulator is off due to input voltage:  the input voltage has
+    /// fallen below VIN_OFF, and has not since risen above VIN_ON
+    Off,
+}
+
+///
+/// An evaluator of input voltage readings against a [`BrownoutProfile`].
+/// This tracks whether the regulator would be on or off (per the hysteresis
+/// between VIN_ON and VIN_OFF); it is assumed that the input voltage is
+/// initially sufficient for the regulator to be on.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Brownout {
+    profile: BrownoutProfile,
+    off: bool,
+}
+
+impl Brownout {
+    /// Returns an evaluator for the specified profile, which must be valid
+    pub fn new(profile: BrownoutProfile) -> Result<Self, Error> {
+        profile.validate()?;
+        Ok(Self {
+            profile,
+            off: false,
+        })
+    }
+
+    /// Returns the profile
+    pub fn profile(&self) -> &BrownoutProfile {
+        &self.profile
+    }
+
+    /// Evaluates the next input voltage reading
+    pub fn update(&mut self, vin: Volts) -> VinState {
+        let below = |limit: Option<Volts>| match limit {
+            Some(limit) => vin.0 < limit.0,
+            None => false,
+        };
+
+        if self.off {
+            if let Some(on) = self.profile.vin_on {
+                self.off = vin.0 < on.0;
+            } else {
+                self.off = false;
+            }
+        } else {
+            self.off = below(self.profile.vin_off);
+        }
+
+        if self.off {
+            VinState::Off
+        } else if below(self.profile.uv_fault) {
+            VinState::Fault
+        } else if below(self.profile.uv_warn) {
+            VinState::Warning
+        } else {
+            VinState::Normal
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Output current calibration.  A device determines output current by
+//! dividing the voltage across its current sense element by IOUT_CAL_GAIN
+//! (the resistance of the element) and then adding IOUT_CAL_OFFSET; error
+//! in either results in gain and offset error in READ_IOUT.  Given
+//! measurements of READ_IOUT at known reference currents, [`calibrate`]
+//! determines the IOUT_CAL_GAIN and IOUT_CAL_OFFSET that correct that
+//! error.
+//!
+
+use crate::units::Amperes;
+use crate::Error;
+
+/// The values of IOUT_CAL_GAIN and IOUT_CAL_OFFSET.  The gain is in the
+/// units of the device's IOUT_CAL_GAIN (generally milliohms); as only the
+/// ratio of gains matters in calibration, the units are immaterial.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IoutCalibration {
+    pub gain: f32,
+    pub offset: Amperes,
+}
+
+/// A measurement of the current reported by a device (via READ_IOUT) at a
+/// known reference current
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IoutMeasurement {
+    pub reference: Amperes,
+    pub reported: Amperes,
+}
+
+///
+/// Given measurements taken with the specified calibration in effect,
+/// determines the calibration that corrects gain and offset error (via a
+/// least-squares fit of the reported current to the reference current).  As
+/// the corrected values must be representable by the device, each is
+/// quantized with the specified function, which should encode its argument
+/// in the device's format and return the value as decoded; the offset is
+/// determined with respect to the gain as quantized.  Returns
+/// [`Error::ValueOutOfRange`] if the measurements do not include at least
+/// two distinct reference currents, or if they indicate that the reported
+/// current does not increase with the reference current.
+///
+pub fn calibrate(
+    measurements: &[IoutMeasurement],
+    current: IoutCalibration,
+    quantize_gain: impl Fn(f32) -> Result<f32, Error>,
+    quantize_offset: impl Fn(Amperes) -> Result<Amperes, Error>,
+) -> Result<IoutCalibration, Error> {
+    let n = measurements.len() as f32;
+
+    let (sx, sy) = measurements.iter().fold((0.0, 0.0), |(sx, sy), m| {
+        (sx + m.reference.0, sy + m.reported.0)
+    });
+
+    let (mx, my) = (sx / n, sy / n);
+
+    let (sxx, sxy) = measurements.iter().fold((0.0, 0.0), |(sxx, sxy), m| {
+        let (dx, dy) = (m.reference.0 - mx, m.reported.0 - my);
+        (sxx + dx * dx, sxy + dx * dy)
+    });
+
+    //
+    // The reported current is (ideally) a linear function of the reference
+    // current:  the slope is the ratio of the true sense resistance to the
+    // calibrated gain, and the intercept is the sum of the calibrated offset
+    // and the sense offset (divided by the gain).
+    //
+    let slope = sxy / sxx;
+    let intercept = my - slope * mx;
+
+    if !slope.is_finite() || slope <= 0.0 || !intercept.is_finite() {
+        return Err(Error::ValueOutOfRange);
+    }
+
+    let gain = quantize_gain(current.gain * slope)?;
+
+    //
+    // With the new gain, the sense offset is scaled by the ratio of the old
+    // gain to the new; the new offset cancels it.
+    //
+    let sense = (intercept - current.offset.0) * current.gain / gain;
+    let offset = quantize_offset(Amperes(-sense))?;
+
+    Ok(IoutCalibration { gain, offset })
+}
@@ -0,0 +1,234 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Compact binary encoding of telemetry, enabled via the `cbor` feature.
+//! Telemetry [`Snapshot`]s and fault reports ([`FaultReport`]) are encoded
+//! as CBOR (RFC 8949) into a caller-provided buffer, allowing them to be
+//! streamed (e.g., from a BMC to a collector) without allocation.  To keep
+//! the encoding compact, maps are keyed by small integers rather than by
+//! strings; the keys are given by the constants in [`snapshot`] and
+//! [`fault`].
+//!
+
+use crate::alert::FaultReport;
+use crate::{Device, Error};
+
+/// Map keys for an encoded [`Snapshot`]
+pub mod snapshot {
+    /// The name of the device, as a text string
+    pub const DEVICE: u8 = 0;
+    /// The (7-bit) address of the device
+    pub const ADDRESS: u8 = 1;
+    /// The caller-defined timestamp of the snapshot
+    pub const TIMESTAMP: u8 = 2;
+    /// The readings, as an array of `[code, page, value]` arrays (with a
+    /// page of null for unpaged devices)
+    pub const READINGS: u8 = 3;
+}
+
+/// Map keys for an encoded [`FaultReport`]; each STATUS_* register is
+/// present only if it is present in the report.
+pub mod fault {
+    pub const ADDRESS: u8 = 0;
+    pub const STATUS_WORD: u8 = 1;
+    pub const STATUS_VOUT: u8 = 2;
+    pub const STATUS_IOUT: u8 = 3;
+    pub const STATUS_INPUT: u8 = 4;
+    pub const STATUS_TEMPERATURE: u8 = 5;
+    pub const STATUS_CML: u8 = 6;
+    pub const STATUS_OTHER: u8 = 7;
+    pub const STATUS_MFR_SPECIFIC: u8 = 8;
+    pub const STATUS_FANS_1_2: u8 = 9;
+}
+
+/// A single telemetry reading:  a command code, the page it was read from
+/// (if the device is paged), and its value as interpreted
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Reading {
+    pub code: u8,
+    pub page: Option<u8>,
+    pub value: f32,
+}
+
+/// A set of readings taken from a device at a particular time
+#[derive(Copy, Clone, Debug)]
+pub struct Snapshot<'a> {
+    pub device: Device,
+    pub address: u8,
+    /// A timestamp, in units of the caller's choosing
+    pub timestamp: u64,
+    pub readings: &'a [Reading],
+}
+
+const MAJOR_UINT: u8 = 0;
+const MAJOR_NINT: u8 = 1;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_SIMPLE: u8 = 7;
+
+const SIMPLE_NULL: u8 = 22;
+const SIMPLE_FLOAT32: u8 = 26;
+
+///
+/// A CBOR encoder that writes into a caller-provided buffer.  Any operation
+/// that would overrun the buffer fails with [`Error::BufferTooSmall`].
+///
+pub struct Encoder<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Encoder<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Returns the number of bytes that have been encoded
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if nothing has been encoded
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn put(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let end = self.len + bytes.len();
+
+        if end > self.buf.len() {
+            return Err(Error::BufferTooSmall);
+        }
+
+        self.buf[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+
+    fn head(&mut self, major: u8, val: u64) -> Result<(), Error> {
+        let major = major << 5;
+
+        if val < 24 {
+            self.put(&[major | val as u8])
+        } else if val <= u8::MAX as u64 {
+            self.put(&[major | 24, val as u8])
+        } else if val <= u16::MAX as u64 {
+            self.put(&[major | 25])?;
+            self.put(&(val as u16).to_be_bytes())
+        } else if val <= u32::MAX as u64 {
+            self.put(&[major | 26])?;
+            self.put(&(val as u32).to_be_bytes())
+        } else {
+            self.put(&[major | 27])?;
+            self.put(&val.to_be_bytes())
+        }
+    }
+
+    pub fn uint(&mut self, val: u64) -> Result<(), Error> {
+        self.head(MAJOR_UINT, val)
+    }
+
+    pub fn int(&mut self, val: i64) -> Result<(), Error> {
+        if val < 0 {
+            self.head(MAJOR_NINT, !val as u64)
+        } else {
+            self.head(MAJOR_UINT, val as u64)
+        }
+    }
+
+    pub fn float(&mut self, val: f32) -> Result<(), Error> {
+        self.put(&[MAJOR_SIMPLE << 5 | SIMPLE_FLOAT32])?;
+        self.put(&val.to_be_bytes())
+    }
+
+    pub fn text(&mut self, val: &str) -> Result<(), Error> {
+        self.head(MAJOR_TEXT, val.len() as u64)?;
+        self.put(val.as_bytes())
+    }
+
+    pub fn null(&mut self) -> Result<(), Error> {
+        self.put(&[MAJOR_SIMPLE << 5 | SIMPLE_NULL])
+    }
+
+    /// Begins an array of the specified number of elements
+    pub fn array(&mut self, len: usize) -> Result<(), Error> {
+        self.head(MAJOR_ARRAY, len as u64)
+    }
+
+    /// Begins a map of the specified number of key/value pairs
+    pub fn map(&mut self, len: usize) -> Result<(), Error> {
+        self.head(MAJOR_MAP, len as u64)
+    }
+}
+
+impl<'a> Snapshot<'a> {
+    /// Encodes the snapshot into the specified buffer, returning the
+    /// length of the encoding
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut e = Encoder::new(buf);
+
+        e.map(4)?;
+        e.uint(snapshot::DEVICE.into())?;
+        e.text(self.device.name())?;
+        e.uint(snapshot::ADDRESS.into())?;
+        e.uint(self.address.into())?;
+        e.uint(snapshot::TIMESTAMP.into())?;
+        e.uint(self.timestamp)?;
+        e.uint(snapshot::READINGS.into())?;
+        e.array(self.readings.len())?;
+
+        for reading in self.readings {
+            e.array(3)?;
+            e.uint(reading.code.into())?;
+
+            match reading.page {
+                Some(page) => e.uint(page.into())?,
+                None => e.null()?,
+            }
+
+            e.float(reading.value)?;
+        }
+
+        Ok(e.len())
+    }
+}
+
+/// Encodes the specified fault report into the specified buffer, returning
+/// the length of the encoding
+pub fn encode_fault(
+    report: &FaultReport,
+    buf: &mut [u8],
+) -> Result<usize, Error> {
+    let registers = [
+        (fault::STATUS_VOUT, report.vout.map(|s| s.0)),
+        (fault::STATUS_IOUT, report.iout.map(|s| s.0)),
+        (fault::STATUS_INPUT, report.input.map(|s| s.0)),
+        (fault::STATUS_TEMPERATURE, report.temperature.map(|s| s.0)),
+        (fault::STATUS_CML, report.cml.map(|s| s.0)),
+        (fault::STATUS_OTHER, report.other.map(|s| s.0)),
+        (fault::STATUS_MFR_SPECIFIC, report.mfr),
+        (fault::STATUS_FANS_1_2, report.fans),
+    ];
+
+    let mut e = Encoder::new(buf);
+
+    e.map(2 + registers.iter().filter(|(_, r)| r.is_some()).count())?;
+    e.uint(fault::ADDRESS.into())?;
+    e.uint(report.address.into())?;
+    e.uint(fault::STATUS_WORD.into())?;
+    e.uint(report.status.0.into())?;
+
+    for (key, val) in registers.iter() {
+        if let Some(val) = val {
+            e.uint((*key).into())?;
+            e.uint((*val).into())?;
+        }
+    }
+
+    Ok(e.len())
+}
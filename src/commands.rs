@@ -22,3 +22,108 @@ include!(concat!(env!("OUT_DIR"), "/commands.rs"));
 // This includes all device-specifics commands and their data payloads.
 //
 include!(concat!(env!("OUT_DIR"), "/devices.rs"));
+
+///
+/// COEFFICIENTS is a block write-block read process call:  the host writes
+/// the command code of interest along with the direction of the data, and
+/// the device responds with the DIRECT coefficients that apply to it (see
+/// Part II, Sec. 7.4).  As with other block payloads, the byte counts are
+/// not included in the slices here.
+///
+#[allow(non_snake_case)]
+pub mod COEFFICIENTS {
+    use crate::Coefficients;
+
+    /// The direction of the data for which coefficients are requested
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub enum Direction {
+        /// Coefficients for data written to the device
+        Write = 0,
+        /// Coefficients for data read from the device
+        Read = 1,
+    }
+
+    /// The payload written to the device
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Request {
+        /// The command code for which coefficients are requested
+        pub code: u8,
+        /// The direction of the data
+        pub direction: Direction,
+    }
+
+    /// The payload returned by the device
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Response(pub Coefficients);
+
+    pub const fn code() -> u8 {
+        super::CommandCode::COEFFICIENTS as u8
+    }
+
+    impl Request {
+        pub const fn len() -> usize {
+            2
+        }
+
+        pub fn from_slice(slice: &[u8]) -> Option<Self> {
+            if slice.len() < Self::len() {
+                return None;
+            }
+
+            let direction = match slice[1] {
+                0 => Direction::Write,
+                1 => Direction::Read,
+                _ => return None,
+            };
+
+            Some(Self {
+                code: slice[0],
+                direction,
+            })
+        }
+
+        pub fn to_slice(&self, slice: &mut [u8]) {
+            slice[0] = self.code;
+            slice[1] = self.direction as u8;
+        }
+    }
+
+    impl Response {
+        pub const fn len() -> usize {
+            5
+        }
+
+        pub fn from_slice(slice: &[u8]) -> Option<Self> {
+            if slice.len() < Self::len() {
+                return None;
+            }
+
+            Some(Self(Coefficients {
+                m: i16::from_le_bytes([slice[0], slice[1]]).into(),
+                b: i16::from_le_bytes([slice[2], slice[3]]),
+                R: slice[4] as i8,
+            }))
+        }
+
+        ///
+        /// Writes the response to the slice.  Note that the slope must fit
+        /// in the two bytes that it has on the wire; it is truncated if not.
+        ///
+        pub fn to_slice(&self, slice: &mut [u8]) {
+            let m = (self.0.m as i16).to_le_bytes();
+            let b = self.0.b.to_le_bytes();
+
+            slice[0] = m[0];
+            slice[1] = m[1];
+            slice[2] = b[0];
+            slice[3] = b[1];
+            slice[4] = self.0.R as u8;
+        }
+    }
+
+    impl From<Response> for Coefficients {
+        fn from(response: Response) -> Self {
+            response.0
+        }
+    }
+}
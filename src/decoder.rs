@@ -0,0 +1,407 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Decoding of PMBus transactions from wire-level I2C events, for use by
+//! (e.g.) logic analyzer plugins.  An [`Analyzer`] is fed [`Event`]s as they
+//! are seen on the bus -- starts, addresses, bytes, acknowledgements and
+//! stops -- and hands each completed transaction to a closure as a
+//! [`Transaction`], which can in turn be interpreted via
+//! [`Device::interpret`].  The analyzer tracks the PAGE and VOUT_MODE of
+//! each address as they are observed on the bus, so that values that
+//! depend on VOUT_MODE can be interpreted without reading it from the
+//! device.
+//!
+//! The operation of a transaction is that dictated by the device for the
+//! command code, falling back to a heuristic (see
+//! [`heuristic::guess_operation`]) if the device does not define the
+//! command or the transaction does not match it.  Whether a transaction
+//! carries a PEC is inferred from its length.  Each command of a group
+//! command (see [`crate::encode::group`]) is handed to the closure as its
+//! own transaction.
+//!
+
+use crate::commands::VOUT_MODE;
+use crate::heuristic;
+use crate::protocol::{self, PecStatus};
+use crate::{
+    CommandCode, Device, Error, Field, FromPrimitive, Operation,
+    VOutModeCommandData, Value,
+};
+
+/// The largest number of bytes in either phase of a transaction:  a
+/// command code, a byte count, a 255-byte block and a PEC
+pub const PHASE_MAX: usize = 258;
+
+/// The number of pages for which VOUT_MODE is tracked
+pub const PAGES: usize = 32;
+
+/// The number of 7-bit addresses
+const ADDRESSES: usize = 128;
+
+/// An event observed on the bus
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Event {
+    /// A start (or repeated start) condition
+    Start,
+    /// An address byte, with its 7-bit address and read/write bit
+    Address { address: u8, read: bool },
+    /// A data byte
+    Byte(u8),
+    /// An acknowledgement of the preceding address or data byte
+    Ack,
+    /// A negative acknowledgement of the preceding address or data byte
+    Nack,
+    /// A stop condition
+    Stop,
+}
+
+/// A completed transaction
+#[derive(Copy, Clone, Debug)]
+pub struct Transaction<'a> {
+    /// The 7-bit address of the device
+    pub address: u8,
+    /// The device at the address
+    pub device: Device,
+    /// The command code
+    pub code: u8,
+    /// The operation of the transaction
+    pub op: Operation,
+    /// True if the operation was inferred heuristically
+    pub heuristic: bool,
+    /// The payload, as per [`protocol::decode`]
+    pub payload: &'a [u8],
+    /// The status of the PEC
+    pub pec: PecStatus,
+    /// True if a byte written was not acknowledged by the device
+    pub nack: bool,
+    /// The page of the device at the time of the transaction, if known
+    pub page: Option<u8>,
+    /// The VOUT_MODE of the page at the time of the transaction, if known
+    pub vout_mode: Option<VOutModeCommandData>,
+}
+
+impl Transaction<'_> {
+    /// Returns true if this transaction wrote its payload to the device
+    pub fn write(&self) -> bool {
+        matches!(
+            self.op,
+            Operation::SendByte
+                | Operation::WriteByte
+                | Operation::WriteWord
+                | Operation::WriteWord32
+                | Operation::WriteBlock
+        )
+    }
+
+    ///
+    /// Interprets the payload of the transaction via [`Device::interpret`],
+    /// using the VOUT_MODE tracked for the page.  If the command's value is
+    /// in the format dictated by VOUT_MODE and VOUT_MODE has not been seen,
+    /// this fails with [`Error::InvalidMode`].
+    ///
+    pub fn interpret(
+        &self,
+        iter: impl FnMut(&dyn Field, &dyn Value),
+    ) -> Result<(), Error> {
+        let mode = match (self.vout_mode, self.device.format(self.code)) {
+            (Some(mode), _) => mode,
+            (None, Some(crate::Format::VOutMode))
+            | (None, Some(crate::Format::SignedVOutMode)) => {
+                return Err(Error::InvalidMode);
+            }
+            (None, _) => VOUT_MODE::CommandData(0),
+        };
+
+        self.device.interpret(self.code, self.payload, mode, iter)
+    }
+}
+
+#[derive(Copy, Clone)]
+struct AddressState {
+    device: Device,
+    page: Option<u8>,
+    vout_mode: [Option<VOutModeCommandData>; PAGES],
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum State {
+    /// Waiting for a start condition
+    Idle,
+    /// Waiting for an address following a start condition
+    Address,
+    /// Waiting for the acknowledgement of the address
+    AddressAck,
+    /// Transferring data bytes
+    Data,
+    /// Discarding events until the next stop condition
+    Discard,
+}
+
+///
+/// A state machine that decodes PMBus transactions from I2C events.  The
+/// analyzer retains its tracked state for every address, and is therefore
+/// several kilobytes in size.
+///
+pub struct Analyzer {
+    devices: [AddressState; ADDRESSES],
+    state: State,
+    address: u8,
+    reading: bool,
+    nack: bool,
+    written: [u8; PHASE_MAX],
+    wlen: usize,
+    read: [u8; PHASE_MAX],
+    rlen: usize,
+    payload: [u8; PHASE_MAX],
+}
+
+impl Analyzer {
+    /// Returns a new analyzer that assumes the specified device at every
+    /// address; see [`Analyzer::set_device`].
+    pub fn new(device: Device) -> Self {
+        Self {
+            devices: [AddressState {
+                device,
+                page: None,
+                vout_mode: [None; PAGES],
+            }; ADDRESSES],
+            state: State::Idle,
+            address: 0,
+            reading: false,
+            nack: false,
+            written: [0; PHASE_MAX],
+            wlen: 0,
+            read: [0; PHASE_MAX],
+            rlen: 0,
+            payload: [0; PHASE_MAX],
+        }
+    }
+
+    /// Sets the device at the specified 7-bit address
+    pub fn set_device(&mut self, address: u8, device: Device) {
+        if let Some(state) = self.devices.get_mut(address as usize) {
+            state.device = device;
+        }
+    }
+
+    /// Returns the page of the device at the specified address, if known
+    pub fn page(&self, address: u8) -> Option<u8> {
+        self.devices.get(address as usize).and_then(|s| s.page)
+    }
+
+    /// Returns the VOUT_MODE of the current page of the device at the
+    /// specified address, if known
+    pub fn vout_mode(&self, address: u8) -> Option<VOutModeCommandData> {
+        let state = self.devices.get(address as usize)?;
+        let page = state.page.unwrap_or(0) as usize;
+        state.vout_mode.get(page).copied().flatten()
+    }
+
+    ///
+    /// Feeds the next event into the analyzer.  When the event completes a
+    /// transaction, the specified closure is called with it -- or with the
+    /// error encountered decoding it, if it could not be decoded.
+    /// Transactions to addresses that are not acknowledged are discarded.
+    ///
+    pub fn feed(
+        &mut self,
+        event: Event,
+        mut emit: impl FnMut(Result<Transaction, Error>),
+    ) {
+        self.state = match (self.state, event) {
+            //
+            // A start in the midst of a transaction is a repeated start;
+            // a start in any other state (e.g., after an unacknowledged
+            // address) begins a new transaction.
+            //
+            (State::Data, Event::Start) => State::Address,
+            (_, Event::Start) => {
+                self.wlen = 0;
+                self.rlen = 0;
+                self.reading = false;
+                self.nack = false;
+                State::Address
+            }
+
+            (State::Address, Event::Address { address, read }) => {
+                if self.wlen == 0 {
+                    //
+                    // A PMBus transaction always begins with a write of
+                    // the command code.
+                    //
+                    if read || address as usize >= ADDRESSES {
+                        State::Discard
+                    } else {
+                        self.address = address;
+                        State::AddressAck
+                    }
+                } else if read && address == self.address && !self.reading {
+                    self.reading = true;
+                    State::AddressAck
+                } else if !read
+                    && !self.reading
+                    && (address as usize) < ADDRESSES
+                {
+                    //
+                    // A repeated start followed by a write address is the
+                    // next command of a group command; the command that
+                    // precedes it is complete.
+                    //
+                    self.complete(&mut emit);
+                    self.wlen = 0;
+                    self.nack = false;
+                    self.address = address;
+                    State::AddressAck
+                } else {
+                    State::Discard
+                }
+            }
+
+            (State::AddressAck, Event::Ack) => State::Data,
+
+            (State::Data, Event::Byte(byte)) => {
+                let (buf, len) = if self.reading {
+                    (&mut self.read, &mut self.rlen)
+                } else {
+                    (&mut self.written, &mut self.wlen)
+                };
+
+                match buf.get_mut(*len) {
+                    Some(b) => {
+                        *b = byte;
+                        *len += 1;
+                        State::Data
+                    }
+                    None => State::Discard,
+                }
+            }
+
+            (State::Data, Event::Ack) => State::Data,
+
+            (State::Data, Event::Nack) => {
+                //
+                // The final byte of a read is not acknowledged by the
+                // host; only a write that is not acknowledged is notable.
+                //
+                if !self.reading {
+                    self.nack = true;
+                }
+
+                State::Data
+            }
+
+            (State::Data, Event::Stop) => {
+                if self.wlen > 0 {
+                    self.complete(&mut emit);
+                }
+
+                State::Idle
+            }
+
+            (_, Event::Stop) => State::Idle,
+            (State::Idle, _) => State::Idle,
+            _ => State::Discard,
+        };
+    }
+
+    fn complete(&mut self, emit: &mut impl FnMut(Result<Transaction, Error>)) {
+        let reading = self.reading;
+        let written = &self.written[..self.wlen];
+        let read = &self.read[..self.rlen];
+        let buf = &mut self.payload;
+        let state = &mut self.devices[self.address as usize];
+        let code = written[0];
+
+        let mut op = Operation::Unknown;
+
+        state.device.command(code, |cmd| {
+            op = if reading {
+                cmd.read_op()
+            } else {
+                cmd.write_op()
+            };
+        });
+
+        //
+        // Try the device's operation without and then with a PEC; failing
+        // that, guess at the operation from the data.
+        //
+        let data = if reading { read } else { &written[1..] };
+        let guess = heuristic::guess_operation(data, !reading);
+
+        let mut found = None;
+        let mut error = Error::InvalidOperation;
+
+        for &(op, pec, heuristic) in
+            &[(op, false, false), (op, true, false), (guess, false, true)]
+        {
+            match protocol::decode(buf, self.address, op, written, read, pec) {
+                Ok(decoded) => {
+                    found = Some((op, heuristic, decoded));
+                    break;
+                }
+                Err(Error::InvalidOperation) => {}
+                Err(err) => {
+                    if error == Error::InvalidOperation {
+                        error = err;
+                    }
+                }
+            }
+        }
+
+        let (op, heuristic, decoded) = match found {
+            Some(found) => found,
+            None => {
+                emit(Err(error));
+                return;
+            }
+        };
+
+        let payload = &buf[..decoded.len];
+        let index = state.page.unwrap_or(0) as usize;
+
+        emit(Ok(Transaction {
+            address: self.address,
+            device: state.device,
+            code,
+            op,
+            heuristic,
+            payload,
+            pec: decoded.pec,
+            nack: self.nack,
+            page: state.page,
+            vout_mode: state.vout_mode.get(index).copied().flatten(),
+        }));
+
+        //
+        // Now track any change in PAGE or VOUT_MODE -- provided that the
+        // transaction is to be believed.
+        //
+        if self.nack || decoded.pec == PecStatus::Invalid || heuristic {
+            return;
+        }
+
+        match (CommandCode::from_u8(code), payload) {
+            (Some(CommandCode::PAGE), &[page]) => {
+                state.page = Some(page);
+            }
+            (Some(CommandCode::VOUT_MODE), &[mode]) => {
+                let mode = Some(VOUT_MODE::CommandData(mode));
+
+                match state.page {
+                    Some(0xff) => state.vout_mode = [mode; PAGES],
+                    _ => {
+                        if let Some(m) = state.vout_mode.get_mut(index) {
+                            *m = mode;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
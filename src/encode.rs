@@ -0,0 +1,166 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Encoding of write transactions into caller-provided buffers.  The bytes
+//! written are exactly those that follow the address byte on the wire --
+//! command code, byte count (for block writes), payload and (optionally)
+//! PEC -- allowing a DMA-based I2C driver to transmit directly from the
+//! buffer.  The address itself is generally sent by the I2C controller, but
+//! is required here as it is covered by the PEC.  The exception is a
+//! transaction of the Group Command Protocol (see [`group`]), which
+//! addresses several devices and therefore includes their address bytes.
+//!
+
+use crate::pec::Pec;
+use crate::CommandCode;
+use crate::Error;
+use crate::Operation;
+
+/// The largest payload that may be sent in a block write
+pub const BLOCK_MAX: usize = 255;
+
+///
+/// Encodes a write of the specified payload with the specified command code
+/// to the device at the specified (7-bit) address, returning the number of
+/// bytes written to `buf`.  The payload length must be exactly that
+/// dictated by the operation; for [`Operation::WriteBlock`], the payload
+/// must be between 1 and [`BLOCK_MAX`] bytes, and the byte count is
+/// inserted.  For [`Operation::ProcessCall`], only the write phase is
+/// encoded.
+///
+pub fn write(
+    buf: &mut [u8],
+    address: u8,
+    code: u8,
+    op: Operation,
+    payload: &[u8],
+    pec: bool,
+) -> Result<usize, Error> {
+    let (count, len) = match op {
+        Operation::SendByte => (false, 0),
+        Operation::WriteByte => (false, 1),
+        Operation::WriteWord | Operation::ProcessCall => (false, 2),
+        Operation::WriteWord32 => (false, 4),
+        Operation::WriteBlock => {
+            if payload.is_empty() || payload.len() > BLOCK_MAX {
+                return Err(Error::InvalidLength);
+            }
+
+            (true, payload.len())
+        }
+        _ => return Err(Error::InvalidOperation),
+    };
+
+    if payload.len() != len {
+        return Err(Error::InvalidLength);
+    }
+
+    let header = if count { 2 } else { 1 };
+    let total = header + len + if pec { 1 } else { 0 };
+
+    let buf = buf.get_mut(..total).ok_or(Error::BufferTooSmall)?;
+
+    buf[0] = code;
+
+    if count {
+        buf[1] = len as u8;
+    }
+
+    buf[header..header + len].copy_from_slice(payload);
+
+    if pec {
+        let mut pec = Pec::new();
+        pec.write_address(address);
+        pec.update(&buf[..total - 1]);
+        buf[total - 1] = pec.value();
+    }
+
+    Ok(total)
+}
+
+///
+/// Encodes a write of the specified payload for the specified (common)
+/// command, using its write operation; see [`write`].
+///
+pub fn write_command(
+    buf: &mut [u8],
+    address: u8,
+    code: CommandCode,
+    payload: &[u8],
+    pec: bool,
+) -> Result<usize, Error> {
+    write(buf, address, code as u8, code.write_op(), payload, pec)
+}
+
+/// A command to be sent as part of a group command; see [`group`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GroupCommand<'a> {
+    /// The 7-bit address of the device
+    pub address: u8,
+    /// The command code
+    pub code: u8,
+    /// The operation, which must be a write
+    pub op: Operation,
+    /// The payload to be written
+    pub payload: &'a [u8],
+}
+
+impl<'a> GroupCommand<'a> {
+    /// Returns a group command for the specified (common) command, using
+    /// its write operation
+    pub fn new(address: u8, code: CommandCode, payload: &'a [u8]) -> Self {
+        Self {
+            address,
+            code: code as u8,
+            op: code.write_op(),
+            payload,
+        }
+    }
+}
+
+///
+/// Encodes a transaction of the Group Command Protocol, in which commands
+/// are sent to several devices with a repeated start between each and a
+/// single stop at the end, such that the devices act on them at once.
+/// Unlike [`write`], the address byte (with its write bit) of each command
+/// is written to `buf`:  the encoded transaction consists of each command
+/// in turn, with a repeated start to precede each address byte after the
+/// first.  Each command must be a write other than a process call, and each
+/// device may be addressed at most once; if `pec` is set, each command
+/// carries its own PEC.  Returns the number of bytes written to `buf`.
+///
+pub fn group(
+    buf: &mut [u8],
+    commands: &[GroupCommand],
+    pec: bool,
+) -> Result<usize, Error> {
+    if commands.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+
+    for (i, cmd) in commands.iter().enumerate() {
+        if cmd.op == Operation::ProcessCall
+            || commands[..i].iter().any(|c| c.address == cmd.address)
+        {
+            return Err(Error::InvalidOperation);
+        }
+    }
+
+    let mut total = 0;
+
+    for cmd in commands {
+        let buf = buf.get_mut(total..).ok_or(Error::BufferTooSmall)?;
+        let (address, rest) =
+            buf.split_first_mut().ok_or(Error::BufferTooSmall)?;
+
+        *address = cmd.address << 1;
+        total +=
+            1 + write(rest, cmd.address, cmd.code, cmd.op, cmd.payload, pec)?;
+    }
+
+    Ok(total)
+}
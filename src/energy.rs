@@ -0,0 +1,111 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Average power from READ_EIN and READ_EOUT.  These commands return a
+//! block consisting of an energy accumulator (the sum of the raw power
+//! readings taken by the device), a count of the times that the accumulator
+//! has rolled over, and a count of the samples taken.  Neither reading alone
+//! says much; the average power over an interval is the growth in the
+//! accumulator divided by the number of samples taken in that interval,
+//! which is what [`average_power`] computes from two [`Sample`]s.
+//!
+
+use crate::units::Watts;
+use crate::{Coefficients, FloatCore, Real};
+
+/// The value at which the energy accumulator rolls over
+pub const ACCUMULATOR_ROLLOVER: u32 = 0x8000;
+
+/// A reading of READ_EIN or READ_EOUT
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sample {
+    /// The energy accumulator, in the raw units of the power reading
+    pub accumulator: u16,
+    /// The number of times that the accumulator has rolled over
+    pub rollover: u8,
+    /// The number of samples taken
+    pub samples: u32,
+    /// The width of the sample count, in bits
+    pub sample_bits: u8,
+}
+
+impl Sample {
+    ///
+    /// Parses a sample from a READ_EIN or READ_EOUT payload (without its
+    /// block count).  The payload is ordinarily 6 bytes, with a 24-bit
+    /// sample count; a 5-byte payload is taken to have a 16-bit sample
+    /// count.  Returns `None` if the payload is of any other length.
+    ///
+    pub fn from_slice(payload: &[u8]) -> Option<Self> {
+        let samples = match payload.len() {
+            5 | 6 => &payload[3..],
+            _ => return None,
+        };
+
+        Some(Self {
+            accumulator: u16::from_le_bytes([payload[0], payload[1]]),
+            rollover: payload[2],
+            samples: samples
+                .iter()
+                .rev()
+                .fold(0, |acc, &b| (acc << 8) | u32::from(b)),
+            sample_bits: (samples.len() * 8) as u8,
+        })
+    }
+
+    //
+    // The total accumulated, modulo the rollover of the rollover count.
+    //
+    fn total(&self) -> u32 {
+        u32::from(self.rollover) * ACCUMULATOR_ROLLOVER
+            + u32::from(self.accumulator)
+    }
+}
+
+///
+/// Returns the average of the raw power readings taken between the two
+/// samples, allowing for the rollover of each counter (but not for more
+/// than one rollover of the rollover count or of the sample count).
+/// Returns `None` if no samples were taken or if the samples have sample
+/// counts of differing widths.
+///
+pub fn average(prev: &Sample, cur: &Sample) -> Option<f32> {
+    if prev.sample_bits != cur.sample_bits {
+        return None;
+    }
+
+    let total_mask = (ACCUMULATOR_ROLLOVER << 8) - 1;
+    let sample_mask = ((1u64 << cur.sample_bits) - 1) as u32;
+
+    let energy = cur.total().wrapping_sub(prev.total()) & total_mask;
+    let samples = cur.samples.wrapping_sub(prev.samples) & sample_mask;
+
+    if samples == 0 {
+        None
+    } else {
+        Some(energy as f32 / samples as f32)
+    }
+}
+
+///
+/// Returns the average power between the two samples, given the DIRECT
+/// coefficients of the corresponding power reading (READ_PIN for READ_EIN;
+/// READ_POUT for READ_EOUT).  See [`average`].
+///
+pub fn average_power(
+    prev: &Sample,
+    cur: &Sample,
+    coefficients: Coefficients,
+) -> Option<Watts> {
+    let y = Real::from(average(prev, cur)?);
+    let m = coefficients.m as Real;
+    let b = Real::from(coefficients.b);
+    let exp: i32 = coefficients.R.into();
+    let ten: Real = 10.0;
+
+    Some(Watts(((y * FloatCore::powi(ten, -exp) - b) / m) as f32))
+}
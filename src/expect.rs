@@ -0,0 +1,283 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Expected-versus-actual checking of decoded commands, for use by (e.g.)
+//! hardware-in-the-loop test benches.  A test names the fields that it
+//! expects a command to have and the values that it expects them to take
+//! -- sentinels by name, analog values with a [`Tolerance`] -- and either
+//! [`check`]s a payload read from hardware against them or [`assert_fields`]
+//! on it, panicking with a message that names the command, the field, and
+//! the expected and actual values (with units).
+//!
+
+use crate::{Device, Error, VOutMode, Value};
+use core::fmt;
+use core::fmt::Write;
+
+/// The tolerance to allow in comparing a real value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Tolerance {
+    /// The value may differ by at most the specified amount
+    Absolute(f32),
+    /// The value may differ by at most the specified percentage of the
+    /// expected value
+    Percent(f32),
+}
+
+/// The value that a field is expected to take
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Expected<'a> {
+    /// A sentinel value, by name (e.g., `"On"`)
+    Sentinel(&'a str),
+    /// A raw value
+    Raw(u32),
+    /// A real value (e.g., a voltage), within the specified tolerance
+    Real(f32, Tolerance),
+}
+
+impl Expected<'_> {
+    /// Returns true if the specified value is as expected
+    pub fn matches(&self, value: &dyn Value) -> bool {
+        match self {
+            Expected::Sentinel(name) => {
+                !value.scalar() && value.name() == *name
+            }
+            Expected::Raw(raw) => value.raw() == *raw,
+            Expected::Real(expected, tolerance) => {
+                let actual = match value.as_float() {
+                    Some(actual) => actual,
+                    None => return false,
+                };
+
+                let allowed = match tolerance {
+                    Tolerance::Absolute(t) => *t,
+                    Tolerance::Percent(p) => (expected * p / 100.0).abs(),
+                };
+
+                (actual - expected).abs() <= allowed
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expected<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Sentinel(name) => write!(f, "{}", name),
+            Expected::Raw(raw) => write!(f, "0x{:x}", raw),
+            Expected::Real(val, Tolerance::Absolute(t)) => {
+                write!(f, "{} ± {}", val, t)
+            }
+            Expected::Real(val, Tolerance::Percent(p)) => {
+                write!(f, "{} ± {}%", val, p)
+            }
+        }
+    }
+}
+
+/// The length of the buffer into which found values are displayed; values
+/// that are longer than this are truncated.
+const FOUND_MAX: usize = 48;
+
+/// A value that was found, as it is displayed (and so with its units)
+#[derive(Copy, Clone)]
+pub struct Found {
+    buf: [u8; FOUND_MAX],
+    len: usize,
+    /// The raw value
+    pub raw: u32,
+    /// The real value, if any
+    pub real: Option<f32>,
+}
+
+impl Found {
+    fn new(value: &dyn Value) -> Self {
+        let mut found = Self {
+            buf: [0; FOUND_MAX],
+            len: 0,
+            raw: value.raw(),
+            real: value.as_float(),
+        };
+
+        let _ = write!(found, "{}", value);
+        found
+    }
+
+    /// Returns the value as it is displayed
+    pub fn as_str(&self) -> &str {
+        //
+        // We only ever write whole strings, but truncation may have split a
+        // character; we display only through the last whole one.
+        //
+        match core::str::from_utf8(&self.buf[..self.len]) {
+            Ok(s) => s,
+            Err(e) => {
+                core::str::from_utf8(&self.buf[..e.valid_up_to()]).unwrap()
+            }
+        }
+    }
+}
+
+impl Write for Found {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let n = s.len().min(FOUND_MAX - self.len);
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Found {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Found({:?})", self.as_str())
+    }
+}
+
+impl fmt::Display for Found {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A way in which a decoded command differs from what was expected
+#[derive(Copy, Clone, Debug)]
+pub enum Mismatch<'a> {
+    /// The payload could not be interpreted
+    Error(Error),
+    /// The command has no field of the specified name
+    Missing(&'a str),
+    /// The field has a value other than that expected
+    Value {
+        field: &'static str,
+        desc: &'static str,
+        expected: Expected<'a>,
+        found: Found,
+    },
+}
+
+impl fmt::Display for Mismatch<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Error(err) => write!(f, "failed to interpret: {:?}", err),
+            Mismatch::Missing(name) => write!(f, "no field {}", name),
+            Mismatch::Value {
+                field,
+                desc,
+                expected,
+                found,
+            } => write!(
+                f,
+                "field {} ({}): expected {}, found {}",
+                field, desc, expected, found
+            ),
+        }
+    }
+}
+
+///
+/// Checks the payload for the specified command code against the expected
+/// values, given as pairs of field names and [`Expected`] values.  (The
+/// field of a numeric command is named `"scalar"`.)  Calls `mismatch` for
+/// each way in which the payload differs, and returns the number of
+/// mismatches.
+///
+pub fn check<'a>(
+    device: Device,
+    code: u8,
+    payload: &[u8],
+    mode: impl VOutMode,
+    expected: &[(&'a str, Expected<'a>)],
+    mut mismatch: impl FnMut(Mismatch<'a>),
+) -> usize {
+    let mut count = 0;
+    let mode = || mode.vout_mode();
+
+    for &(name, expected) in expected {
+        let mut outcome = None;
+
+        let rval = device.interpret(code, payload, mode, |f, v| {
+            if f.name() != name {
+                return;
+            }
+
+            outcome = Some(if expected.matches(v) {
+                None
+            } else {
+                Some(Mismatch::Value {
+                    field: f.name(),
+                    desc: f.desc(),
+                    expected,
+                    found: Found::new(v),
+                })
+            });
+        });
+
+        if let Err(err) = rval {
+            mismatch(Mismatch::Error(err));
+            return 1;
+        }
+
+        match outcome {
+            Some(None) => continue,
+            Some(Some(m)) => mismatch(m),
+            None => mismatch(Mismatch::Missing(name)),
+        }
+
+        count += 1;
+    }
+
+    count
+}
+
+///
+/// Asserts that the payload for the specified command code is as expected
+/// (as per [`check`]), panicking with a message that names the device, the
+/// command, and the first field that differs (along with its expected and
+/// actual values) if it is not.
+///
+#[track_caller]
+pub fn assert_fields<'a>(
+    device: Device,
+    code: u8,
+    payload: &[u8],
+    mode: impl VOutMode,
+    expected: &[(&'a str, Expected<'a>)],
+) {
+    let mut first = None;
+
+    let count = check(device, code, payload, mode, expected, |m| {
+        if first.is_none() {
+            first = Some(m);
+        }
+    });
+
+    if let Some(m) = first {
+        let mut name = "<unknown>";
+        device.command(code, |cmd| name = cmd.name());
+
+        panic!(
+            "{} {} (0x{:02x}): {}{}",
+            device.name(),
+            name,
+            code,
+            m,
+            More(count - 1)
+        );
+    }
+}
+
+struct More(usize);
+
+impl fmt::Display for More {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => Ok(()),
+            1 => write!(f, " (and 1 other mismatch)"),
+            n => write!(f, " (and {} other mismatches)", n),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Fan control.  Whether a fan's FAN_COMMAND is expressed in RPM or as a
+//! duty cycle is dictated by its configuration in FAN_CONFIG_1_2 or
+//! FAN_CONFIG_3_4; a [`FanConfig`] decodes this configuration, and converts
+//! a [`FanTarget`] into the value to be commanded (and back), allowing fan
+//! control loops to be written in terms of their target regardless of how
+//! a particular device is configured.
+//!
+
+use crate::commands::{FAN_CONFIG_1_2, FAN_CONFIG_3_4};
+use crate::units::{Percent, RPM};
+use crate::Error;
+use crate::Linear11;
+
+/// The units in which a fan is commanded
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FanMode {
+    Duty,
+    Rpm,
+}
+
+/// A target for a fan, either in RPM or as a duty cycle
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FanTarget {
+    Rpm(RPM),
+    Duty(Percent),
+}
+
+/// The configuration of a single fan
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FanConfig {
+    /// True if the fan is installed
+    pub installed: bool,
+    /// The units in which the fan is commanded
+    pub mode: FanMode,
+    /// The number of tachometer pulses per revolution
+    pub pulses: u8,
+}
+
+macro_rules! fan_config {
+    ($fn:ident, $cmd:ident, $fan:literal,
+     $get_installed:ident, $installed:ident,
+     $get_mode:ident, $mode:ident,
+     $get_pulses:ident, $pulses:ident) => {
+        #[doc = concat!(
+            "Decodes the configuration of fan ", $fan, " from ",
+            stringify!($cmd), ", returning `None` if it cannot be decoded"
+        )]
+        pub fn $fn(config: $cmd::CommandData) -> Option<Self> {
+            use $cmd::{$installed, $mode, $pulses};
+
+            Some(Self {
+                installed: config.$get_installed()? == $installed::Installed,
+                mode: match config.$get_mode()? {
+                    $mode::RPM => FanMode::Rpm,
+                    $mode::Duty => FanMode::Duty,
+                },
+                pulses: match config.$get_pulses()? {
+                    $pulses::One => 1,
+                    $pulses::Two => 2,
+                    $pulses::Three => 3,
+                    $pulses::Four => 4,
+                },
+            })
+        }
+    };
+}
+
+impl FanConfig {
+    fan_config!(
+        fan1,
+        FAN_CONFIG_1_2,
+        "1",
+        get_fan_1_installed,
+        Fan1Installed,
+        get_fan_1_mode,
+        Fan1Mode,
+        get_fan_1_pulses,
+        Fan1Pulses
+    );
+
+    fan_config!(
+        fan2,
+        FAN_CONFIG_1_2,
+        "2",
+        get_fan_2_installed,
+        Fan2Installed,
+        get_fan_2_mode,
+        Fan2Mode,
+        get_fan_2_pulses,
+        Fan2Pulses
+    );
+
+    fan_config!(
+        fan3,
+        FAN_CONFIG_3_4,
+        "3",
+        get_fan_3_installed,
+        Fan3Installed,
+        get_fan_3_mode,
+        Fan3Mode,
+        get_fan_3_pulses,
+        Fan3Pulses
+    );
+
+    fan_config!(
+        fan4,
+        FAN_CONFIG_3_4,
+        "4",
+        get_fan_4_installed,
+        Fan4Installed,
+        get_fan_4_mode,
+        Fan4Mode,
+        get_fan_4_pulses,
+        Fan4Pulses
+    );
+
+    ///
+    /// Returns the value to be commanded via FAN_COMMAND to achieve the
+    /// specified target:  an RPM or a duty cycle (in percent), as dictated
+    /// by the fan's mode.  Converting between RPM and duty cycle assumes
+    /// that fan speed is proportional to duty cycle, and requires the
+    /// fan's speed at 100% duty cycle; if this is needed but not provided,
+    /// [`Error::Unsupported`] is returned.
+    ///
+    pub fn command(
+        &self,
+        target: FanTarget,
+        max: Option<RPM>,
+    ) -> Result<f32, Error> {
+        if !self.installed {
+            return Err(Error::Unsupported);
+        }
+
+        let (val, limit) = match target {
+            FanTarget::Rpm(rpm) => (rpm.0, max.map_or(f32::MAX, |m| m.0)),
+            FanTarget::Duty(duty) => (duty.0, 100.0),
+        };
+
+        if !(0.0..=limit).contains(&val) {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        match (self.mode, target) {
+            (FanMode::Rpm, FanTarget::Rpm(rpm)) => Ok(rpm.0),
+            (FanMode::Duty, FanTarget::Duty(duty)) => Ok(duty.0),
+            (FanMode::Rpm, FanTarget::Duty(duty)) => match max {
+                Some(max) => Ok(max.0 * duty.0 / 100.0),
+                None => Err(Error::Unsupported),
+            },
+            (FanMode::Duty, FanTarget::Rpm(rpm)) => match max {
+                Some(max) if max.0 > 0.0 => Ok(rpm.0 / max.0 * 100.0),
+                _ => Err(Error::Unsupported),
+            },
+        }
+    }
+
+    /// Returns the FAN_COMMAND payload to achieve the specified target,
+    /// for devices that express FAN_COMMAND in LINEAR11 format; see
+    /// [`FanConfig::command`].
+    pub fn command_linear11(
+        &self,
+        target: FanTarget,
+        max: Option<RPM>,
+    ) -> Result<Linear11, Error> {
+        let val = self.command(target, max)?;
+        Linear11::from_real(val).ok_or(Error::ValueOutOfRange)
+    }
+
+    /// Returns the target corresponding to a value commanded via
+    /// FAN_COMMAND, as interpreted according to the fan's mode
+    pub fn target(&self, command: f32) -> FanTarget {
+        match self.mode {
+            FanMode::Rpm => FanTarget::Rpm(RPM(command)),
+            FanMode::Duty => FanTarget::Duty(Percent(command)),
+        }
+    }
+}
@@ -0,0 +1,428 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! A C interface to the reflection interface, enabled via the `ffi` feature
+//! and declared in `include/pmbus.h`.  This allows for host tooling written
+//! in C or C++ to use the definitions in this crate rather than maintaining
+//! its own.  As this crate is `no_std`, it does not itself build a library
+//! to link against; a consumer should depend on this crate (with the `ffi`
+//! feature) from a `staticlib` or `cdylib` crate of its own.
+//!
+//! Devices are named by NUL-terminated strings (as returned by
+//! [`Device::name`]); names, descriptions and values are passed to
+//! callbacks as pointers and lengths, and are *not* NUL-terminated.  All
+//! functions return 0 on success, or a negative [`Error`] code (see
+//! [`error_code`], and `PMBUS_ERR_*` in `include/pmbus.h`) on failure.
+//!
+
+use crate::{
+    commands, encode, Device, Error, Field, Operation, Replacement, Value,
+};
+use core::ffi::{c_char, c_void, CStr};
+use core::fmt::Write;
+
+/// A field (and, if interpreting data, its value) as passed to a
+/// [`FieldCallback`]
+#[repr(C)]
+pub struct PmbusField {
+    pub name: *const u8,
+    pub name_len: usize,
+    pub desc: *const u8,
+    pub desc_len: usize,
+    pub bitpos: u8,
+    pub width: u8,
+    /// The raw value of the field, or 0 if there is no value
+    pub raw: u32,
+    /// The value of the field as it would be displayed, or null if there
+    /// is no value
+    pub value: *const u8,
+    pub value_len: usize,
+}
+
+/// A callback that is called with a caller-provided argument and a field
+pub type FieldCallback =
+    Option<unsafe extern "C" fn(arg: *mut c_void, field: *const PmbusField)>;
+
+/// The length of the buffer into which values are displayed; values that
+/// are longer than this are truncated (on a character boundary).
+const VALUE_MAX: usize = 64;
+
+struct ValueBuf {
+    buf: [u8; VALUE_MAX],
+    len: usize,
+    truncated: bool,
+}
+
+impl Write for ValueBuf {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if self.truncated {
+            return Err(core::fmt::Error);
+        }
+
+        //
+        // If the string doesn't fit, we truncate it on a character boundary
+        // (lest we split a multi-byte character like "°" or "Ω") and refuse
+        // all subsequent writes, lest they be appended to a truncated value.
+        //
+        let room = VALUE_MAX - self.len;
+        let mut n = s.len().min(room);
+
+        while !s.is_char_boundary(n) {
+            n -= 1;
+        }
+
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+
+        if n < s.len() {
+            self.truncated = true;
+            return Err(core::fmt::Error);
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// Returns the (negative) code for the specified error.  These codes are
+/// part of the C interface (as `PMBUS_ERR_*` in `include/pmbus.h`), and
+/// must therefore never change.
+///
+pub fn error_code(err: Error) -> i32 {
+    match err {
+        Error::ShortData => -1,
+        Error::InvalidCode => -2,
+        Error::ValueOutOfRange => -3,
+        Error::InvalidMode => -4,
+        Error::InvalidSentinel => -5,
+        Error::MissingCoefficients => -6,
+        Error::InvalidReplacement => -7,
+        Error::OverflowReplacement => -8,
+        Error::InvalidField => -9,
+        Error::InvalidOperation => -10,
+        Error::InvalidLength => -11,
+        Error::BufferTooSmall => -12,
+        Error::Unsupported => -13,
+        Error::UnsupportedRevision => -14,
+        Error::BadPec => -15,
+    }
+}
+
+fn result(rval: Result<(), Error>) -> i32 {
+    match rval {
+        Ok(_) => 0,
+        Err(err) => error_code(err),
+    }
+}
+
+unsafe fn device(name: *const c_char) -> Result<Device, Error> {
+    if name.is_null() {
+        return Err(Error::InvalidCode);
+    }
+
+    CStr::from_ptr(name)
+        .to_str()
+        .ok()
+        .and_then(Device::from_str)
+        .ok_or(Error::InvalidCode)
+}
+
+unsafe fn slice<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if ptr.is_null() {
+        &[]
+    } else {
+        core::slice::from_raw_parts(ptr, len)
+    }
+}
+
+unsafe fn slice_mut<'a>(ptr: *mut u8, len: usize) -> &'a mut [u8] {
+    if ptr.is_null() {
+        &mut []
+    } else {
+        core::slice::from_raw_parts_mut(ptr, len)
+    }
+}
+
+fn call(
+    cb: unsafe extern "C" fn(*mut c_void, *const PmbusField),
+    arg: *mut c_void,
+    field: &dyn Field,
+    value: Option<&dyn Value>,
+) {
+    let (pos, width) = field.bits();
+    let mut buf = ValueBuf {
+        buf: [0; VALUE_MAX],
+        len: 0,
+        truncated: false,
+    };
+
+    let raw = match value {
+        Some(value) => {
+            let _ = write!(buf, "{}", value);
+            value.raw()
+        }
+        None => 0,
+    };
+
+    let f = PmbusField {
+        name: field.name().as_ptr(),
+        name_len: field.name().len(),
+        desc: field.desc().as_ptr(),
+        desc_len: field.desc().len(),
+        bitpos: pos.0,
+        width: width.0,
+        raw,
+        value: match value {
+            Some(_) => buf.buf.as_ptr(),
+            None => core::ptr::null(),
+        },
+        value_len: buf.len,
+    };
+
+    unsafe { cb(arg, &f) };
+}
+
+///
+/// Interprets the payload for the specified command code, calling `cb` for
+/// each field and its value.  `vout_mode` is the contents of VOUT_MODE.
+///
+/// # Safety
+///
+/// `device` must be a valid NUL-terminated string, and `payload` must point
+/// to at least `len` bytes.
+///
+#[no_mangle]
+pub unsafe extern "C" fn pmbus_decode(
+    device: *const c_char,
+    code: u8,
+    payload: *const u8,
+    len: usize,
+    vout_mode: u8,
+    cb: FieldCallback,
+    arg: *mut c_void,
+) -> i32 {
+    result((|| {
+        let device = self::device(device)?;
+        let cb = cb.ok_or(Error::InvalidOperation)?;
+        let mode = || commands::VOUT_MODE::CommandData(vout_mode);
+
+        device.interpret(code, slice(payload, len), mode, |f, v| {
+            call(cb, arg, f, Some(v))
+        })
+    })())
+}
+
+///
+/// Iterates over the fields of the specified command code, calling `cb` for
+/// each (with no value).
+///
+/// # Safety
+///
+/// `device` must be a valid NUL-terminated string.
+///
+#[no_mangle]
+pub unsafe extern "C" fn pmbus_fields(
+    device: *const c_char,
+    code: u8,
+    cb: FieldCallback,
+    arg: *mut c_void,
+) -> i32 {
+    result((|| {
+        let device = self::device(device)?;
+        let cb = cb.ok_or(Error::InvalidOperation)?;
+
+        device.fields(code, |f| call(cb, arg, f, None))
+    })())
+}
+
+///
+/// Retrieves the raw value of the named field from the payload for the
+/// specified command code.
+///
+/// # Safety
+///
+/// `device` and `field` must be valid NUL-terminated strings, `payload`
+/// must point to at least `len` bytes, and `raw` must be valid for writes.
+///
+#[no_mangle]
+pub unsafe extern "C" fn pmbus_get_field(
+    device: *const c_char,
+    code: u8,
+    payload: *const u8,
+    len: usize,
+    vout_mode: u8,
+    field: *const c_char,
+    raw: *mut u32,
+) -> i32 {
+    result((|| {
+        let device = self::device(device)?;
+        let name = field_name(field)?;
+        let mode = || commands::VOUT_MODE::CommandData(vout_mode);
+        let mut found = None;
+
+        device.interpret(code, slice(payload, len), mode, |f, v| {
+            if f.name() == name {
+                found = Some(v.raw());
+            }
+        })?;
+
+        *raw = found.ok_or(Error::InvalidField)?;
+        Ok(())
+    })())
+}
+
+unsafe fn field_name<'a>(field: *const c_char) -> Result<&'a str, Error> {
+    if field.is_null() {
+        return Err(Error::InvalidField);
+    }
+
+    CStr::from_ptr(field)
+        .to_str()
+        .map_err(|_| Error::InvalidField)
+}
+
+unsafe fn set_field(
+    device: *const c_char,
+    code: u8,
+    payload: *mut u8,
+    len: usize,
+    vout_mode: u8,
+    field: *const c_char,
+    replacement: Replacement,
+) -> Result<(), Error> {
+    let device = self::device(device)?;
+    let name = field_name(field)?;
+    let mode = || commands::VOUT_MODE::CommandData(vout_mode);
+    let mut found = false;
+
+    device.mutate(code, slice_mut(payload, len), mode, |f, _| {
+        if f.name() == name {
+            found = true;
+            Some(replacement)
+        } else {
+            None
+        }
+    })?;
+
+    if found {
+        Ok(())
+    } else {
+        Err(Error::InvalidField)
+    }
+}
+
+///
+/// Sets the named field in the payload for the specified command code to
+/// the specified integer value (which, for fields that have sentinels, is
+/// the raw value of the sentinel).
+///
+/// # Safety
+///
+/// `device` and `field` must be valid NUL-terminated strings, and `payload`
+/// must point to at least `len` bytes.
+///
+#[no_mangle]
+pub unsafe extern "C" fn pmbus_set_field_integer(
+    device: *const c_char,
+    code: u8,
+    payload: *mut u8,
+    len: usize,
+    vout_mode: u8,
+    field: *const c_char,
+    value: u32,
+) -> i32 {
+    let replacement = Replacement::Integer(value);
+    result(set_field(
+        device,
+        code,
+        payload,
+        len,
+        vout_mode,
+        field,
+        replacement,
+    ))
+}
+
+///
+/// Sets the named field in the payload for the specified command code to
+/// the specified real value.
+///
+/// # Safety
+///
+/// `device` and `field` must be valid NUL-terminated strings, and `payload`
+/// must point to at least `len` bytes.
+///
+#[no_mangle]
+pub unsafe extern "C" fn pmbus_set_field_float(
+    device: *const c_char,
+    code: u8,
+    payload: *mut u8,
+    len: usize,
+    vout_mode: u8,
+    field: *const c_char,
+    value: f32,
+) -> i32 {
+    let replacement = Replacement::Float(value);
+    result(set_field(
+        device,
+        code,
+        payload,
+        len,
+        vout_mode,
+        field,
+        replacement,
+    ))
+}
+
+///
+/// Encodes a write of the specified payload for the specified command code
+/// to the device at the specified (7-bit) address into `buf`, as described
+/// in [`encode::write`].  On success, the length of the transaction is
+/// stored in `out`.
+///
+/// # Safety
+///
+/// `device` must be a valid NUL-terminated string, `payload` must point to
+/// at least `len` bytes, `buf` must point to at least `buflen` bytes, and
+/// `out` must be valid for writes.
+///
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn pmbus_encode(
+    device: *const c_char,
+    address: u8,
+    code: u8,
+    payload: *const u8,
+    len: usize,
+    pec: bool,
+    buf: *mut u8,
+    buflen: usize,
+    out: *mut usize,
+) -> i32 {
+    result((|| {
+        let device = self::device(device)?;
+        let mut op = None;
+
+        device.command(code, |cmd| op = Some(cmd.write_op()));
+
+        let op = match op {
+            Some(Operation::Unknown) | None => return Err(Error::InvalidCode),
+            Some(op) => op,
+        };
+
+        *out = encode::write(
+            slice_mut(buf, buflen),
+            address,
+            code,
+            op,
+            slice(payload, len),
+            pec,
+        )?;
+
+        Ok(())
+    })())
+}
@@ -0,0 +1,46 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+use crate::Coefficients;
+use crate::VidCode;
+
+///
+/// The format of a command's data payload, as described by the device's
+/// definition.  This allows the payload of an arbitrary command to be
+/// understood without interpreting it; see [`crate::Device::format`].
+///
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Format {
+    /// LINEAR11 (Part II, Sec. 7.3)
+    Linear11,
+    /// ULINEAR16 with a fixed exponent
+    ULinear16,
+    /// SLINEAR16 with a fixed exponent
+    SLinear16,
+    /// IEEE 754 half-precision floating point
+    Half,
+    /// DIRECT (Part II, Sec. 7.4) with the given coefficients
+    Direct(Coefficients),
+    /// DIRECT with coefficients that must be determined at run-time
+    RuntimeDirect,
+    /// Unsigned, in the format dictated by VOUT_MODE
+    VOutMode,
+    /// Signed, in the format dictated by VOUT_MODE
+    SignedVOutMode,
+    /// A VID code of the given protocol
+    Vid(VidCode),
+    /// Unsigned fixed point, with the real value being the raw value
+    /// divided by the given factor
+    FixedPoint(f32),
+    /// Signed fixed point, with the real value being the raw value divided
+    /// by the given factor
+    SignedFixedPoint(f32),
+    /// A structured register consisting of fields
+    Bitfield,
+    /// A value without further interpretation
+    Raw,
+}
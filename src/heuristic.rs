@@ -0,0 +1,238 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Best-effort decoding of commands that a device does not define.  A bus
+//! trace will often contain command codes that are not known for the
+//! selected [`Device`] (e.g., undocumented manufacturer-specific commands);
+//! rather than decode nothing, [`interpret`] can be used in lieu of
+//! [`Device::interpret`] to guess at the operation from the shape of the
+//! transaction and at the value as LINEAR11, ULINEAR16 and ASCII.  These
+//! guesses are just that:  every field and value that results from them is
+//! named and described as heuristic, and has an identifier of 0.
+//!
+
+use crate::{
+    Bitpos, Bitwidth, Device, Error, Field, Linear11, Operation, ULinear16,
+    ULinear16Exponent, VOutMode, Value,
+};
+
+///
+/// Guesses the operation from the length of the payload observed in a
+/// transaction, and whether it was a write.  Payloads of 1, 2 and 4 bytes
+/// are taken to be byte, word and 32-bit word operations, respectively,
+/// unless (for a 2- or 4-byte payload) the first byte is a plausible block
+/// count; any other payload is a block operation if its first byte is the
+/// count of the bytes that follow it.
+///
+pub fn guess_operation(payload: &[u8], write: bool) -> Operation {
+    let block = match payload.first() {
+        Some(&count) => count as usize + 1 == payload.len(),
+        None => false,
+    };
+
+    match (payload.len(), write) {
+        (0, true) => Operation::SendByte,
+        (1, true) => Operation::WriteByte,
+        (1, false) => Operation::ReadByte,
+        (2, true) if !block => Operation::WriteWord,
+        (2, false) if !block => Operation::ReadWord,
+        (4, true) if !block => Operation::WriteWord32,
+        (4, false) if !block => Operation::ReadWord32,
+        (_, true) if block => Operation::WriteBlock,
+        (_, false) if block => Operation::ReadBlock,
+        _ => Operation::Unknown,
+    }
+}
+
+/// A field that results from a heuristic interpretation
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HeuristicField {
+    /// The operation, as guessed by [`guess_operation`]
+    Operation,
+    /// A word interpreted as LINEAR11
+    Linear11,
+    /// A word interpreted as ULINEAR16, using the exponent in VOUT_MODE
+    ULinear16,
+    /// A block (or other payload) that consists entirely of printable
+    /// ASCII characters
+    Ascii,
+}
+
+impl Field for HeuristicField {
+    fn bitfield(&self) -> bool {
+        false
+    }
+
+    fn bits(&self) -> (Bitpos, Bitwidth) {
+        match self {
+            HeuristicField::Linear11 | HeuristicField::ULinear16 => {
+                (Bitpos(0), Bitwidth(16))
+            }
+            HeuristicField::Operation | HeuristicField::Ascii => {
+                (Bitpos(0), Bitwidth(0))
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            HeuristicField::Operation => "HeuristicOperation",
+            HeuristicField::Linear11 => "HeuristicLinear11",
+            HeuristicField::ULinear16 => "HeuristicULinear16",
+            HeuristicField::Ascii => "HeuristicAscii",
+        }
+    }
+
+    fn desc(&self) -> &'static str {
+        match self {
+            HeuristicField::Operation => "Operation (heuristic)",
+            HeuristicField::Linear11 => "Value as LINEAR11 (heuristic)",
+            HeuristicField::ULinear16 => "Value as ULINEAR16 (heuristic)",
+            HeuristicField::Ascii => "Value as ASCII (heuristic)",
+        }
+    }
+
+    fn id(&self) -> u32 {
+        0
+    }
+}
+
+/// A value that results from a heuristic interpretation
+#[derive(Copy, Clone, Debug)]
+pub enum HeuristicValue<'a> {
+    Operation(Operation),
+    /// A real value, along with the raw word it was interpreted from
+    Real(f32, u16),
+    Ascii(&'a str),
+}
+
+impl Value for HeuristicValue<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            HeuristicValue::Operation(_) => "operation",
+            HeuristicValue::Real(..) => "real",
+            HeuristicValue::Ascii(_) => "ascii",
+        }
+    }
+
+    fn desc(&self) -> &'static str {
+        match self {
+            HeuristicValue::Operation(_) => "guessed operation",
+            HeuristicValue::Real(..) => "guessed real value",
+            HeuristicValue::Ascii(_) => "guessed string",
+        }
+    }
+
+    fn raw(&self) -> u32 {
+        match self {
+            HeuristicValue::Real(_, raw) => (*raw).into(),
+            HeuristicValue::Operation(_) | HeuristicValue::Ascii(_) => 0,
+        }
+    }
+
+    fn scalar(&self) -> bool {
+        matches!(self, HeuristicValue::Real(..))
+    }
+
+    fn as_float(&self) -> Option<f32> {
+        match self {
+            HeuristicValue::Real(val, _) => Some(*val),
+            HeuristicValue::Operation(_) | HeuristicValue::Ascii(_) => None,
+        }
+    }
+}
+
+impl core::fmt::Display for HeuristicValue<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HeuristicValue::Operation(op) => write!(f, "{:?}", op),
+            HeuristicValue::Real(val, _) => write!(f, "{}", val),
+            HeuristicValue::Ascii(s) => write!(f, "\"{}\"", s),
+        }
+    }
+}
+
+fn ascii(payload: &[u8]) -> Option<&str> {
+    if !payload.is_empty() && payload.iter().all(|&b| (0x20..0x7f).contains(&b))
+    {
+        core::str::from_utf8(payload).ok()
+    } else {
+        None
+    }
+}
+
+///
+/// Heuristically interprets a payload without regard to any definition,
+/// calling `iter` with the guessed operation and with each plausible
+/// interpretation of the payload.  A word is interpreted as LINEAR11, and
+/// (if VOUT_MODE indicates ULINEAR16) as ULINEAR16; a payload consisting of
+/// printable characters (following the count, for a block) is interpreted
+/// as ASCII.
+///
+pub fn guess(
+    payload: &[u8],
+    write: bool,
+    mode: impl VOutMode,
+    mut iter: impl FnMut(&dyn Field, &dyn Value),
+) {
+    let op = guess_operation(payload, write);
+
+    iter(&HeuristicField::Operation, &HeuristicValue::Operation(op));
+
+    match op {
+        Operation::ReadWord | Operation::WriteWord => {
+            let raw = u16::from_le_bytes([payload[0], payload[1]]);
+            let val = Linear11(raw).to_real();
+
+            iter(&HeuristicField::Linear11, &HeuristicValue::Real(val, raw));
+
+            if let Ok(exp) = ULinear16Exponent::from_mode(&mode.vout_mode()) {
+                let val = ULinear16(raw, exp).to_real();
+                let field = HeuristicField::ULinear16;
+                iter(&field, &HeuristicValue::Real(val, raw));
+            }
+        }
+
+        Operation::ReadBlock | Operation::WriteBlock => {
+            if let Some(s) = ascii(&payload[1..]) {
+                iter(&HeuristicField::Ascii, &HeuristicValue::Ascii(s));
+            }
+        }
+
+        _ => {
+            if let Some(s) = ascii(payload) {
+                iter(&HeuristicField::Ascii, &HeuristicValue::Ascii(s));
+            }
+        }
+    }
+}
+
+///
+/// Interprets the payload for the specified command code as
+/// [`Device::interpret`] does, but falls back to a heuristic interpretation
+/// (as per [`guess`]) if the device does not define the command.  Returns
+/// true if the interpretation was heuristic.
+///
+pub fn interpret(
+    device: Device,
+    code: u8,
+    payload: &[u8],
+    write: bool,
+    mode: impl VOutMode,
+    mut iter: impl FnMut(&dyn Field, &dyn Value),
+) -> Result<bool, Error> {
+    let mode = || mode.vout_mode();
+
+    match device.interpret(code, payload, mode, &mut iter) {
+        Ok(()) => Ok(false),
+        Err(Error::InvalidCode) => {
+            guess(payload, write, mode, iter);
+            Ok(true)
+        }
+        Err(err) => Err(err),
+    }
+}
@@ -0,0 +1,269 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Hot-swap controller configuration.  Hot-swap controllers limit the
+//! inrush current into their load, but the registers that configure this
+//! (and the behavior after a fault) differ from vendor to vendor.  The
+//! [`HotSwap`] trait is implemented for the types of supported hot-swap
+//! controllers (e.g., [`Adm1272`] and [`Lm25066`]), and translates a
+//! desired [`Inrush`] configuration into the requisite reads and writes.
+//!
+
+use crate::commands::adm1272::{self, Adm1272};
+use crate::commands::adm1275::Adm1275;
+use crate::commands::lm25066::{self, Lm25066};
+use crate::commands::lm5066i::Lm5066I;
+use crate::units::Amperes;
+use crate::Error;
+use crate::FloatCore;
+use crate::PmbusDevice;
+use core::time::Duration;
+
+/// The behavior of a hot-swap controller after it has turned off due to a
+/// fault
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Retry {
+    /// Remain off until commanded on
+    Latch,
+    /// Retry after the specified delay
+    After(Duration),
+}
+
+/// A desired inrush configuration
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Inrush {
+    /// The current limit of the hot-swap controller, as determined by its
+    /// sense resistor (and any external current limit setting)
+    pub current_limit: Amperes,
+    /// The maximum current to allow during startup
+    pub max_current: Amperes,
+    /// The time to allow the controller to limit current before faulting,
+    /// if it is programmable
+    pub fault_timer: Option<Duration>,
+    /// The behavior after a fault
+    pub retry: Retry,
+}
+
+/// An error in configuring a hot-swap controller
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HotSwapError<E> {
+    /// The configuration cannot be represented by the device:  a value is
+    /// out of range ([`Error::ValueOutOfRange`]) or a setting is not
+    /// programmable ([`Error::Unsupported`])
+    Invalid(Error),
+    /// An error from the caller's transport
+    Transport(E),
+}
+
+/// A hot-swap controller
+pub trait HotSwap: PmbusDevice {
+    ///
+    /// Configures the device for the specified inrush settings.  `read` is
+    /// called with a command code and a buffer to read it into, for any
+    /// register that must be read to be modified; `write` is called with a
+    /// command code and its payload for each register to be written.  The
+    /// configuration (and any register read) is validated in its entirety
+    /// before any register is written.
+    ///
+    fn configure_inrush<E>(
+        inrush: &Inrush,
+        read: impl FnMut(u8, &mut [u8]) -> Result<(), E>,
+        write: impl FnMut(u8, &[u8]) -> Result<(), E>,
+    ) -> Result<(), HotSwapError<E>>;
+}
+
+impl HotSwap for Adm1272 {
+    fn configure_inrush<E>(
+        inrush: &Inrush,
+        mut read: impl FnMut(u8, &mut [u8]) -> Result<(), E>,
+        mut write: impl FnMut(u8, &[u8]) -> Result<(), E>,
+    ) -> Result<(), HotSwapError<E>> {
+        use adm1272::{DEVICE_CONFIG, RESTART_TIME, STRT_UP_IOUT_LIM};
+
+        let invalid = HotSwapError::Invalid;
+
+        //
+        // The ADM1272's fault timer is set by an external capacitor.
+        //
+        if inrush.fault_timer.is_some() {
+            return Err(invalid(Error::Unsupported));
+        }
+
+        //
+        // The startup current limit is expressed in sixteenths of the
+        // current limit, from 1/16 through 16/16; we select the largest
+        // limit that does not exceed the maximum.
+        //
+        let frac = inrush.max_current.0 / inrush.current_limit.0;
+
+        if !frac.is_finite() || frac < 1.0 / 16.0 {
+            return Err(invalid(Error::ValueOutOfRange));
+        }
+
+        let sixteenths = FloatCore::floor(frac * 16.0).min(16.0) as u16;
+
+        let mut limit = STRT_UP_IOUT_LIM::CommandData(0);
+        limit
+            .set_startup_i_out_limit(sixteenths - 1)
+            .map_err(invalid)?;
+
+        //
+        // The restart time is 100 ms times one more than its value.
+        //
+        let restart = match inrush.retry {
+            Retry::Latch => None,
+            Retry::After(delay) => {
+                let hundred_ms = (delay.as_millis() + 50) / 100;
+
+                if !(1..=256).contains(&hundred_ms) {
+                    return Err(invalid(Error::ValueOutOfRange));
+                }
+
+                Some(RESTART_TIME::CommandData((hundred_ms - 1) as u8))
+            }
+        };
+
+        //
+        // DEVICE_CONFIG is read (and validated) before anything is written,
+        // lest a failure leave the device partially configured.
+        //
+        let transport = HotSwapError::Transport;
+        let mut buf = [0u8; 2];
+
+        read(DEVICE_CONFIG::CommandData::code(), &mut buf)
+            .map_err(transport)?;
+
+        let mut config = DEVICE_CONFIG::CommandData::from_slice(&buf)
+            .ok_or(invalid(Error::ShortData))?;
+
+        config.set_oc_retry(match restart {
+            Some(_) => DEVICE_CONFIG::OCRetry::Retry,
+            None => DEVICE_CONFIG::OCRetry::TurnOff,
+        });
+
+        limit.to_slice(&mut buf);
+        write(STRT_UP_IOUT_LIM::CommandData::code(), &buf)
+            .map_err(transport)?;
+
+        config.to_slice(&mut buf);
+        write(DEVICE_CONFIG::CommandData::code(), &buf).map_err(transport)?;
+
+        if let Some(restart) = restart {
+            restart.to_slice(&mut buf);
+            write(RESTART_TIME::CommandData::code(), &buf[..1])
+                .map_err(transport)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl HotSwap for Adm1275 {
+    fn configure_inrush<E>(
+        inrush: &Inrush,
+        _read: impl FnMut(u8, &mut [u8]) -> Result<(), E>,
+        _write: impl FnMut(u8, &[u8]) -> Result<(), E>,
+    ) -> Result<(), HotSwapError<E>> {
+        //
+        // The ADM1275 has no startup current limit (inrush is limited only
+        // by the current limit itself), its fault timer is set by an
+        // external capacitor, and it has no register to configure its
+        // behavior after a fault.  We therefore accept only a configuration
+        // that it satisfies as it stands, and write nothing.
+        //
+        if inrush.fault_timer.is_some() || inrush.retry != Retry::Latch {
+            return Err(HotSwapError::Invalid(Error::Unsupported));
+        }
+
+        if inrush.max_current.0 < inrush.current_limit.0 {
+            return Err(HotSwapError::Invalid(Error::Unsupported));
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// Configures an LM25066 or a derivative, given the ratio of its low
+/// current limit threshold to its high one.  These parts limit inrush to
+/// their current limit, which can be set (via DEVICE_SETUP) to either its
+/// high threshold (taken to be that of the [`Inrush`] current limit) or its
+/// low one; we select the high limit if it does not exceed the maximum.
+/// The fault timer is set by an external capacitor, as is the delay
+/// between retries; a retry is therefore taken to be an unlimited number
+/// of retries, and its delay is not checked.
+///
+fn configure_lm25066<E>(
+    inrush: &Inrush,
+    low: f32,
+    mut read: impl FnMut(u8, &mut [u8]) -> Result<(), E>,
+    mut write: impl FnMut(u8, &[u8]) -> Result<(), E>,
+) -> Result<(), HotSwapError<E>> {
+    use lm25066::DEVICE_SETUP;
+
+    let invalid = HotSwapError::Invalid;
+
+    if inrush.fault_timer.is_some() {
+        return Err(invalid(Error::Unsupported));
+    }
+
+    let frac = inrush.max_current.0 / inrush.current_limit.0;
+
+    let limit = if !frac.is_finite() || frac < low {
+        return Err(invalid(Error::ValueOutOfRange));
+    } else if frac < 1.0 {
+        DEVICE_SETUP::CurrentLimit::Low
+    } else {
+        DEVICE_SETUP::CurrentLimit::High
+    };
+
+    let transport = HotSwapError::Transport;
+    let mut buf = [0u8; 1];
+
+    read(DEVICE_SETUP::CommandData::code(), &mut buf).map_err(transport)?;
+
+    let mut setup = DEVICE_SETUP::CommandData::from_slice(&buf)
+        .ok_or(invalid(Error::ShortData))?;
+
+    setup.set_current_limit(limit);
+    setup.set_current_limit_config(DEVICE_SETUP::CurrentLimitConfig::Register);
+    setup.set_retry_setting(match inrush.retry {
+        Retry::Latch => DEVICE_SETUP::RetrySetting::NoRetry,
+        Retry::After(_) => DEVICE_SETUP::RetrySetting::Unlimited,
+    });
+
+    setup.to_slice(&mut buf);
+    write(DEVICE_SETUP::CommandData::code(), &buf).map_err(transport)?;
+
+    Ok(())
+}
+
+impl HotSwap for Lm25066 {
+    fn configure_inrush<E>(
+        inrush: &Inrush,
+        read: impl FnMut(u8, &mut [u8]) -> Result<(), E>,
+        write: impl FnMut(u8, &[u8]) -> Result<(), E>,
+    ) -> Result<(), HotSwapError<E>> {
+        //
+        // The LM25066's current limit thresholds are 46 mV and 25 mV.
+        //
+        configure_lm25066(inrush, 25.0 / 46.0, read, write)
+    }
+}
+
+impl HotSwap for Lm5066I {
+    fn configure_inrush<E>(
+        inrush: &Inrush,
+        read: impl FnMut(u8, &mut [u8]) -> Result<(), E>,
+        write: impl FnMut(u8, &[u8]) -> Result<(), E>,
+    ) -> Result<(), HotSwapError<E>> {
+        //
+        // The LM5066I's current limit thresholds are 50 mV and 26 mV.
+        //
+        configure_lm25066(inrush, 26.0 / 50.0, read, write)
+    }
+}
@@ -0,0 +1,43 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+///
+/// Infineon-specific functionality.
+///
+use crate::commands::VOUT_MODE::Mode;
+use crate::units::Volts;
+use crate::{Error, VOutModeCommandData, Vid, VidCode};
+
+///
+/// Returns the VID protocol dictated by VOUT_MODE on the XDPE family of
+/// controllers (and on the TDA38640 in SVID mode), failing with
+/// [`Error::InvalidMode`] if VOUT_MODE does not indicate VID or indicates a
+/// protocol that we don't know.
+///
+pub fn vid_code(mode: &VOutModeCommandData) -> Result<VidCode, Error> {
+    if mode.get_mode() != Some(Mode::VID) {
+        return Err(Error::InvalidMode);
+    }
+
+    //
+    // The parameter is an unsigned protocol identifier, not the signed
+    // exponent of ULINEAR16.
+    //
+    match mode.0 & 0x1f {
+        0x01 => Ok(VidCode::Vr12),
+        0x02 => Ok(VidCode::Vr13),
+        0x03 => Ok(VidCode::Imvp9),
+        0x10 => Ok(VidCode::Amd625mV),
+        _ => Err(Error::InvalidMode),
+    }
+}
+
+///
+/// Interprets a raw VOUT-related value (e.g., that of READ_VOUT) on the
+/// XDPE family of controllers when VOUT_MODE indicates VID.
+///
+pub fn vid_vout(raw: u16, mode: &VOutModeCommandData) -> Result<Volts, Error> {
+    Ok(Volts(Vid(raw, vid_code(mode)?).to_real()))
+}
@@ -67,6 +67,15 @@
 //! good about dead code elimination and will not include unused program text
 //! in the embedded system.
 //!
+//! Nor does this crate perform any I/O of its own.  Where an operation
+//! consists of transactions with a device (e.g., polling telemetry,
+//! servicing SMBALERT# or storing to nonvolatile memory), the transactions
+//! themselves are performed by the caller -- either via caller-provided
+//! closures or transports, or by consuming the reads and writes that the
+//! crate yields.  The crate is therefore agnostic as to the bus and the
+//! driver; the `async` feature additionally allows telemetry to be polled
+//! as an asynchronous stream (see [`poll`]).
+//!
 //! If it needs to be said:  all of this adds up to specifications almost
 //! entirely via RON definitions -- and an absolutely unholy `build.rs` to
 //! assemble it all at build time.  Paraphrasing [the late Roger
@@ -74,13 +83,36 @@
 //! terrible things are sometimes required for beautiful abstractions.
 //!
 
+#[cfg(feature = "wasm-bindgen")]
+extern crate alloc;
+
 pub use num_derive::{FromPrimitive, ToPrimitive};
 pub use num_traits::float::FloatCore;
 pub use num_traits::{FromPrimitive, ToPrimitive};
 
+//
+// The precision in which values are converted to and from the DIRECT
+// format.  By default this is single precision; the `f64` feature (intended
+// for hosts, where exactness matters more than code size) performs the
+// conversions in double precision, rounding only the result.  The units
+// themselves remain `f32` either way, so that enabling the feature doesn't
+// change any interface.
+//
+#[cfg(not(feature = "f64"))]
+pub(crate) type Real = f32;
+
+#[cfg(feature = "f64")]
+pub(crate) type Real = f64;
+
+mod format;
+pub use crate::format::Format;
+
 mod operation;
 pub use crate::operation::Operation;
 
+mod revision;
+pub use crate::revision::SpecRevision;
+
 pub mod units;
 
 pub mod commands;
@@ -89,7 +121,39 @@ pub use crate::commands::CommandCode;
 pub use crate::commands::Device;
 
 // Pull in any vendor-specific auxiliary modules
+pub mod infineon;
 pub mod renesas;
+pub mod ti;
+
+pub mod alert;
+pub mod audit;
+pub mod brownout;
+pub mod calibration;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+pub mod decoder;
+pub mod encode;
+pub mod energy;
+pub mod expect;
+pub mod fan;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod heuristic;
+pub mod hotswap;
+pub mod pec;
+pub mod plausibility;
+pub mod poll;
+pub mod protocol;
+pub mod sequence;
+#[cfg(feature = "serde")]
+pub mod serialize;
+pub mod signature;
+pub mod store;
+pub mod stream;
+pub mod vectors;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
+pub mod zone;
 
 /// The position, in bits, of a field.  If a field contains multiple bits, this
 /// position represents the **least** significant bit of the multi-bit field.
@@ -100,6 +164,39 @@ pub struct Bitpos(pub u8);
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Bitwidth(pub u8);
 
+/// The order of the bytes within a multi-byte field.  PMBus is
+/// little-endian, but some manufacturer-specific registers contain fields
+/// that are not; such fields are annotated with their byte order, and are
+/// converted to native order when they are read or written.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ByteOrder {
+    LittleEndian,
+    /// The most significant byte is first
+    BigEndian,
+    /// Each 16-bit word is little-endian, but the most significant word is
+    /// first
+    WordSwapped,
+}
+
+impl ByteOrder {
+    /// Converts the raw value of a field of the specified width between
+    /// this byte order and little-endian.  The conversion is its own
+    /// inverse.
+    pub fn convert(&self, raw: u128, width: Bitwidth) -> u128 {
+        let width = u32::from(width.0);
+
+        match self {
+            ByteOrder::LittleEndian => raw,
+            ByteOrder::BigEndian if width < 16 => raw,
+            ByteOrder::BigEndian => raw.swap_bytes() >> (128 - width),
+            ByteOrder::WordSwapped => (0..width / 16).fold(0, |rval, i| {
+                let word = (raw >> (i * 16)) & 0xffff;
+                rval | word << (width - 16 - i * 16)
+            }),
+        }
+    }
+}
+
 /// A PMBus error
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Error {
@@ -121,6 +218,18 @@ pub enum Error {
     OverflowReplacement,
     /// Specified bit position does not correspond to any field
     InvalidField,
+    /// Operation is not valid for the requested transaction
+    InvalidOperation,
+    /// Payload length is not valid for the operation
+    InvalidLength,
+    /// Caller-provided buffer is too small to hold the transaction
+    BufferTooSmall,
+    /// Requested configuration is not supported by the device
+    Unsupported,
+    /// Command is newer than the PMBus revision supported by the device
+    UnsupportedRevision,
+    /// Packet error code does not match the transaction
+    BadPec,
 }
 
 /// A value used to replace a field when mutating command data.  In general,
@@ -131,6 +240,9 @@ pub enum Replacement {
     Float(f32),
     Integer(u32),
     Boolean(bool),
+    /// A real value that is clamped to the range that can be represented
+    /// rather than being rejected if it lies outside of it
+    SaturatingFloat(f32),
 }
 
 /// A trait to express a field as part of the reflection interface.  As
@@ -151,6 +263,13 @@ pub trait Field: core::fmt::Debug {
 
     /// Returns the description of the field
     fn desc(&self) -> &'static str;
+
+    /// Returns the numeric identifier of the field.  This is derived from
+    /// the device that defines the field (if it is not a common definition),
+    /// the name of its command, and the name of the field; it does not
+    /// change with the field's description, and is suitable for referring
+    /// to the field compactly (e.g., in logs).
+    fn id(&self) -> u32;
 }
 
 /// A trait to express the value contained by a field as part of the
@@ -168,6 +287,112 @@ pub trait Value: core::fmt::Display + core::fmt::Debug {
     fn raw(&self) -> u32;
 
     fn scalar(&self) -> bool;
+
+    /// Returns the value as a real number in its units, if it is one (e.g.,
+    /// a voltage, as opposed to a sentinel or a raw value)
+    fn as_float(&self) -> Option<f32>;
+
+    /// Returns the suffix denoting the units of this value (e.g., "V"), or
+    /// an empty string if it has none
+    fn units(&self) -> &'static str {
+        ""
+    }
+
+    /// Returns the name of the sentinel, if this value is one of the
+    /// enumerated values of its field (e.g., "On" for the operation of
+    /// `OPERATION`)
+    fn sentinel_name(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// The radix in which to display a raw value; see [`ValueFormat`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Radix {
+    Binary,
+    Decimal,
+    Hex,
+}
+
+///
+/// Options for displaying a [`Value`], as applied via [`FormattedValue`]
+/// (which can be had by calling `format` on a `&dyn Value`).  The default
+/// options display a value just as its `Display` implementation does.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ValueFormat {
+    /// The number of digits after the decimal point for real values; if
+    /// `None`, two digits are displayed
+    pub precision: Option<usize>,
+    /// The radix in which to display values that aren't real (e.g., a
+    /// bitfield, along with its description); if `None`, each value is
+    /// displayed in its default radix
+    pub radix: Option<Radix>,
+    /// Whether to display the units of real values
+    pub units: bool,
+}
+
+impl Default for ValueFormat {
+    fn default() -> Self {
+        Self {
+            precision: None,
+            radix: None,
+            units: true,
+        }
+    }
+}
+
+/// A [`Value`] that displays according to a [`ValueFormat`]
+pub struct FormattedValue<'a> {
+    value: &'a dyn Value,
+    format: ValueFormat,
+}
+
+impl<'a> dyn Value + 'a {
+    /// Returns the value, to be displayed according to the specified format
+    pub fn format(&self, format: ValueFormat) -> FormattedValue<'_> {
+        FormattedValue {
+            value: self,
+            format,
+        }
+    }
+}
+
+impl core::fmt::Display for FormattedValue<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let value = self.value;
+        let format = &self.format;
+
+        if let Some(real) = value.as_float() {
+            let precision = format.precision.unwrap_or(2);
+            write!(f, "{:.*}", precision, real)?;
+
+            if format.units {
+                write!(f, "{}", value.units())?;
+            }
+
+            return Ok(());
+        }
+
+        let raw = value.raw();
+
+        match format.radix {
+            None => write!(f, "{}", value),
+            Some(radix) => {
+                match radix {
+                    Radix::Binary => write!(f, "0b{:b}", raw)?,
+                    Radix::Decimal => write!(f, "{}", raw)?,
+                    Radix::Hex => write!(f, "0x{:x}", raw)?,
+                }
+
+                if value.scalar() {
+                    Ok(())
+                } else {
+                    write!(f, " = {}", value.desc())
+                }
+            }
+        }
+    }
 }
 
 /// A trait to express a PMBus command
@@ -180,6 +405,74 @@ pub trait Command: core::fmt::Debug {
 
     /// Returns the operation for writing data with this command, if any
     fn write_op(&self) -> Operation;
+
+    /// Returns the time that must be allowed to elapse after this command
+    /// before the device can be expected to accept another, if any
+    fn delay_after(&self) -> Option<core::time::Duration> {
+        None
+    }
+
+    /// Returns the revision of the PMBus specification that introduced this
+    /// command, if it is known
+    fn introduced(&self) -> Option<SpecRevision> {
+        None
+    }
+
+    /// Returns true if this command is part of the device's configuration
+    /// (e.g., a limit or a fault response) rather than its status or
+    /// telemetry; see [`signature`].
+    fn configuration(&self) -> bool {
+        false
+    }
+
+    /// Returns the mask of the bits in this command's payload (taken as a
+    /// little-endian integer) that are volatile, changing in normal
+    /// operation rather than as a result of configuration
+    fn volatile(&self) -> u32 {
+        0
+    }
+
+    /// Returns the other names by which this command is known (e.g., in
+    /// earlier revisions of the datasheet or of the specification), if any
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// A trait implemented by a type generated for each supported device (e.g.,
+/// [`devices::adm1272::Adm1272`]), allowing for code to be written
+/// generically over any device while still being resolved statically.
+pub trait PmbusDevice {
+    /// The command codes for this device
+    type CommandCode: Command + Copy + PartialEq + 'static;
+
+    /// The [`Device`] that corresponds to this type
+    const DEVICE: Device;
+
+    /// The name of this device (as returned by [`Device::name`])
+    const NAME: &'static str;
+
+    /// The description of this device (as returned by [`Device::desc`])
+    const DESC: &'static str;
+
+    /// The commands that return telemetry for this device (that is, the
+    /// numeric `READ_` commands), in order of command code
+    const TELEMETRY: &'static [Self::CommandCode];
+
+    /// Returns the command that corresponds to the given code, if any
+    fn command(code: u8) -> Option<Self::CommandCode>;
+
+    /// Returns the code of the given command
+    fn code(command: Self::CommandCode) -> u8;
+
+    /// Interprets the payload for the given command; see
+    /// [`Device::interpret`].
+    fn interpret(
+        code: Self::CommandCode,
+        payload: &[u8],
+        mode: impl VOutMode,
+        iter: impl FnMut(&dyn Field, &dyn Value),
+    ) -> Result<(), Error>;
 }
 
 /// A regrettable complexity of PMBus is that the output of one command --
@@ -188,6 +481,71 @@ pub trait Command: core::fmt::Debug {
 /// interface.
 pub type VOutModeCommandData = commands::VOUT_MODE::CommandData;
 
+///
+/// A source of VOUT_MODE for the interpretation (or setting) of values that
+/// depend on it.  This is implemented by [`VOutModeCommandData`] itself, by
+/// [`CachedVOutMode`], and by closures that return `VOutModeCommandData`
+/// (allowing VOUT_MODE to be retrieved only if it is in fact needed).
+///
+pub trait VOutMode {
+    /// Returns VOUT_MODE
+    fn vout_mode(&self) -> VOutModeCommandData;
+
+    /// Returns the ULINEAR16 exponent given by the parameter of VOUT_MODE.
+    /// This is only meaningful if VOUT_MODE indicates ULINEAR16; see
+    /// [`ULinear16Exponent::from_mode`] to also check the mode.
+    fn exponent(&self) -> ULinear16Exponent {
+        ULinear16Exponent(self.vout_mode().get_parameter())
+    }
+}
+
+impl<F: Fn() -> VOutModeCommandData> VOutMode for F {
+    fn vout_mode(&self) -> VOutModeCommandData {
+        self()
+    }
+}
+
+impl VOutMode for VOutModeCommandData {
+    fn vout_mode(&self) -> VOutModeCommandData {
+        *self
+    }
+}
+
+///
+/// VOUT_MODE as read once (e.g., for a rail), to be passed by value where
+/// VOUT_MODE is required rather than re-reading it (or capturing it in a
+/// closure) each time.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct CachedVOutMode(VOutModeCommandData);
+
+impl CachedVOutMode {
+    pub fn new(mode: VOutModeCommandData) -> Self {
+        Self(mode)
+    }
+
+    /// Returns the cached VOUT_MODE from its payload, or `None` if the
+    /// payload is too short
+    pub fn from_slice(payload: &[u8]) -> Option<Self> {
+        VOutModeCommandData::from_slice(payload).map(Self)
+    }
+}
+
+impl VOutMode for CachedVOutMode {
+    fn vout_mode(&self) -> VOutModeCommandData {
+        self.0
+    }
+}
+
+/// A function that interprets the data payload for a particular command,
+/// calling the specified closure for each field and its value.  These are
+/// generated for each command that has data; see [`Device::interpreter`].
+pub type Interpreter = fn(
+    payload: &[u8],
+    mode: &dyn Fn() -> VOutModeCommandData,
+    iter: &mut dyn FnMut(&dyn Field, &dyn Value),
+) -> Result<(), Error>;
+
 /// A trait to express PMBus command data.  This is part of the reflection
 /// interfact, and allows for consumers to iterate over fields (via
 /// [`Device::interpret`] or optionally mutate a particular field (via
@@ -198,14 +556,14 @@ pub trait CommandData {
     /// a closure to be called for each field and its value.
     fn interpret(
         &self,
-        mode: impl Fn() -> VOutModeCommandData,
+        mode: impl VOutMode,
         iter: impl FnMut(&dyn Field, &dyn Value),
     ) -> Result<(), Error>;
 
     /// Mutates the contents of command data.
     fn mutate(
         &mut self,
-        mode: impl Fn() -> VOutModeCommandData,
+        mode: impl VOutMode,
         iter: impl FnMut(&dyn Field, &dyn Value) -> Option<Replacement>,
     ) -> Result<(), Error>;
 
@@ -230,7 +588,7 @@ pub trait CommandData {
 /// A [`Field`]-implementing structure that denotes that the entire command
 /// data payload is a single, numeric field.
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub struct WholeField(&'static str, Bitwidth);
+pub struct WholeField(&'static str, Bitwidth, u32);
 
 impl Field for WholeField {
     fn bitfield(&self) -> bool {
@@ -248,6 +606,10 @@ impl Field for WholeField {
     fn desc(&self) -> &'static str {
         self.0
     }
+
+    fn id(&self) -> u32 {
+        self.2
+    }
 }
 
 ///
@@ -256,6 +618,7 @@ impl Field for WholeField {
 /// the condition.
 ///
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(non_snake_case)]
 pub struct Coefficients {
     /// Slope coefficient. Two byte signed off the wire (but potentially
@@ -267,6 +630,30 @@ pub struct Coefficients {
     pub R: i8,
 }
 
+//
+// For our integer conversions:  divides, rounding the quotient to the
+// nearest integer with halves rounded away from zero (as f32::round() does).
+// The divisor must be positive.
+//
+fn div_round(a: i128, b: i128) -> i128 {
+    if a >= 0 {
+        (a + b / 2) / b
+    } else {
+        -((-a + b / 2) / b)
+    }
+}
+
+//
+// Returns 10 raised to the specified power, or None if it doesn't fit.
+//
+fn pow10(exp: u32) -> Option<i128> {
+    10i128.checked_pow(exp)
+}
+
+fn saturate(val: i128) -> i64 {
+    val.clamp(i64::MIN.into(), i64::MAX.into()) as i64
+}
+
 ///
 /// A datum in the DIRECT data format.
 ///
@@ -277,22 +664,170 @@ impl Direct {
     #[allow(dead_code)]
     pub fn to_real(&self) -> f32 {
         let coefficients = &self.1;
-        let m: f32 = coefficients.m as f32;
-        let b: f32 = coefficients.b.into();
+        let m = coefficients.m as Real;
+        let b = Real::from(coefficients.b);
         let exp: i32 = coefficients.R.into();
-        let y: f32 = (self.0 as i16).into();
+        let y = Real::from(self.0 as i16);
+        let ten: Real = 10.0;
 
-        (y * f32::powi(10.0, -exp) - b) / m
+        //
+        // We call FloatCore's powi() explicitly:  if std is anywhere in the
+        // crate graph (as it is with some features), f32::powi() resolves to
+        // std's, which can differ in the last place -- and our results
+        // shouldn't depend on what else has been linked.
+        //
+        ((y * FloatCore::powi(ten, -exp) - b) / m) as f32
     }
 
+    ///
+    /// Encodes the specified value, rounding to the nearest datum.  A
+    /// negative datum is encoded in two's complement (as [`Direct::to_real`]
+    /// decodes it) rather than being clamped to zero.
+    ///
     #[allow(dead_code)]
     pub fn from_real(x: f32, coefficients: Coefficients) -> Self {
-        let m: f32 = coefficients.m as f32;
-        let b: f32 = coefficients.b.into();
+        Self::from_real_rounded(x, coefficients, Rounding::Nearest)
+    }
+
+    /// Encodes the specified value, rounding as specified
+    pub fn from_real_rounded(
+        x: f32,
+        coefficients: Coefficients,
+        rounding: Rounding,
+    ) -> Self {
+        let m = coefficients.m as Real;
+        let b = Real::from(coefficients.b);
         let exp: i32 = coefficients.R.into();
-        let y: f32 = (m * x + b) * f32::powi(10.0, exp);
+        let ten: Real = 10.0;
+        let y = (m * Real::from(x) + b) * FloatCore::powi(ten, exp);
+
+        //
+        // A negative slope inverts the direction of rounding.
+        //
+        let rounding = match rounding.resolve(x) {
+            Rounding::Up if m < 0.0 => Rounding::Down,
+            Rounding::Down if m < 0.0 => Rounding::Up,
+            rounding => rounding,
+        };
+
+        Self(rounding.round(y) as i16 as u16, coefficients)
+    }
+
+    ///
+    /// Returns the value in millionths of its units (e.g., microvolts for a
+    /// voltage), rounded to the nearest integer and saturating at the bounds
+    /// of an `i64`.  This uses only integer arithmetic.  If the slope
+    /// coefficient is zero, the value cannot be determined and 0 is
+    /// returned.
+    ///
+    pub fn to_micros(&self) -> i64 {
+        let Coefficients { m, b, R: r } = self.1;
+        let y = i128::from(self.0 as i16);
+        let (b, m) = (i128::from(b), i128::from(m));
+
+        if m == 0 {
+            return 0;
+        }
+
+        //
+        // X = (Y * 10^-R - b) / m; we arrange to divide only once.
+        //
+        let (num, den) = if r <= 0 {
+            match pow10(u32::from(r.unsigned_abs())) {
+                Some(p) => ((y * p - b) * 1_000_000, m),
+                None if y == 0 => (-b * 1_000_000, m),
+                None if (y > 0) == (m > 0) => return i64::MAX,
+                None => return i64::MIN,
+            }
+        } else {
+            match pow10(r as u32) {
+                Some(p) => ((y - b * p) * 1_000_000, m * p),
+                None => return 0,
+            }
+        };
+
+        let (num, den) = if den < 0 { (-num, -den) } else { (num, den) };
+
+        saturate(div_round(num, den))
+    }
+
+    ///
+    /// Encodes a value expressed in millionths of its units, using only
+    /// integer arithmetic.  Returns `None` if the value cannot be
+    /// represented.
+    ///
+    pub fn from_micros(
+        micros: i64,
+        coefficients: Coefficients,
+    ) -> Option<Self> {
+        let Coefficients { m, b, R: r } = coefficients;
+        let x = i128::from(micros);
+        let (b, m) = (i128::from(b), i128::from(m));
+
+        //
+        // Y = (m * X + b) * 10^R, where X is our value divided by 10^6.
+        //
+        let num = m.checked_mul(x)?.checked_add(b * 1_000_000)?;
+
+        let y = if r >= 0 {
+            div_round(num.checked_mul(pow10(r as u32)?)?, 1_000_000)
+        } else {
+            div_round(num, pow10(u32::from(r.unsigned_abs()))? * 1_000_000)
+        };
+
+        if y < i16::MIN.into() || y > i16::MAX.into() {
+            return None;
+        }
 
-        Self(y.round() as u16, coefficients)
+        Some(Self(y as i16 as u16, coefficients))
+    }
+}
+
+///
+/// A 32-bit datum in the DIRECT data format, as used by (e.g.) READ_KWH_IN
+/// and READ_KWH_OUT.  As with [`Direct`], Y is a two's complement integer.
+/// The computation is done in double precision so that large values keep
+/// their precision until they are returned.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Direct32(pub u32, pub Coefficients);
+
+impl Direct32 {
+    pub fn to_real(&self) -> f32 {
+        let coefficients = &self.1;
+        let m: f64 = coefficients.m.into();
+        let b: f64 = coefficients.b.into();
+        let exp: i32 = coefficients.R.into();
+        let y: f64 = (self.0 as i32).into();
+
+        ((y * FloatCore::powi(10.0f64, -exp) - b) / m) as f32
+    }
+
+    pub fn from_real(x: f32, coefficients: Coefficients) -> Self {
+        Self::from_real_rounded(x, coefficients, Rounding::Nearest)
+    }
+
+    /// Encodes the specified value, rounding as specified
+    pub fn from_real_rounded(
+        x: f32,
+        coefficients: Coefficients,
+        rounding: Rounding,
+    ) -> Self {
+        let m: f64 = coefficients.m.into();
+        let b: f64 = coefficients.b.into();
+        let exp: i32 = coefficients.R.into();
+        let y: f64 = (m * f64::from(x) + b) * FloatCore::powi(10.0f64, exp);
+
+        //
+        // A negative slope inverts the direction of rounding.
+        //
+        let rounding = match rounding.resolve(x) {
+            Rounding::Up if m < 0.0 => Rounding::Down,
+            Rounding::Down if m < 0.0 => Rounding::Up,
+            rounding => rounding,
+        };
+
+        Self(rounding.round(y) as i32 as u32, coefficients)
     }
 }
 
@@ -329,6 +864,18 @@ const LINEAR11_N_MIN: i16 = -(1 << (LINEAR11_N_WIDTH - 1));
 const LINEAR11_N_MASK: i16 = (1 << LINEAR11_N_WIDTH) - 1;
 
 impl Linear11 {
+    /// The largest value that can be represented:  1023 × 2^15
+    pub const MAX: Linear11 = Linear11(
+        ((LINEAR11_N_MAX as u16) << LINEAR11_Y_WIDTH) | LINEAR11_Y_MAX as u16,
+    );
+
+    /// The smallest (that is, most negative) value that can be represented:
+    /// -1024 × 2^15
+    pub const MIN: Linear11 = Linear11(
+        ((LINEAR11_N_MAX as u16) << LINEAR11_Y_WIDTH)
+            | (LINEAR11_Y_MIN & LINEAR11_Y_MASK) as u16,
+    );
+
     pub fn to_real(&self) -> f32 {
         let n = (self.0 as i16) >> LINEAR11_Y_WIDTH;
         let y = ((self.0 << LINEAR11_N_WIDTH) as i16) >> LINEAR11_N_WIDTH;
@@ -336,45 +883,581 @@ impl Linear11 {
         y as f32 * f32::powi(2.0, n.into())
     }
 
+    #[allow(dead_code)]
+    pub fn from_real(x: f32) -> Option<Self> {
+        Self::from_real_rounded(x, Rounding::Nearest)
+    }
+
+    ///
+    /// Encodes the specified value, rounding as specified.  With directed
+    /// rounding, the encoding is the closest one that lies in the specified
+    /// direction.
+    ///
+    pub fn from_real_rounded(x: f32, rounding: Rounding) -> Option<Self> {
+        //
+        // We want the encoding that lands closest to our real value.  In
+        // general, this is the smallest N for which the rounded value of Y
+        // still fits -- but deriving N from the logarithm of X is fraught
+        // near powers of two (where rounding Y can carry it out of range, or
+        // where a coarser exponent happens to represent X exactly), so we
+        // instead consider every N, pick the closest Y for each (in the
+        // direction of rounding), and keep whichever encoding has the least
+        // error.  On ties, we prefer the smaller N (and therefore the
+        // greater precision).  If no N has a Y that fits, we cannot
+        // represent the value.
+        //
+        if !x.is_finite() {
+            return None;
+        }
+
+        let mut best: Option<(i16, i16, f32)> = None;
+
+        for n in LINEAR11_N_MIN..=LINEAR11_N_MAX {
+            let exp = f32::powi(2.0, n.into());
+            let y = rounding.round(x / exp);
+
+            if y < LINEAR11_Y_MIN as f32 || y > LINEAR11_Y_MAX as f32 {
+                continue;
+            }
+
+            let err = (x - y * exp).abs();
+
+            match best {
+                Some((_, _, best_err)) if best_err <= err => {}
+                _ => {
+                    best = Some((n, y as i16, err));
+                }
+            }
+        }
+
+        best.map(|(n, y, _)| {
+            let high = ((n & LINEAR11_N_MASK) as u16) << LINEAR11_Y_WIDTH;
+            let low = (y & LINEAR11_Y_MASK) as u16;
+
+            Linear11(high | low)
+        })
+    }
+
+    ///
+    /// Encodes the specified value.  Values that are out of range are
+    /// either rejected or clamped to the largest magnitude of the same sign
+    /// as dictated by the specified [`Saturation`] policy.  A value that is
+    /// not a number is always rejected.
+    ///
+    pub fn try_from_real(
+        x: f32,
+        saturation: Saturation,
+    ) -> Result<Self, Error> {
+        Self::try_from_real_rounded(x, saturation, Rounding::Nearest)
+    }
+
+    /// As [`Linear11::try_from_real`], but rounding as specified
+    pub fn try_from_real_rounded(
+        x: f32,
+        saturation: Saturation,
+        rounding: Rounding,
+    ) -> Result<Self, Error> {
+        if x.is_nan() {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        match (Self::from_real_rounded(x, rounding), saturation) {
+            (Some(lin), _) => Ok(lin),
+            (None, Saturation::Fail) => Err(Error::ValueOutOfRange),
+            (None, Saturation::Clamp) if x < 0.0 => Ok(Self::MIN),
+            (None, Saturation::Clamp) => Ok(Self::MAX),
+        }
+    }
+
+    ///
+    /// Returns the resolution with which the specified value would be
+    /// encoded by [`Linear11::from_real`] -- that is, the difference
+    /// between adjacent values that can be represented at that magnitude
+    /// -- or `None` if the value cannot be represented.
+    ///
+    pub fn resolution_at(x: f32) -> Option<f32> {
+        let lin = Self::from_real(x)?;
+        let n = (lin.0 as i16) >> LINEAR11_Y_WIDTH;
+
+        Some(f32::powi(2.0, n.into()))
+    }
+
+    ///
+    /// Returns the value in thousandths of its units (e.g., millivolts for
+    /// a voltage), rounded to the nearest integer (with halves rounded away
+    /// from zero) and saturating at the bounds of an `i32`.  This uses only
+    /// integer arithmetic, allowing (e.g.) telemetry to be read on targets
+    /// without floating point:
+    /// `Linear11(data.0).to_millis()`.
+    ///
+    pub fn to_millis(&self) -> i32 {
+        let n = (self.0 as i16) >> LINEAR11_Y_WIDTH;
+        let y = ((self.0 << LINEAR11_N_WIDTH) as i16) >> LINEAR11_N_WIDTH;
+        let x = i128::from(y) * 1000;
+
+        let millis = if n >= 0 {
+            x << n
+        } else {
+            div_round(x, 1 << -n)
+        };
+
+        millis.clamp(i32::MIN.into(), i32::MAX.into()) as i32
+    }
+
+    ///
+    /// Encodes a value expressed in thousandths of its units, using only
+    /// integer arithmetic.  As with [`Linear11::from_real`], the encoding
+    /// closest to the value is chosen; if no encoding can represent the
+    /// value, `None` is returned.
+    ///
+    pub fn from_millis(millis: i32) -> Option<Self> {
+        //
+        // We compare errors scaled by 2^16, so that they are integers for
+        // every exponent.
+        //
+        let target = i64::from(millis) << 16;
+        let mut best: Option<(i16, i16, i64)> = None;
+
+        for n in LINEAR11_N_MIN..=LINEAR11_N_MAX {
+            let y = if n >= 0 {
+                div_round(millis.into(), 1000 << n) as i64
+            } else {
+                div_round((i64::from(millis) << -n).into(), 1000) as i64
+            };
+
+            if y < LINEAR11_Y_MIN.into() || y > LINEAR11_Y_MAX.into() {
+                continue;
+            }
+
+            let err = (target - ((y * 1000) << (n + 16))).abs();
+
+            match best {
+                Some((_, _, best_err)) if best_err <= err => {}
+                _ => {
+                    best = Some((n, y as i16, err));
+                }
+            }
+        }
+
+        best.map(|(n, y, _)| {
+            let high = ((n & LINEAR11_N_MASK) as u16) << LINEAR11_Y_WIDTH;
+            let low = (y & LINEAR11_Y_MASK) as u16;
+
+            Linear11(high | low)
+        })
+    }
+}
+
+///
+/// A datum in the IEEE 754 half-precision format, as permitted by PMBus 1.3
+/// (Part II, Sec. 7.5).
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Half(pub u16);
+
+//
+// The half-precision format consists of a sign bit (S), 5 bits of biased
+// exponent (E), and 10 bits of fraction (F):
+//
+// |<------------ high byte ------------>|<--------- low byte ---------->|
+// +---+---+---+---+---+---+     +---+---+---+---+---+---+---+---+---+---+
+// | 7 | 6 | 5 | 4 | 3 | 2 |     | 1 | 0 | 7 | 6 | 5 | 4 | 3 | 2 | 1 | 0 |
+// +---+---+---+---+---+---+     +---+---+---+---+---+---+---+---+---+---+
+//
+// | S |<------ E ------>|     |<----------------- F ----------------->|
+//
+// For E of 0, X = (-1)^S * F * 2^-24 (a subnormal); for E of 31, X is
+// infinite (F of 0) or not a number; otherwise:
+//
+//   X = (-1)^S * (1024 + F) * 2^(E - 25)
+//
+const HALF_F_WIDTH: u32 = 10;
+const HALF_F_MASK: u16 = (1 << HALF_F_WIDTH) - 1;
+const HALF_E_MAX: u16 = 0x1f;
+const HALF_E_BIAS: i32 = 15;
+const HALF_SIGN: u16 = 0x8000;
+const HALF_INFINITY: u16 = HALF_E_MAX << HALF_F_WIDTH;
+
+impl Half {
+    pub fn to_real(&self) -> f32 {
+        let e = (self.0 >> HALF_F_WIDTH) & HALF_E_MAX;
+        let f = self.0 & HALF_F_MASK;
+
+        let x = match e {
+            0 => f as f32 * f32::powi(2.0, 1 - HALF_E_BIAS - 10),
+            HALF_E_MAX if f == 0 => f32::INFINITY,
+            HALF_E_MAX => f32::NAN,
+            _ => {
+                let y = (f | (1 << HALF_F_WIDTH)) as f32;
+                y * f32::powi(2.0, i32::from(e) - HALF_E_BIAS - 10)
+            }
+        };
+
+        if self.0 & HALF_SIGN != 0 {
+            -x
+        } else {
+            x
+        }
+    }
+
     #[allow(dead_code)]
     pub fn from_real(x: f32) -> Option<Self> {
         //
-        // We get our closest approximation when we have as many digits as
-        // possible in Y; to determine the value of N that will satisfy this,
-        // we pick a value of Y that is further away from 0 (more positive or
-        // more negative) than our true Y and determine what N would be, taking
-        // the ceiling of this value.  If this value exceeds our resolution for
-        // N, we cannot represent the value.
+        // We round to the nearest representable value (with ties going to
+        // the even value), just as IEEE 754 conversion would.  Values too
+        // small to be represented as subnormals become zero; values too large
+        // to be represented (and infinities and NaN) are rejected.
         //
-        let n = if x >= 0.0 {
-            x / LINEAR11_Y_MAX as f32
+        if !x.is_finite() {
+            return None;
+        }
+
+        let bits = x.to_bits();
+        let sign = if bits >> 31 != 0 { HALF_SIGN } else { 0 };
+        let exp = ((bits >> 23) & 0xff) as i32 - 127;
+        let mantissa = (bits & 0x7f_ffff) | 0x80_0000;
+
+        //
+        // The shift takes our 24-bit f32 mantissa down to the 11 bits of a
+        // normal half (or fewer for a subnormal).
+        //
+        let (base, shift) = if exp >= 1 - HALF_E_BIAS {
+            if exp > HALF_E_BIAS {
+                return None;
+            }
+
+            let e = (exp + HALF_E_BIAS) as u32;
+            (e << HALF_F_WIDTH, 13)
+        } else if exp >= -HALF_E_BIAS - 10 {
+            (0, (13 + (1 - HALF_E_BIAS) - exp) as u32)
+        } else {
+            return Some(Half(sign));
+        };
+
+        let mantissa = if base != 0 {
+            mantissa & 0x7f_ffff
         } else {
-            x / LINEAR11_Y_MIN as f32
+            mantissa
         };
 
-        let n = f32::ceil(libm::log2f(n)) as i16;
+        let mut val = base + (mantissa >> shift);
+        let rem = mantissa & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+
+        if rem > half || (rem == half && val & 1 != 0) {
+            //
+            // A carry out of the fraction correctly bumps the exponent.
+            //
+            val += 1;
+        }
 
-        if n < LINEAR11_N_MIN || n > LINEAR11_N_MAX {
+        if val >= u32::from(HALF_INFINITY) {
             None
         } else {
-            let exp = f32::powi(2.0, n.into());
-            let y = x / exp;
+            Some(Half(sign | val as u16))
+        }
+    }
 
-            let high = ((n & LINEAR11_N_MASK) as u16) << LINEAR11_Y_WIDTH;
-            let low = ((y as i16) & LINEAR11_Y_MASK) as u16;
+    ///
+    /// Encodes the specified value, rounding as specified.  With directed
+    /// rounding, a value too large to be represented is encoded as the
+    /// largest finite value if the direction of rounding allows it.
+    ///
+    pub fn from_real_rounded(x: f32, rounding: Rounding) -> Option<Self> {
+        const MAX: u16 = HALF_INFINITY - 1;
+
+        if !x.is_finite() {
+            return None;
+        }
+
+        let rounding = rounding.resolve(x);
+
+        let nearest = match (Self::from_real(x), rounding) {
+            (Some(half), _) => half,
+            (None, Rounding::Down) if x > 0.0 => return Some(Half(MAX)),
+            (None, Rounding::Up) if x < 0.0 => {
+                return Some(Half(HALF_SIGN | MAX))
+            }
+            (None, _) => return None,
+        };
+
+        //
+        // The nearest value is at most one step away from the value that
+        // we want.  Stepping the magnitude of a finite half moves it by
+        // exactly one representable value.
+        //
+        let v = nearest.to_real();
+        let (h, negative) = (nearest.0, nearest.0 & HALF_SIGN != 0);
+
+        let h = match rounding {
+            Rounding::Up if v < x => match h {
+                0x8000 => 0x0001,
+                _ if negative => h - 1,
+                _ => h + 1,
+            },
+            Rounding::Down if v > x => match h {
+                0x0000 => 0x8001,
+                _ if negative => h + 1,
+                _ => h - 1,
+            },
+            _ => h,
+        };
+
+        if h & !HALF_SIGN >= HALF_INFINITY {
+            None
+        } else {
+            Some(Half(h))
+        }
+    }
+
+    ///
+    /// Encodes the specified value.  Values that are too large to be
+    /// represented (including infinities) are either rejected or clamped to
+    /// the largest finite value of the same sign as dictated by the
+    /// specified [`Saturation`] policy.  A value that is not a number is
+    /// always rejected.
+    ///
+    pub fn try_from_real(
+        x: f32,
+        saturation: Saturation,
+    ) -> Result<Self, Error> {
+        Self::try_from_real_rounded(x, saturation, Rounding::Nearest)
+    }
+
+    /// As [`Half::try_from_real`], but rounding as specified
+    pub fn try_from_real_rounded(
+        x: f32,
+        saturation: Saturation,
+        rounding: Rounding,
+    ) -> Result<Self, Error> {
+        const MAX: u16 = HALF_INFINITY - 1;
+
+        if x.is_nan() {
+            return Err(Error::ValueOutOfRange);
+        }
 
-            Some(Linear11(high | low))
+        match (Self::from_real_rounded(x, rounding), saturation) {
+            (Some(half), _) => Ok(half),
+            (None, Saturation::Fail) => Err(Error::ValueOutOfRange),
+            (None, Saturation::Clamp) if x < 0.0 => Ok(Half(HALF_SIGN | MAX)),
+            (None, Saturation::Clamp) => Ok(Half(MAX)),
         }
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+///
+/// The protocol of a VID code -- that is, the mapping of codes to voltages.
+/// VOUT_MODE can indicate that a device's output voltage is expressed as a
+/// VID code, but the protocol is specific to the device.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VidCode {
+    /// Intel VR11:  6.25 mV steps down from 1.6 V at code 0x02
+    Vr11,
+    /// Intel VR12:  5 mV steps up from 0.25 V at code 0x01
+    Vr12,
+    /// Intel VR12.5 and VR13:  10 mV steps up from 0.5 V at code 0x01
+    Vr13,
+    /// Intel IMVP9:  10 mV steps up from 0.2 V at code 0x01
+    Imvp9,
+    /// AMD:  6.25 mV steps down from 1.55 V at code 0x00
+    Amd625mV,
+}
+
+impl VidCode {
+    //
+    // Each protocol is linear over its range of valid codes; returns the
+    // first and last valid codes, the voltage of the first valid code, and
+    // the step per code (both in microvolts).
+    //
+    fn params(&self) -> (u16, u16, i32, i32) {
+        match self {
+            VidCode::Vr11 => (0x02, 0xb2, 1_600_000, -6_250),
+            VidCode::Vr12 => (0x01, 0xff, 250_000, 5_000),
+            VidCode::Vr13 => (0x01, 0xff, 500_000, 10_000),
+            VidCode::Imvp9 => (0x01, 0xff, 200_000, 10_000),
+            VidCode::Amd625mV => (0x00, 0xd8, 1_550_000, -6_250),
+        }
+    }
+}
+
+/// A datum in VID format, along with the protocol of its code
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Vid(pub u16, pub VidCode);
+
+impl Vid {
+    /// Returns the voltage denoted by the code.  A code outside of the
+    /// protocol's range (e.g., 0 in VR12) denotes an output that is off,
+    /// and is returned as 0 V.
+    pub fn to_real(&self) -> f32 {
+        let (first, last, base, step) = self.1.params();
+
+        if self.0 < first || self.0 > last {
+            return 0.0;
+        }
+
+        let uv = base + i32::from(self.0 - first) * step;
+        uv as f32 / 1_000_000.0
+    }
+
+    /// Encodes the specified voltage as the code that denotes the nearest
+    /// voltage, returning `None` if it lies outside of the protocol's range.
+    pub fn from_real(x: f32, code: VidCode) -> Option<Self> {
+        let (first, last, base, step) = code.params();
+        let steps = (x * 1_000_000.0 - base as f32) / step as f32;
+
+        if !steps.is_finite() {
+            return None;
+        }
+
+        let steps = FloatCore::round(steps);
+
+        if steps < 0.0 || steps > f32::from(last - first) {
+            None
+        } else {
+            Some(Vid(first + steps as u16, code))
+        }
+    }
+
+    ///
+    /// Encodes the specified voltage.  A voltage outside of the protocol's
+    /// range is either rejected or clamped to the code denoting the nearest
+    /// voltage as dictated by the specified [`Saturation`] policy.  A value
+    /// that is not a number is always rejected.
+    ///
+    pub fn try_from_real(
+        x: f32,
+        code: VidCode,
+        saturation: Saturation,
+    ) -> Result<Self, Error> {
+        if x.is_nan() {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        match (Self::from_real(x, code), saturation) {
+            (Some(vid), _) => Ok(vid),
+            (None, Saturation::Fail) => Err(Error::ValueOutOfRange),
+            (None, Saturation::Clamp) => {
+                let (first, last, _, step) = code.params();
+
+                //
+                // For a protocol that steps down, the first code denotes
+                // the highest voltage.
+                //
+                let (low, high) = if step < 0 {
+                    (last, first)
+                } else {
+                    (first, last)
+                };
+
+                if x < Vid(low, code).to_real() {
+                    Ok(Vid(low, code))
+                } else {
+                    Ok(Vid(high, code))
+                }
+            }
+        }
+    }
+}
+
+//
+// The ULINEAR16 exponent comes from the parameter field of VOUT_MODE, which
+// is a 5-bit two's complement value.
+//
+const ULINEAR16_EXPONENT_MIN: i8 = -16;
+const ULINEAR16_EXPONENT_MAX: i8 = 15;
+
+///
+/// The exponent for a ULINEAR16 datum.  This is the (signed) parameter of
+/// VOUT_MODE when VOUT_MODE indicates ULINEAR16; it is typically negative,
+/// but may be zero or positive on parts with coarse output voltages.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ULinear16Exponent(pub i8);
 
+impl ULinear16Exponent {
+    /// Returns an exponent, or `None` if it cannot be represented in the
+    /// parameter field of VOUT_MODE.
+    pub fn new(exp: i8) -> Option<Self> {
+        if (ULINEAR16_EXPONENT_MIN..=ULINEAR16_EXPONENT_MAX).contains(&exp) {
+            Some(Self(exp))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the exponent dictated by the specified VOUT_MODE, failing
+    /// with [`Error::InvalidMode`] if VOUT_MODE does not indicate ULINEAR16.
+    pub fn from_mode(mode: &VOutModeCommandData) -> Result<Self, Error> {
+        match mode.get_mode() {
+            Some(commands::VOUT_MODE::Mode::ULINEAR16) => {
+                Ok(Self(mode.get_parameter()))
+            }
+            _ => Err(Error::InvalidMode),
+        }
+    }
+}
+
+/// The policy to apply when encoding a value that lies outside of the range
+/// that can be represented.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Saturation {
+    /// Fail with [`Error::ValueOutOfRange`]
+    Fail,
+    /// Clamp to the nearest value that can be represented
+    Clamp,
+}
+
+/// The direction in which to round when encoding a value that cannot be
+/// represented exactly.  Directed rounding allows (e.g.) an undervoltage
+/// limit to be rounded down and an overvoltage limit to be rounded up, so
+/// that the encoded limit is never tighter than that specified.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Rounding {
+    /// Round to the nearest value that can be represented
+    Nearest,
+    /// Round toward zero
+    TowardZero,
+    /// Round toward positive infinity
+    Up,
+    /// Round toward negative infinity
+    Down,
+}
+
+impl Rounding {
+    //
+    // Resolves rounding toward zero into rounding up or down, as dictated
+    // by the sign of the value being encoded.
+    //
+    fn resolve(&self, x: f32) -> Self {
+        match self {
+            Rounding::TowardZero if x < 0.0 => Rounding::Up,
+            Rounding::TowardZero => Rounding::Down,
+            _ => *self,
+        }
+    }
+
+    //
+    // Rounds the specified quotient to an integer; the quotient must have
+    // been arrived at by scaling the value being encoded by a positive
+    // factor.
+    //
+    fn round<T: FloatCore>(&self, q: T) -> T {
+        match self {
+            Rounding::Nearest => q.round(),
+            Rounding::TowardZero => q.trunc(),
+            Rounding::Up => q.ceil(),
+            Rounding::Down => q.floor(),
+        }
+    }
+}
+
 ///
 /// A datum in the ULINEAR16 format.  ULINEAR16 is used only for voltage;
 /// the exponent comes from VOUT_MODE.
 ///
+#[derive(Copy, Clone, Debug)]
 pub struct ULinear16(pub u16, pub ULinear16Exponent);
 
 impl ULinear16 {
@@ -384,12 +1467,231 @@ impl ULinear16 {
     }
 
     pub fn from_real(x: f32, exp: ULinear16Exponent) -> Option<Self> {
-        let val = (x / f32::powi(2.0, exp.0.into())).round();
+        Self::try_from_real(x, exp, Saturation::Fail).ok()
+    }
 
-        if val > core::u16::MAX as f32 {
-            None
+    ///
+    /// Encodes the specified value with the specified exponent.  ULINEAR16
+    /// is unsigned, so negative values are out of range; values that are
+    /// out of range are either rejected or clamped as dictated by the
+    /// specified [`Saturation`] policy.  A value that is not a number is
+    /// always rejected.
+    ///
+    pub fn try_from_real(
+        x: f32,
+        exp: ULinear16Exponent,
+        saturation: Saturation,
+    ) -> Result<Self, Error> {
+        Self::try_from_real_rounded(x, exp, saturation, Rounding::Nearest)
+    }
+
+    /// As [`ULinear16::try_from_real`], but rounding as specified
+    pub fn try_from_real_rounded(
+        x: f32,
+        exp: ULinear16Exponent,
+        saturation: Saturation,
+        rounding: Rounding,
+    ) -> Result<Self, Error> {
+        if x.is_nan() || ULinear16Exponent::new(exp.0).is_none() {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        let val = rounding.round(x / f32::powi(2.0, exp.0.into()));
+
+        if (0.0..=u16::MAX as f32).contains(&val) {
+            Ok(Self(val as u16, exp))
+        } else {
+            match saturation {
+                Saturation::Fail => Err(Error::ValueOutOfRange),
+                Saturation::Clamp if val < 0.0 => Ok(Self(0, exp)),
+                Saturation::Clamp => Ok(Self(u16::MAX, exp)),
+            }
+        }
+    }
+
+    ///
+    /// Returns the value in millionths of its units (i.e., microvolts),
+    /// rounded to the nearest integer.  This uses only integer arithmetic.
+    ///
+    pub fn to_micros(&self) -> i64 {
+        let exp = self.1 .0;
+        let x = i128::from(self.0) * 1_000_000;
+
+        let micros = if exp >= 0 {
+            x << exp
         } else {
+            div_round(x, 1 << -exp)
+        };
+
+        saturate(micros)
+    }
+
+    ///
+    /// Encodes a value expressed in millionths of its units (i.e.,
+    /// microvolts) with the specified exponent, using only integer
+    /// arithmetic.  Returns `None` if the value cannot be represented.
+    ///
+    pub fn from_micros(micros: i64, exp: ULinear16Exponent) -> Option<Self> {
+        ULinear16Exponent::new(exp.0)?;
+
+        let x = i128::from(micros);
+
+        let val = if exp.0 >= 0 {
+            div_round(x, 1_000_000 << exp.0)
+        } else {
+            div_round(x << -exp.0, 1_000_000)
+        };
+
+        if (0..=u16::MAX.into()).contains(&val) {
             Some(Self(val as u16, exp))
+        } else {
+            None
         }
     }
 }
+
+///
+/// A datum in the SLINEAR16 format:  a two's complement mantissa with the
+/// exponent from VOUT_MODE (in its ULINEAR16 mode).  SLINEAR16 is used for
+/// voltages that may be negative, e.g. VOUT_TRIM or the outputs of bipolar
+/// rails.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct SLinear16(pub u16, pub ULinear16Exponent);
+
+impl SLinear16 {
+    pub fn to_real(&self) -> f32 {
+        let exp = self.1 .0;
+        (self.0 as i16) as f32 * f32::powi(2.0, exp.into())
+    }
+
+    pub fn from_real(x: f32, exp: ULinear16Exponent) -> Option<Self> {
+        Self::try_from_real(x, exp, Saturation::Fail).ok()
+    }
+
+    ///
+    /// Encodes the specified value with the specified exponent.  Values
+    /// that are out of range are either rejected or clamped as dictated by
+    /// the specified [`Saturation`] policy.  A value that is not a number
+    /// is always rejected.
+    ///
+    pub fn try_from_real(
+        x: f32,
+        exp: ULinear16Exponent,
+        saturation: Saturation,
+    ) -> Result<Self, Error> {
+        Self::try_from_real_rounded(x, exp, saturation, Rounding::Nearest)
+    }
+
+    /// As [`SLinear16::try_from_real`], but rounding as specified
+    pub fn try_from_real_rounded(
+        x: f32,
+        exp: ULinear16Exponent,
+        saturation: Saturation,
+        rounding: Rounding,
+    ) -> Result<Self, Error> {
+        if x.is_nan() || ULinear16Exponent::new(exp.0).is_none() {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        let val = rounding.round(x / f32::powi(2.0, exp.0.into()));
+
+        if (i16::MIN as f32..=i16::MAX as f32).contains(&val) {
+            Ok(Self(val as i16 as u16, exp))
+        } else {
+            match saturation {
+                Saturation::Fail => Err(Error::ValueOutOfRange),
+                Saturation::Clamp if val < 0.0 => {
+                    Ok(Self(i16::MIN as u16, exp))
+                }
+                Saturation::Clamp => Ok(Self(i16::MAX as u16, exp)),
+            }
+        }
+    }
+
+    ///
+    /// Returns the value in millionths of its units (i.e., microvolts),
+    /// rounded to the nearest integer.  This uses only integer arithmetic.
+    ///
+    pub fn to_micros(&self) -> i64 {
+        let exp = self.1 .0;
+        let x = i128::from(self.0 as i16) * 1_000_000;
+
+        let micros = if exp >= 0 {
+            x << exp
+        } else {
+            div_round(x, 1 << -exp)
+        };
+
+        saturate(micros)
+    }
+
+    ///
+    /// Encodes a value expressed in millionths of its units (i.e.,
+    /// microvolts) with the specified exponent, using only integer
+    /// arithmetic.  Returns `None` if the value cannot be represented.
+    ///
+    pub fn from_micros(micros: i64, exp: ULinear16Exponent) -> Option<Self> {
+        ULinear16Exponent::new(exp.0)?;
+
+        let x = i128::from(micros);
+
+        let val = if exp.0 >= 0 {
+            div_round(x, 1_000_000 << exp.0)
+        } else {
+            div_round(x << -exp.0, 1_000_000)
+        };
+
+        if (i16::MIN.into()..=i16::MAX.into()).contains(&val) {
+            Some(Self(val as i16 as u16, exp))
+        } else {
+            None
+        }
+    }
+}
+
+///
+/// A measurement along with its accuracy (if known), expressed as a
+/// percentage of the measured value.  Accuracy may come from the device
+/// definition (for measurements that have a specified accuracy), or from
+/// the device itself (e.g., via `MFR_PIN_ACCURACY`).
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Reading<T> {
+    pub value: T,
+    pub tolerance: Option<units::Percent>,
+}
+
+impl<T: Copy + From<f32> + Into<f32>> Reading<T> {
+    pub fn new(value: T, tolerance: Option<units::Percent>) -> Self {
+        Self { value, tolerance }
+    }
+
+    /// Returns the lowest and highest values that the measured quantity
+    /// could have, or `None` if the accuracy is not known.
+    pub fn bounds(&self) -> Option<(T, T)> {
+        let val: f32 = self.value.into();
+        let delta = (val * self.tolerance?.0 / 100.0).abs();
+
+        Some(((val - delta).into(), (val + delta).into()))
+    }
+
+    ///
+    /// Combines this reading with another to form a derived quantity that is
+    /// their product or quotient (e.g., power from voltage and current).  In
+    /// the worst case, relative errors sum; if either reading lacks an
+    /// accuracy, so does the result.
+    ///
+    pub fn combine<U, V>(&self, other: &Reading<U>, value: V) -> Reading<V>
+    where
+        U: Copy + From<f32> + Into<f32>,
+        V: Copy + From<f32> + Into<f32>,
+    {
+        let tolerance = match (self.tolerance, other.tolerance) {
+            (Some(lhs), Some(rhs)) => Some(units::Percent(lhs.0 + rhs.0)),
+            _ => None,
+        };
+
+        Reading::new(value, tolerance)
+    }
+}
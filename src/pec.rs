@@ -0,0 +1,180 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! SMBus Packet Error Checking.  The packet error code (PEC) is a CRC-8
+//! (with polynomial 0x07) over every byte of a transaction -- including the
+//! address bytes, with their read/write bits -- save the PEC itself.  A
+//! [`Pec`] can be used to compute the code incrementally as bytes are sent
+//! or received; [`compute`] and [`verify`] compute and check the code for
+//! an entire transaction of a given [`Operation`].
+//!
+//! Payloads are as they are interpreted elsewhere in this crate:  for block
+//! operations, the byte count is not part of the payload (and is inserted
+//! here), and for [`Operation::ProcessCall`], the payload is the two bytes
+//! written followed by the two bytes read.
+//!
+
+use crate::CommandCode;
+use crate::Error;
+use crate::Operation;
+
+/// Computes the SMBus packet error code (a CRC-8 with polynomial 0x07) over
+/// the specified bytes, starting from the specified CRC.
+fn crc8(crc: u8, bytes: &[u8]) -> u8 {
+    bytes.iter().fold(crc, |crc, &byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+/// An incremental packet error code computation
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Pec(u8);
+
+impl Pec {
+    /// Returns a new computation, over no bytes
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Adds the specified bytes to the computation
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.0 = crc8(self.0, bytes);
+    }
+
+    /// Adds the write address byte for the specified (7-bit) address
+    pub fn write_address(&mut self, address: u8) {
+        self.update(&[address << 1]);
+    }
+
+    /// Adds the read address byte for the specified (7-bit) address
+    pub fn read_address(&mut self, address: u8) {
+        self.update(&[(address << 1) | 1]);
+    }
+
+    /// Returns the packet error code for the bytes added thus far
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+}
+
+///
+/// Computes the packet error code for a transaction of the specified
+/// operation with the specified command code and payload to or from the
+/// device at the specified (7-bit) address.  The payload length must be
+/// exactly that dictated by the operation; for block operations, it must be
+/// at most 255 bytes.
+///
+pub fn compute(
+    address: u8,
+    code: u8,
+    op: Operation,
+    payload: &[u8],
+) -> Result<u8, Error> {
+    let (read, count, len) = match op {
+        Operation::SendByte => (false, false, 0),
+        Operation::WriteByte => (false, false, 1),
+        Operation::WriteWord => (false, false, 2),
+        Operation::WriteWord32 => (false, false, 4),
+        Operation::WriteBlock => (false, true, payload.len()),
+        Operation::ReadByte => (true, false, 1),
+        Operation::ReadWord => (true, false, 2),
+        Operation::ReadWord32 => (true, false, 4),
+        Operation::ReadBlock => (true, true, payload.len()),
+        Operation::ProcessCall => (true, false, 4),
+        _ => return Err(Error::InvalidOperation),
+    };
+
+    if payload.len() != len || len > u8::MAX as usize {
+        return Err(Error::InvalidLength);
+    }
+
+    //
+    // For a process call, the bytes written precede the repeated start.
+    //
+    let (written, payload) = match op {
+        Operation::ProcessCall => payload.split_at(2),
+        _ => (&payload[..0], payload),
+    };
+
+    let mut pec = Pec::new();
+    pec.write_address(address);
+    pec.update(&[code]);
+    pec.update(written);
+
+    if read {
+        pec.read_address(address);
+    }
+
+    if count {
+        pec.update(&[len as u8]);
+    }
+
+    pec.update(payload);
+
+    Ok(pec.value())
+}
+
+///
+/// Verifies the specified packet error code for a transaction, as computed
+/// by [`compute`], failing with [`Error::BadPec`] if it does not match.
+///
+pub fn verify(
+    address: u8,
+    code: u8,
+    op: Operation,
+    payload: &[u8],
+    pec: u8,
+) -> Result<(), Error> {
+    if compute(address, code, op, payload)? == pec {
+        Ok(())
+    } else {
+        Err(Error::BadPec)
+    }
+}
+
+///
+/// Computes the packet error code for a read or write of the specified
+/// (common) command, using its read or write operation; see [`compute`].
+///
+pub fn compute_command(
+    address: u8,
+    code: CommandCode,
+    write: bool,
+    payload: &[u8],
+) -> Result<u8, Error> {
+    let op = if write {
+        code.write_op()
+    } else {
+        code.read_op()
+    };
+    compute(address, code as u8, op, payload)
+}
+
+///
+/// Verifies the packet error code for a read or write of the specified
+/// (common) command, using its read or write operation; see [`verify`].
+///
+pub fn verify_command(
+    address: u8,
+    code: CommandCode,
+    write: bool,
+    payload: &[u8],
+    pec: u8,
+) -> Result<(), Error> {
+    let op = if write {
+        code.write_op()
+    } else {
+        code.read_op()
+    };
+    verify(address, code as u8, op, payload, pec)
+}
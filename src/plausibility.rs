@@ -0,0 +1,115 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Plausibility checking of telemetry.  A device that has wedged (or a bus
+//! that is corrupting data) will often still return readings -- they are
+//! just not readings that can be believed.  The checks here flag readings
+//! that are implausible:  raw values that are stuck across many polls,
+//! temperatures that are physically impossible, and current flowing through
+//! an output that has been commanded off.
+//!
+
+use crate::commands::OPERATION;
+use crate::units::{Amperes, Celsius};
+
+/// The lowest temperature that is considered plausible
+pub const TEMPERATURE_MIN: Celsius = Celsius(-60.0);
+
+/// The highest temperature that is considered plausible
+pub const TEMPERATURE_MAX: Celsius = Celsius(200.0);
+
+/// A reason that a reading is implausible
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Implausible {
+    /// The raw reading has been identical for the indicated number of
+    /// consecutive polls
+    Stuck(u32),
+    /// The temperature is outside of [`TEMPERATURE_MIN`] and
+    /// [`TEMPERATURE_MAX`] (or is not a number)
+    Temperature(Celsius),
+    /// The indicated current is flowing although the output is off
+    CurrentWhileOff(Amperes),
+}
+
+///
+/// A detector of stuck readings:  it is fed each raw reading for a single
+/// command (e.g., the LINEAR11 word returned by READ_IOUT), and flags the
+/// reading as stuck if it has been identical for `threshold` consecutive
+/// polls.  Note that some readings can legitimately remain unchanged (e.g.,
+/// READ_VOUT of a well-regulated rail); this is best applied to readings that
+/// are expected to exhibit noise.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct StuckDetector {
+    threshold: u32,
+    last: Option<u16>,
+    polls: u32,
+}
+
+impl StuckDetector {
+    /// Returns a detector that flags a reading identical for `threshold`
+    /// consecutive polls
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold,
+            last: None,
+            polls: 0,
+        }
+    }
+
+    /// Checks the next raw reading
+    pub fn check(&mut self, raw: u16) -> Result<(), Implausible> {
+        if self.last == Some(raw) {
+            self.polls = self.polls.saturating_add(1);
+        } else {
+            self.last = Some(raw);
+            self.polls = 1;
+        }
+
+        if self.polls >= self.threshold {
+            Err(Implausible::Stuck(self.polls))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Resets the detector, e.g. after the device has been reset
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.polls = 0;
+    }
+}
+
+/// Checks that a temperature is physically possible
+pub fn check_temperature(temp: Celsius) -> Result<(), Implausible> {
+    if temp.0 >= TEMPERATURE_MIN.0 && temp.0 <= TEMPERATURE_MAX.0 {
+        Ok(())
+    } else {
+        Err(Implausible::Temperature(temp))
+    }
+}
+
+///
+/// Checks that no more than `threshold` current is flowing if OPERATION
+/// indicates that the output is off.  (Note that if the output is controlled
+/// by the CONTROL pin rather than OPERATION, the output may be on
+/// regardless of OPERATION; this check should only be used for outputs
+/// controlled by OPERATION.)
+///
+pub fn check_current(
+    operation: OPERATION::CommandData,
+    current: Amperes,
+    threshold: Amperes,
+) -> Result<(), Implausible> {
+    let off = operation.get_on_off_state() == Some(OPERATION::OnOffState::Off);
+
+    if off && (current.0 > threshold.0 || current.0 < -threshold.0) {
+        Err(Implausible::CurrentWhileOff(current))
+    } else {
+        Ok(())
+    }
+}
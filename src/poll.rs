@@ -0,0 +1,326 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Telemetry polling.  A [`Poller`] yields the sequence of reads that
+//! constitute one pass over a device's telemetry (per
+//! [`PmbusDevice::TELEMETRY`]) across its pages, and has no opinion on how
+//! (or how often) each read is performed.  With the `async` feature,
+//! [`readings`] builds upon a poller to yield an asynchronous stream of
+//! interpreted readings, performing the reads via a caller-provided
+//! [`Transport`] and delaying between passes with a caller-provided timer
+//! (e.g., an `embassy_time::Delay`), for use by Embassy-based monitoring
+//! tasks.
+//!
+
+use crate::PmbusDevice;
+use core::marker::PhantomData;
+
+#[cfg(feature = "async")]
+use crate::{
+    commands::VOUT_MODE, units::Units, Command, CommandCode, Error, Format,
+    VOutModeCommandData,
+};
+
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs;
+
+#[cfg(feature = "async")]
+use futures_util::stream::{self, Stream};
+
+///
+/// A single read in a polling pass:  the page to select (if the device is
+/// paged), and the command to read.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Poll<C> {
+    pub page: Option<u8>,
+    pub command: C,
+}
+
+///
+/// An iterator over the reads constituting a pass over the telemetry for
+/// device `D`.  Reads are ordered by page, and then by command.
+///
+#[derive(Clone, Debug)]
+pub struct Poller<D> {
+    pages: Option<u8>,
+    page: u8,
+    index: usize,
+    device: PhantomData<D>,
+}
+
+impl<D: PmbusDevice> Poller<D> {
+    /// Returns a poller for an unpaged device
+    pub fn new() -> Self {
+        Self {
+            pages: None,
+            page: 0,
+            index: 0,
+            device: PhantomData,
+        }
+    }
+
+    /// Returns a poller for a device with the specified number of pages
+    pub fn paged(pages: u8) -> Self {
+        Self {
+            pages: Some(pages),
+            page: 0,
+            index: 0,
+            device: PhantomData,
+        }
+    }
+
+    /// Returns the number of reads in a pass
+    pub fn pass_len(&self) -> usize {
+        let pages = self.pages.map_or(1, usize::from);
+        pages * D::TELEMETRY.len()
+    }
+
+    /// Restarts the poller at the beginning of a pass
+    pub fn restart(&mut self) {
+        self.page = 0;
+        self.index = 0;
+    }
+}
+
+impl<D: PmbusDevice> Default for Poller<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: PmbusDevice> Iterator for Poller<D> {
+    type Item = Poll<D::CommandCode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pages) = self.pages {
+                if self.page >= pages {
+                    return None;
+                }
+            } else if self.page > 0 {
+                return None;
+            }
+
+            if let Some(&command) = D::TELEMETRY.get(self.index) {
+                self.index += 1;
+
+                return Some(Poll {
+                    page: self.pages.map(|_| self.page),
+                    command,
+                });
+            }
+
+            self.index = 0;
+            self.page += 1;
+        }
+    }
+}
+
+/// The largest telemetry payload that [`readings`] will read
+#[cfg(feature = "async")]
+pub const READING_MAX: usize = 32;
+
+///
+/// An asynchronous transport to a single device, as provided by the caller
+/// (e.g., atop an Embassy I2C driver).  As elsewhere, payloads exclude the
+/// byte count of block operations.
+///
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait Transport {
+    type Error;
+
+    /// Reads the command with the specified code via the specified
+    /// operation into `buf`, returning the length of the payload
+    async fn read(
+        &mut self,
+        code: u8,
+        op: crate::Operation,
+        buf: &mut [u8],
+    ) -> Result<usize, Self::Error>;
+
+    /// Writes the specified payload to the command with the specified code
+    /// via the specified operation
+    async fn write(
+        &mut self,
+        code: u8,
+        op: crate::Operation,
+        payload: &[u8],
+    ) -> Result<(), Self::Error>;
+}
+
+/// An error encountered while polling
+#[cfg(feature = "async")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PollError<E> {
+    /// The transport failed
+    Transport(E),
+    /// The payload read from the device could not be interpreted
+    Pmbus(Error),
+}
+
+/// A single telemetry reading
+#[cfg(feature = "async")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Reading<C> {
+    /// The page read, if the device is paged
+    pub page: Option<u8>,
+    /// The command read
+    pub command: C,
+    /// The value, or `None` if the payload did not denote a real number
+    pub value: Option<f32>,
+    /// The units of the value, if any
+    pub units: Option<Units>,
+}
+
+#[cfg(feature = "async")]
+type ReadingResult<D, E> =
+    Result<Reading<<D as PmbusDevice>::CommandCode>, PollError<E>>;
+
+#[cfg(feature = "async")]
+struct Readings<'a, D, T, W> {
+    poller: Poller<D>,
+    transport: &'a mut T,
+    delay: &'a mut W,
+    interval_ms: u32,
+    page: Option<u8>,
+    mode: Option<(Option<u8>, VOutModeCommandData)>,
+}
+
+#[cfg(feature = "async")]
+impl<D: PmbusDevice, T: Transport, W: DelayNs> Readings<'_, D, T, W> {
+    async fn next(&mut self) -> ReadingResult<D, T::Error> {
+        let poll = loop {
+            match self.poller.next() {
+                Some(poll) => break poll,
+                None => {
+                    self.delay.delay_ms(self.interval_ms).await;
+                    self.poller.restart();
+                    self.mode = None;
+                }
+            }
+        };
+
+        //
+        // The device retains its page across passes, so we only write PAGE
+        // when it differs from the page that we last wrote.
+        //
+        if let Some(page) = poll.page {
+            if self.page != Some(page) {
+                let cmd = CommandCode::PAGE;
+
+                self.transport
+                    .write(cmd as u8, cmd.write_op(), &[page])
+                    .await
+                    .map_err(PollError::Transport)?;
+
+                self.page = Some(page);
+            }
+        }
+
+        let code = D::code(poll.command);
+
+        let mode = match D::DEVICE.format(code) {
+            Some(Format::VOutMode) | Some(Format::SignedVOutMode) => {
+                self.vout_mode(poll.page).await?
+            }
+            _ => VOUT_MODE::CommandData(0),
+        };
+
+        let mut buf = [0u8; READING_MAX];
+
+        let len = self
+            .transport
+            .read(code, poll.command.read_op(), &mut buf)
+            .await
+            .map_err(PollError::Transport)?;
+
+        let payload = buf
+            .get(..len)
+            .ok_or(PollError::Pmbus(Error::InvalidLength))?;
+
+        let mut value = None;
+
+        D::interpret(poll.command, payload, mode, |_, v| {
+            if value.is_none() {
+                value = v.as_float();
+            }
+        })
+        .map_err(PollError::Pmbus)?;
+
+        Ok(Reading {
+            page: poll.page,
+            command: poll.command,
+            value,
+            units: D::DEVICE.units(code),
+        })
+    }
+
+    async fn vout_mode(
+        &mut self,
+        page: Option<u8>,
+    ) -> Result<VOutModeCommandData, PollError<T::Error>> {
+        match self.mode {
+            Some((p, mode)) if p == page => return Ok(mode),
+            _ => {}
+        }
+
+        let cmd = CommandCode::VOUT_MODE;
+        let mut buf = [0u8; 1];
+
+        let len = self
+            .transport
+            .read(cmd as u8, cmd.read_op(), &mut buf)
+            .await
+            .map_err(PollError::Transport)?;
+
+        let mode = buf
+            .get(..len)
+            .and_then(VOUT_MODE::CommandData::from_slice)
+            .ok_or(PollError::Pmbus(Error::ShortData))?;
+
+        self.mode = Some((page, mode));
+        Ok(mode)
+    }
+}
+
+///
+/// Returns an endless stream of the readings of the device behind the
+/// specified transport, in the order dictated by `poller`.  Following each
+/// pass, the stream delays for `interval_ms` milliseconds via `delay`
+/// before beginning the next.  PAGE is written as needed to read each page,
+/// and VOUT_MODE is read (once per page per pass) for any command whose
+/// value depends on it.  A failed read is yielded as an error, but does
+/// not end the stream.
+///
+#[cfg(feature = "async")]
+pub fn readings<'a, D, T, W>(
+    poller: Poller<D>,
+    transport: &'a mut T,
+    delay: &'a mut W,
+    interval_ms: u32,
+) -> impl Stream<Item = ReadingResult<D, T::Error>> + 'a
+where
+    D: PmbusDevice + 'a,
+    T: Transport,
+    W: DelayNs,
+{
+    let readings = Readings {
+        poller,
+        transport,
+        delay,
+        interval_ms,
+        page: None,
+        mode: None,
+    };
+
+    stream::unfold(readings, |mut readings| async move {
+        let reading = readings.next().await;
+        Some((reading, readings))
+    })
+}
@@ -0,0 +1,253 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! The SMBus byte sequences of PMBus transactions.  A transaction consists
+//! of a write phase -- the bytes that follow the write address, beginning
+//! with the command code -- and, for reads and process calls, a read phase
+//! consisting of the bytes that follow a repeated start and the read
+//! address.  [`encode`] produces the write phase of a transaction along
+//! with the [`Layout`] of the transaction (that is, how many bytes are to
+//! be read after the repeated start, if any); [`decode`] takes the phases
+//! of a captured transaction and recovers its command code and payload,
+//! checking its PEC (if any).  Both firmware drivers and bus analyzers can
+//! therefore share one encoding.
+//!
+//! As elsewhere, payloads exclude the byte count of block operations, and
+//! the payload of an [`Operation::ProcessCall`] consists of the two bytes
+//! written followed by the two bytes read.
+//!
+
+use crate::encode;
+use crate::pec;
+use crate::CommandCode;
+use crate::Error;
+use crate::Operation;
+
+/// The read phase of a transaction
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Read {
+    /// The specified number of bytes (including any PEC) are read
+    Fixed(usize),
+    /// A byte count is read, followed by that many bytes (and any PEC)
+    Block,
+}
+
+/// The layout of a transaction on the wire
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Layout {
+    /// The number of bytes written following the write address
+    pub write: usize,
+    /// The read phase following the repeated start, if any
+    pub read: Option<Read>,
+}
+
+/// The status of the PEC of a decoded transaction
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PecStatus {
+    /// The transaction has no PEC
+    Absent,
+    /// The PEC matches the transaction
+    Valid,
+    /// The PEC does not match the transaction
+    Invalid,
+}
+
+/// A decoded transaction
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Decoded {
+    /// The command code
+    pub code: u8,
+    /// The length of the payload
+    pub len: usize,
+    /// The status of the PEC
+    pub pec: PecStatus,
+}
+
+fn fixed(op: Operation) -> Option<usize> {
+    match op {
+        Operation::SendByte => Some(0),
+        Operation::ReadByte | Operation::WriteByte => Some(1),
+        Operation::ReadWord | Operation::WriteWord => Some(2),
+        Operation::ReadWord32 | Operation::WriteWord32 => Some(4),
+        _ => None,
+    }
+}
+
+fn reads(op: Operation) -> Result<bool, Error> {
+    match op {
+        Operation::SendByte
+        | Operation::WriteByte
+        | Operation::WriteWord
+        | Operation::WriteWord32
+        | Operation::WriteBlock => Ok(false),
+        Operation::ReadByte
+        | Operation::ReadWord
+        | Operation::ReadWord32
+        | Operation::ReadBlock
+        | Operation::ProcessCall => Ok(true),
+        _ => Err(Error::InvalidOperation),
+    }
+}
+
+///
+/// Encodes the write phase of a transaction of the specified operation
+/// with the specified command code to the device at the specified (7-bit)
+/// address into `buf`, returning the layout of the transaction.  For a
+/// write, the payload is that to be written (see [`encode::write`]); for a
+/// read, the payload must be empty; for a process call, the payload
+/// consists of the two bytes to be written.  If `pec` is set, a write
+/// carries its PEC, and the read phase of a read includes it.
+///
+pub fn encode(
+    buf: &mut [u8],
+    address: u8,
+    code: u8,
+    op: Operation,
+    payload: &[u8],
+    pec: bool,
+) -> Result<Layout, Error> {
+    if !reads(op)? {
+        let write = encode::write(buf, address, code, op, payload, pec)?;
+        return Ok(Layout { write, read: None });
+    }
+
+    let (len, read) = match op {
+        Operation::ProcessCall => (2, Read::Fixed(2)),
+        Operation::ReadBlock => (0, Read::Block),
+        _ => (0, Read::Fixed(fixed(op).ok_or(Error::InvalidOperation)?)),
+    };
+
+    if payload.len() != len {
+        return Err(Error::InvalidLength);
+    }
+
+    let read = match read {
+        Read::Fixed(n) if pec => Read::Fixed(n + 1),
+        read => read,
+    };
+
+    let write = 1 + len;
+    let buf = buf.get_mut(..write).ok_or(Error::BufferTooSmall)?;
+
+    buf[0] = code;
+    buf[1..].copy_from_slice(payload);
+
+    Ok(Layout {
+        write,
+        read: Some(read),
+    })
+}
+
+///
+/// Encodes the write phase of a read or write of the specified (common)
+/// command, using its read or write operation; see [`encode`].
+///
+pub fn encode_command(
+    buf: &mut [u8],
+    address: u8,
+    code: CommandCode,
+    write: bool,
+    payload: &[u8],
+    pec: bool,
+) -> Result<Layout, Error> {
+    let op = if write {
+        code.write_op()
+    } else {
+        code.read_op()
+    };
+    encode(buf, address, code as u8, op, payload, pec)
+}
+
+///
+/// Decodes a captured transaction of the specified operation with the
+/// device at the specified (7-bit) address, given the bytes of its write
+/// phase (following the write address) and of its read phase (following
+/// the read address; empty for a write).  The payload is copied into
+/// `buf`.  If `pec` is set, the last byte of the transaction is taken to
+/// be its PEC, which is checked; a mismatch is indicated in the result
+/// rather than as an error, as the transaction may still be of interest.
+///
+pub fn decode(
+    buf: &mut [u8],
+    address: u8,
+    op: Operation,
+    written: &[u8],
+    read: &[u8],
+    pec: bool,
+) -> Result<Decoded, Error> {
+    let reads = reads(op)?;
+    let (&code, written) = written.split_first().ok_or(Error::ShortData)?;
+
+    //
+    // Split off the PEC (if any) from the phase that ends the transaction.
+    //
+    let ends = if reads { read } else { written };
+
+    let (ends, actual) = match (pec, ends.split_last()) {
+        (false, _) => (ends, None),
+        (true, Some((&actual, rest))) => (rest, Some(actual)),
+        (true, None) => return Err(Error::ShortData),
+    };
+
+    let (written, read) = if reads {
+        (written, ends)
+    } else if read.is_empty() {
+        (ends, read)
+    } else {
+        return Err(Error::InvalidLength);
+    };
+
+    //
+    // For a block, split off (and check) the byte count.
+    //
+    let (written, read) = match op {
+        Operation::WriteBlock | Operation::ReadBlock => {
+            let data = if reads { read } else { written };
+            let (&count, data) = data.split_first().ok_or(Error::ShortData)?;
+
+            if data.len() != count as usize {
+                return Err(Error::InvalidLength);
+            }
+
+            if reads {
+                (written, data)
+            } else {
+                (data, read)
+            }
+        }
+        _ => (written, read),
+    };
+
+    let expected = match op {
+        Operation::ProcessCall => (2, 2),
+        Operation::WriteBlock => (written.len(), 0),
+        Operation::ReadBlock => (0, read.len()),
+        _ if reads => (0, fixed(op).ok_or(Error::InvalidOperation)?),
+        _ => (fixed(op).ok_or(Error::InvalidOperation)?, 0),
+    };
+
+    if (written.len(), read.len()) != expected {
+        return Err(Error::InvalidLength);
+    }
+
+    let len = written.len() + read.len();
+    let payload = buf.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+
+    payload[..written.len()].copy_from_slice(written);
+    payload[written.len()..].copy_from_slice(read);
+
+    let pec = match actual {
+        None => PecStatus::Absent,
+        Some(actual) => match pec::verify(address, code, op, payload, actual) {
+            Ok(()) => PecStatus::Valid,
+            Err(Error::BadPec) => PecStatus::Invalid,
+            Err(err) => return Err(err),
+        },
+    };
+
+    Ok(Decoded { code, len, pec })
+}
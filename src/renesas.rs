@@ -11,6 +11,7 @@
 // in the ISL68224 definition
 //
 use crate::commands::isl68224::*;
+use crate::stream::BlockField;
 
 ///
 /// Structure that defines per-rail blackbox information.  There are three
@@ -31,65 +32,106 @@ pub struct BlackboxRail {
 }
 
 ///
-/// Enum for a rail index in the blackbox recording.  Even though not
-/// every Renesas part supports it, the blackbox records have room for
-/// three rails.
+/// The offsets of the fields of a rail within a blackbox entry.  Even
+/// though not every Renesas part supports it, the blackbox records have
+/// room for three rails.
 ///
-enum RailIndex {
-    Rail0,
-    Rail1,
-    Rail2,
+struct RailOffsets {
+    uptime: usize,
+    first_fault: usize,
+    status: usize,
+    vout_status: usize,
+    iout_status: usize,
+    temp_status: usize,
+    input_status: usize,
+    vin: usize,
+    vout: usize,
+    iin: usize,
+    iout: usize,
 }
 
+//
+// The blackbox is laid out as 32-bit words, with fields that are smaller
+// than a word packed within them; we express offsets as a word and a byte
+// offset within it, as the datasheet does.
+//
+const fn offset(word: usize, offs: usize) -> usize {
+    (word * 4) + offs
+}
+
+const RAIL_OFFSETS: [RailOffsets; 3] = [
+    RailOffsets {
+        uptime: offset(1, 0),
+        first_fault: offset(5, 0),
+        status: offset(11, 0),
+        vout_status: offset(13, 1),
+        iout_status: offset(14, 2),
+        temp_status: offset(15, 3),
+        input_status: offset(15, 3),
+        vin: offset(16, 0),
+        vout: offset(18, 2),
+        iin: offset(19, 0),
+        iout: offset(21, 2),
+    },
+    RailOffsets {
+        uptime: offset(2, 0),
+        first_fault: offset(6, 0),
+        status: offset(12, 2),
+        vout_status: offset(13, 0),
+        iout_status: offset(14, 1),
+        temp_status: offset(15, 2),
+        input_status: offset(16, 3),
+        vin: offset(17, 2),
+        vout: offset(18, 0),
+        iin: offset(20, 2),
+        iout: offset(21, 0),
+    },
+    RailOffsets {
+        uptime: offset(3, 0),
+        first_fault: offset(7, 0),
+        status: offset(12, 0),
+        vout_status: offset(14, 3),
+        iout_status: offset(14, 0),
+        temp_status: offset(15, 1),
+        input_status: offset(16, 2),
+        vin: offset(17, 0),
+        vout: offset(19, 2),
+        iin: offset(20, 0),
+        iout: offset(22, 2),
+    },
+];
+
+const CONTROLLER_FIRST_FAULT: usize = offset(4, 0);
+const MFR_SPECIFIC: usize = offset(13, 2);
+const CML_STATUS: usize = offset(13, 3);
+
 macro_rules! bb_field {
-    ($slice:expr, $cmd:tt, $word:expr, $offs:expr) => {
-        $cmd::CommandData::from_slice(&$slice[($word * 4) + $offs..]).unwrap()
+    ($slice:expr, $cmd:tt, $offset:expr) => {
+        $cmd::CommandData::from_slice(
+            $slice.get($offset..$offset + $cmd::CommandData::len())?,
+        )?
     };
 }
 
 impl BlackboxRail {
-    fn from_slice(buf: &[u8], rail: RailIndex) -> Self {
-        match rail {
-            RailIndex::Rail0 => BlackboxRail {
-                uptime: bb_field!(buf, UptimeCounter, 1, 0),
-                first_fault: bb_field!(buf, RailFault, 5, 0),
-                status: bb_field!(buf, STATUS_WORD, 11, 0),
-                vout_status: bb_field!(buf, STATUS_VOUT, 13, 1),
-                iout_status: bb_field!(buf, STATUS_IOUT, 14, 2),
-                temp_status: bb_field!(buf, STATUS_TEMPERATURE, 15, 3),
-                input_status: bb_field!(buf, STATUS_INPUT, 15, 3),
-                vin: bb_field!(buf, READ_VIN, 16, 0),
-                vout: bb_field!(buf, READ_VOUT, 18, 2),
-                iin: bb_field!(buf, READ_IIN, 19, 0),
-                iout: bb_field!(buf, READ_IOUT, 21, 2),
-            },
-            RailIndex::Rail1 => BlackboxRail {
-                uptime: bb_field!(buf, UptimeCounter, 2, 0),
-                first_fault: bb_field!(buf, RailFault, 6, 0),
-                status: bb_field!(buf, STATUS_WORD, 12, 2),
-                vout_status: bb_field!(buf, STATUS_VOUT, 13, 0),
-                iout_status: bb_field!(buf, STATUS_IOUT, 14, 1),
-                temp_status: bb_field!(buf, STATUS_TEMPERATURE, 15, 2),
-                input_status: bb_field!(buf, STATUS_INPUT, 16, 3),
-                vin: bb_field!(buf, READ_VIN, 17, 2),
-                vout: bb_field!(buf, READ_VOUT, 18, 0),
-                iin: bb_field!(buf, READ_IIN, 20, 2),
-                iout: bb_field!(buf, READ_IOUT, 21, 0),
-            },
-            RailIndex::Rail2 => BlackboxRail {
-                uptime: bb_field!(buf, UptimeCounter, 3, 0),
-                first_fault: bb_field!(buf, RailFault, 7, 0),
-                status: bb_field!(buf, STATUS_WORD, 12, 0),
-                vout_status: bb_field!(buf, STATUS_VOUT, 14, 3),
-                iout_status: bb_field!(buf, STATUS_IOUT, 14, 0),
-                temp_status: bb_field!(buf, STATUS_TEMPERATURE, 15, 1),
-                input_status: bb_field!(buf, STATUS_INPUT, 16, 2),
-                vin: bb_field!(buf, READ_VIN, 17, 0),
-                vout: bb_field!(buf, READ_VOUT, 19, 2),
-                iin: bb_field!(buf, READ_IIN, 20, 0),
-                iout: bb_field!(buf, READ_IOUT, 22, 2),
-            },
-        }
+    fn from_slice(buf: &[u8], offsets: &RailOffsets) -> Option<Self> {
+        Some(BlackboxRail {
+            uptime: bb_field!(buf, UptimeCounter, offsets.uptime),
+            first_fault: bb_field!(buf, RailFault, offsets.first_fault),
+            status: bb_field!(buf, STATUS_WORD, offsets.status),
+            vout_status: bb_field!(buf, STATUS_VOUT, offsets.vout_status),
+            iout_status: bb_field!(buf, STATUS_IOUT, offsets.iout_status),
+            temp_status: bb_field!(
+                buf,
+                STATUS_TEMPERATURE,
+                offsets.temp_status
+            ),
+            input_status: bb_field!(buf, STATUS_INPUT, offsets.input_status),
+            vin: bb_field!(buf, READ_VIN, offsets.vin),
+            vout: bb_field!(buf, READ_VOUT, offsets.vout),
+            iin: bb_field!(buf, READ_IIN, offsets.iin),
+            iout: bb_field!(buf, READ_IOUT, offsets.iout),
+        })
     }
 }
 
@@ -105,20 +147,99 @@ pub struct Blackbox {
 }
 
 impl Blackbox {
-    pub fn from_slice(buf: &[u8]) -> Self {
-        Self {
-            controller_first_fault: bb_field!(buf, ControllerFault, 4, 0),
-            cml_status: bb_field!(buf, STATUS_CML, 13, 3),
-            mfr_specific: bb_field!(buf, STATUS_MFR_SPECIFIC, 13, 2),
+    ///
+    /// Parses a blackbox entry from its raw contents, returning `None` if
+    /// the buffer is too short to contain an entire entry.
+    ///
+    pub fn from_slice(buf: &[u8]) -> Option<Self> {
+        Some(Self {
+            controller_first_fault: bb_field!(
+                buf,
+                ControllerFault,
+                CONTROLLER_FIRST_FAULT
+            ),
+            cml_status: bb_field!(buf, STATUS_CML, CML_STATUS),
+            mfr_specific: bb_field!(buf, STATUS_MFR_SPECIFIC, MFR_SPECIFIC),
             rails: [
-                BlackboxRail::from_slice(buf, RailIndex::Rail0),
-                BlackboxRail::from_slice(buf, RailIndex::Rail1),
-                BlackboxRail::from_slice(buf, RailIndex::Rail2),
+                BlackboxRail::from_slice(buf, &RAIL_OFFSETS[0])?,
+                BlackboxRail::from_slice(buf, &RAIL_OFFSETS[1])?,
+                BlackboxRail::from_slice(buf, &RAIL_OFFSETS[2])?,
             ],
-        }
+        })
     }
 }
 
+macro_rules! bb_stream {
+    ($name:expr, $cmd:tt, $offset:expr) => {
+        BlockField {
+            name: $name,
+            offset: $offset,
+            len: $cmd::CommandData::len(),
+            interpret: |payload, mode, iter| {
+                match $cmd::CommandData::from_slice(payload) {
+                    Some(data) => data.interpret(mode, iter),
+                    None => Err(Error::ShortData),
+                }
+            },
+        }
+    };
+}
+
+macro_rules! bb_rail {
+    ($name:expr, $cmd:tt, $rail:expr, $field:ident) => {
+        bb_stream!($name, $cmd, RAIL_OFFSETS[$rail].$field)
+    };
+}
+
+///
+/// The layout of a blackbox entry, allowing it to be interpreted with a
+/// [`BlockStream`](crate::stream::BlockStream) as it is read from the device
+/// rather than requiring the entire entry to be present (as
+/// [`Blackbox::from_slice`] does).  The fields are in order of offset.
+///
+pub const BLACKBOX_LAYOUT: [BlockField; 36] = [
+    bb_rail!("rail0.uptime", UptimeCounter, 0, uptime),
+    bb_rail!("rail1.uptime", UptimeCounter, 1, uptime),
+    bb_rail!("rail2.uptime", UptimeCounter, 2, uptime),
+    bb_stream!(
+        "controller_first_fault",
+        ControllerFault,
+        CONTROLLER_FIRST_FAULT
+    ),
+    bb_rail!("rail0.first_fault", RailFault, 0, first_fault),
+    bb_rail!("rail1.first_fault", RailFault, 1, first_fault),
+    bb_rail!("rail2.first_fault", RailFault, 2, first_fault),
+    bb_rail!("rail0.status", STATUS_WORD, 0, status),
+    bb_rail!("rail2.status", STATUS_WORD, 2, status),
+    bb_rail!("rail1.status", STATUS_WORD, 1, status),
+    bb_rail!("rail1.vout_status", STATUS_VOUT, 1, vout_status),
+    bb_rail!("rail0.vout_status", STATUS_VOUT, 0, vout_status),
+    bb_stream!("mfr_specific", STATUS_MFR_SPECIFIC, MFR_SPECIFIC),
+    bb_stream!("cml_status", STATUS_CML, CML_STATUS),
+    bb_rail!("rail2.iout_status", STATUS_IOUT, 2, iout_status),
+    bb_rail!("rail1.iout_status", STATUS_IOUT, 1, iout_status),
+    bb_rail!("rail0.iout_status", STATUS_IOUT, 0, iout_status),
+    bb_rail!("rail2.vout_status", STATUS_VOUT, 2, vout_status),
+    bb_rail!("rail2.temp_status", STATUS_TEMPERATURE, 2, temp_status),
+    bb_rail!("rail1.temp_status", STATUS_TEMPERATURE, 1, temp_status),
+    bb_rail!("rail0.temp_status", STATUS_TEMPERATURE, 0, temp_status),
+    bb_rail!("rail0.input_status", STATUS_INPUT, 0, input_status),
+    bb_rail!("rail0.vin", READ_VIN, 0, vin),
+    bb_rail!("rail2.input_status", STATUS_INPUT, 2, input_status),
+    bb_rail!("rail1.input_status", STATUS_INPUT, 1, input_status),
+    bb_rail!("rail2.vin", READ_VIN, 2, vin),
+    bb_rail!("rail1.vin", READ_VIN, 1, vin),
+    bb_rail!("rail1.vout", READ_VOUT, 1, vout),
+    bb_rail!("rail0.vout", READ_VOUT, 0, vout),
+    bb_rail!("rail0.iin", READ_IIN, 0, iin),
+    bb_rail!("rail2.vout", READ_VOUT, 2, vout),
+    bb_rail!("rail2.iin", READ_IIN, 2, iin),
+    bb_rail!("rail1.iin", READ_IIN, 1, iin),
+    bb_rail!("rail1.iout", READ_IOUT, 1, iout),
+    bb_rail!("rail0.iout", READ_IOUT, 0, iout),
+    bb_rail!("rail2.iout", READ_IOUT, 2, iout),
+];
+
 ///
 /// The Gen2 multiphase parts have a `DMAADDR` interface for reading memory.
 /// This interfaces takes a 32-bit word offset into memory rather than an
@@ -0,0 +1,48 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+/// A revision of the PMBus specification, in increasing order
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+pub enum SpecRevision {
+    Rev1_0,
+    Rev1_1,
+    Rev1_2,
+    Rev1_3,
+    Rev1_3_1,
+}
+
+impl SpecRevision {
+    ///
+    /// Returns the revision of Part II of the specification indicated by
+    /// the contents of PMBUS_REVISION (whose low nibble denotes the Part II
+    /// revision), or `None` if it is not known.  Note that PMBus 1.3.1 does
+    /// not have its own encoding:  a device implementing it reports 1.3
+    /// (see [`SpecRevision::admits`]).
+    ///
+    pub fn from_pmbus_revision(val: u8) -> Option<Self> {
+        match val & 0xf {
+            0 => Some(SpecRevision::Rev1_0),
+            1 => Some(SpecRevision::Rev1_1),
+            2 => Some(SpecRevision::Rev1_2),
+            3 => Some(SpecRevision::Rev1_3),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Returns true if a command introduced by the specified revision may
+    /// be implemented by a device at this revision.  As 1.3.1 has no
+    /// encoding in PMBUS_REVISION of its own (and only adds commands to
+    /// 1.3), a device reporting 1.3 is admitted the commands of 1.3.1,
+    /// lest they be denied to the very devices that implement them.
+    ///
+    pub fn admits(&self, introduced: SpecRevision) -> bool {
+        match (*self, introduced) {
+            (SpecRevision::Rev1_3, SpecRevision::Rev1_3_1) => true,
+            _ => introduced <= *self,
+        }
+    }
+}
@@ -0,0 +1,381 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Sequences of operations.  Common tasks (e.g., clearing faults across all
+//! pages of a device, bringing up a power tree, or ramping an output
+//! voltage) consist of a sequence of transactions, expressed here as
+//! iterators of [`Step`]s that a consumer performs in order, handing any
+//! reads back for verification.
+//!
+
+use crate::commands::ON_OFF_CONFIG;
+use crate::commands::OPERATION;
+use crate::commands::STATUS_WORD;
+use crate::commands::VOUT_COMMAND;
+use crate::units::{Volts, VoltsPerMillisecond};
+use crate::CommandCode;
+use crate::Error;
+use crate::VOutModeCommandData;
+use core::time::Duration;
+
+/// A single step in a sequence of operations
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Step {
+    /// Select the specified page via PAGE
+    Page(u8),
+    /// Send the specified command, which has no data
+    Send(CommandCode),
+    /// Write the specified byte to the specified command
+    WriteByte(CommandCode, u8),
+    /// Write the specified word to the specified command
+    WriteWord(CommandCode, u16),
+    /// Read the specified command, handing the result back for
+    /// verification
+    Read(CommandCode),
+    /// Wait for the specified duration
+    Delay(Duration),
+}
+
+///
+/// An iterator over the steps required to clear faults on each page of a
+/// device:  for each page, the page is selected, CLEAR_FAULTS is sent, and
+/// STATUS_WORD is read.  Each STATUS_WORD read should be passed to
+/// [`verify_cleared`] to determine if any faults are sticky.
+///
+#[derive(Clone, Debug)]
+pub struct ClearFaults {
+    pages: Option<u8>,
+    page: u8,
+    step: u8,
+}
+
+impl ClearFaults {
+    /// Returns the steps to clear faults for an unpaged device
+    pub fn new() -> Self {
+        Self {
+            pages: None,
+            page: 0,
+            step: 0,
+        }
+    }
+
+    /// Returns the steps to clear faults for a device with the specified
+    /// number of pages
+    pub fn paged(pages: u8) -> Self {
+        Self {
+            pages: Some(pages),
+            page: 0,
+            step: 0,
+        }
+    }
+}
+
+impl Default for ClearFaults {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for ClearFaults {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        loop {
+            match self.pages {
+                Some(pages) if self.page >= pages => return None,
+                None if self.page > 0 => return None,
+                _ => {}
+            }
+
+            let step = self.step;
+            self.step += 1;
+
+            match step {
+                0 => {
+                    if self.pages.is_some() {
+                        return Some(Step::Page(self.page));
+                    }
+                }
+                1 => return Some(Step::Send(CommandCode::CLEAR_FAULTS)),
+                2 => return Some(Step::Read(CommandCode::STATUS_WORD)),
+                _ => {
+                    self.step = 0;
+                    self.page += 1;
+                }
+            }
+        }
+    }
+}
+
+/// The bits in STATUS_WORD that indicate status rather than a fault, and
+/// are therefore not cleared by CLEAR_FAULTS
+const STATUS_WORD_NONFAULT: u16 = (1 << 11) | (1 << 6);
+
+/// A fault that reasserted immediately after being cleared
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StickyFault {
+    /// The page on which the fault was seen, if the device is paged
+    pub page: Option<u8>,
+    /// The STATUS_WORD after faults were cleared
+    pub status: STATUS_WORD::CommandData,
+}
+
+///
+/// Verifies that faults were cleared on the specified page, given the
+/// STATUS_WORD read after CLEAR_FAULTS, returning a [`StickyFault`] if any
+/// fault remains asserted.  (The POWER_GOOD# and OFF bits indicate status
+/// rather than faults, and are ignored.)
+///
+pub fn verify_cleared(
+    page: Option<u8>,
+    status: STATUS_WORD::CommandData,
+) -> Result<(), StickyFault> {
+    if status.0 & !STATUS_WORD_NONFAULT != 0 {
+        Err(StickyFault { page, status })
+    } else {
+        Ok(())
+    }
+}
+
+/// A rail in a power tree:  the address of its device, its page (if the
+/// device is paged), and the time to wait after it has been transitioned
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rail {
+    pub address: u8,
+    pub page: Option<u8>,
+    pub delay: Duration,
+}
+
+/// The direction in which a power tree is to be sequenced
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// Returns true if the specified STATUS_WORD (as read after a rail has
+    /// been transitioned) indicates that the transition completed:  for
+    /// [`Direction::Up`], that the rail is on with POWER_GOOD asserted; for
+    /// [`Direction::Down`], that the rail is off.
+    pub fn verify(&self, status: STATUS_WORD::CommandData) -> bool {
+        let off = status.get_off() == Some(STATUS_WORD::Off::PowerOff);
+
+        match self {
+            Direction::Up => {
+                let good = status.get_power_good_status()
+                    == Some(STATUS_WORD::PowerGoodStatus::PowerGood);
+                !off && good
+            }
+            Direction::Down => off,
+        }
+    }
+}
+
+///
+/// An iterator over the steps required to sequence a power tree up or down,
+/// yielding each step along with the address of the device to which it
+/// applies.  Rails are brought up in the order specified and brought down in
+/// the reverse order.  For each rail, the page is selected (if the device is
+/// paged); when sequencing up, ON_OFF_CONFIG is written to have the rail
+/// abide by OPERATION (and ignore the CONTROL pin); OPERATION is written to
+/// turn the rail on or off; STATUS_WORD is read (and should be checked with
+/// [`Direction::verify`]); and the rail's delay is waited.
+///
+#[derive(Clone, Debug)]
+pub struct PowerSequence<'a> {
+    rails: &'a [Rail],
+    direction: Direction,
+    index: usize,
+    step: u8,
+}
+
+impl<'a> PowerSequence<'a> {
+    /// Returns the steps to sequence the specified rails in the specified
+    /// direction
+    pub fn new(rails: &'a [Rail], direction: Direction) -> Self {
+        Self {
+            rails,
+            direction,
+            index: 0,
+            step: 0,
+        }
+    }
+
+    fn on_off_config() -> u8 {
+        let mut config = ON_OFF_CONFIG::CommandData(0);
+        config.set_power_control(ON_OFF_CONFIG::PowerControl::Controlled);
+        config.set_command_control(ON_OFF_CONFIG::CommandControl::Abides);
+        config.set_control_control(ON_OFF_CONFIG::ControlControl::Ignore);
+        config.0
+    }
+
+    fn operation(&self) -> u8 {
+        let mut op = OPERATION::CommandData(0);
+
+        op.set_on_off_state(match self.direction {
+            Direction::Up => OPERATION::OnOffState::On,
+            Direction::Down => OPERATION::OnOffState::Off,
+        });
+
+        op.0
+    }
+}
+
+impl<'a> Iterator for PowerSequence<'a> {
+    type Item = (u8, Step);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rail = match self.direction {
+                Direction::Up => self.rails.get(self.index)?,
+                Direction::Down => {
+                    let index = self.rails.len().checked_sub(self.index + 1)?;
+                    &self.rails[index]
+                }
+            };
+
+            let step = self.step;
+            self.step += 1;
+
+            let step = match step {
+                0 => rail.page.map(Step::Page),
+                1 => match self.direction {
+                    Direction::Up => Some(Step::WriteByte(
+                        CommandCode::ON_OFF_CONFIG,
+                        Self::on_off_config(),
+                    )),
+                    Direction::Down => None,
+                },
+                2 => Some(Step::WriteByte(
+                    CommandCode::OPERATION,
+                    self.operation(),
+                )),
+                3 => Some(Step::Read(CommandCode::STATUS_WORD)),
+                4 => Some(Step::Delay(rail.delay)),
+                _ => {
+                    self.step = 0;
+                    self.index += 1;
+                    None
+                }
+            };
+
+            if let Some(step) = step {
+                return Some((rail.address, step));
+            }
+        }
+    }
+}
+
+///
+/// An iterator over the steps required to ramp the output voltage from one
+/// voltage to another in steps no larger than a specified maximum:  each
+/// step writes VOUT_COMMAND and then waits for the output to transition at
+/// the specified rate (which should be no faster than that dictated by
+/// VOUT_TRANSITION_RATE).  Intermediate voltages are encoded according to
+/// VOUT_MODE (and are therefore only bounded to within its resolution);
+/// steps that are below its resolution are elided, and the delays reflect
+/// the voltages as encoded.
+///
+#[derive(Clone, Debug)]
+pub struct VoutRamp {
+    mode: VOutModeCommandData,
+    from: f32,
+    to: f32,
+    rate: f32,
+    steps: u32,
+    step: u32,
+    last: u16,
+    delay: Option<Duration>,
+}
+
+impl VoutRamp {
+    ///
+    /// Returns the steps to ramp from `from` to `to` in steps no larger than
+    /// `max_step` at `rate`.  Returns [`Error::ValueOutOfRange`] if either
+    /// voltage cannot be encoded in the specified mode, or if the maximum
+    /// step or rate is not positive.
+    ///
+    pub fn new(
+        mode: VOutModeCommandData,
+        from: Volts,
+        to: Volts,
+        max_step: Volts,
+        rate: VoltsPerMillisecond,
+    ) -> Result<Self, Error> {
+        if max_step.0.is_nan() || max_step.0 <= 0.0 {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        if rate.0.is_nan() || rate.0 <= 0.0 {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        let mut first = VOUT_COMMAND::CommandData(0);
+        let mut last = VOUT_COMMAND::CommandData(0);
+
+        first.set(mode, from)?;
+        last.set(mode, to)?;
+
+        //
+        // We determine the number of steps based on the voltages as encoded
+        // to prevent an extra step due to rounding.
+        //
+        let (from, to) = (first.get(mode)?.0, last.get(mode)?.0);
+        let steps = libm::ceilf(libm::fabsf(to - from) / max_step.0);
+
+        Ok(Self {
+            mode,
+            from,
+            to,
+            rate: rate.0,
+            steps: steps as u32,
+            step: 0,
+            last: first.0,
+            delay: None,
+        })
+    }
+}
+
+impl Iterator for VoutRamp {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        if let Some(delay) = self.delay.take() {
+            return Some(Step::Delay(delay));
+        }
+
+        while self.step < self.steps {
+            self.step += 1;
+
+            let v = if self.step == self.steps {
+                self.to
+            } else {
+                let frac = self.step as f32 / self.steps as f32;
+                self.from + (self.to - self.from) * frac
+            };
+
+            let mut data = VOUT_COMMAND::CommandData(0);
+            data.set(self.mode, Volts(v)).ok()?;
+
+            if data.0 == self.last {
+                continue;
+            }
+
+            let last = VOUT_COMMAND::CommandData(self.last).get(self.mode);
+            let delta = data.get(self.mode).ok()?.0 - last.ok()?.0;
+            let nanos = libm::fabsf(delta) / self.rate * 1_000_000.0;
+
+            self.last = data.0;
+            self.delay = Some(Duration::from_nanos(nanos as u64));
+
+            return Some(Step::WriteWord(CommandCode::VOUT_COMMAND, data.0));
+        }
+
+        None
+    }
+}
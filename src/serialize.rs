@@ -0,0 +1,54 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Serialization support, enabled via the `serde` feature.  The core types
+//! (e.g., [`Coefficients`](crate::Coefficients), [`CommandCode`](
+//! crate::CommandCode), [`Device`](crate::Device) and the [`units`](
+//! crate::units)) implement `Serialize` and `Deserialize` directly; the
+//! fields and values yielded by interpretation are trait objects, so
+//! [`FieldValue`] captures each pair in a form that can be serialized (and
+//! later deserialized without the definitions at hand).
+//!
+
+use crate::{Field, Value};
+use serde::{Deserialize, Serialize};
+
+/// A field and its value, as yielded by interpretation
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldValue<'a> {
+    /// The field's identifier; see [`Field::id`]
+    pub id: u32,
+    pub name: &'a str,
+    pub desc: &'a str,
+    pub bitpos: u8,
+    pub width: u8,
+    /// The name of the value
+    pub value: &'a str,
+    /// The description of the value
+    pub value_desc: &'a str,
+    pub raw: u32,
+    /// The value as a real number in its units, if it is numeric
+    pub real: Option<f32>,
+}
+
+impl FieldValue<'static> {
+    pub fn new(field: &dyn Field, value: &dyn Value) -> Self {
+        let (pos, width) = field.bits();
+
+        Self {
+            id: field.id(),
+            name: field.name(),
+            desc: field.desc(),
+            bitpos: pos.0,
+            width: width.0,
+            value: value.name(),
+            value_desc: value.desc(),
+            raw: value.raw(),
+            real: value.as_float(),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Configuration signatures.  To detect configuration drift across a fleet,
+//! it is convenient to reduce the configuration of each device to a single
+//! [`Signature`] that can be compared against that of its peers.  The
+//! configuration of a device consists of those commands for which
+//! [`crate::Command::configuration`] is true; volatile fields within them (as
+//! indicated by [`crate::Command::volatile`]) are masked off, so that (e.g.) a
+//! device whose output is on has the same signature as one whose output is
+//! off.
+//!
+//! A signature is computed over a single page; for paged devices, the
+//! caller should compute a signature for each page.
+//!
+
+use crate::encode::BLOCK_MAX;
+use crate::{Device, Error, Operation};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// The signature of a device's configuration
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Signature(pub u64);
+
+///
+/// A signature under construction.  The signature depends only on the
+/// device and on the set of configuration commands and their payloads that
+/// have been added, and not on the order in which they were added.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct Signer {
+    device: Device,
+    sum: u64,
+}
+
+impl Signer {
+    pub fn new(device: Device) -> Self {
+        Self {
+            device,
+            sum: fnv(FNV_OFFSET, device.name().as_bytes()),
+        }
+    }
+
+    ///
+    /// Adds the payload of the specified command code, as read from the
+    /// device.  Fails with [`Error::InvalidCode`] if the command is not
+    /// part of the device's configuration.
+    ///
+    pub fn add(&mut self, code: u8, payload: &[u8]) -> Result<(), Error> {
+        let mut volatile = None;
+
+        self.device.command(code, |cmd| {
+            if cmd.configuration() {
+                volatile = Some(cmd.volatile());
+            }
+        });
+
+        let volatile = volatile.ok_or(Error::InvalidCode)?;
+        let mut hash = fnv(FNV_OFFSET, &[code]);
+
+        for (i, b) in payload.iter().enumerate() {
+            let mask = match i {
+                0..=3 => !(volatile >> (i * 8)) as u8,
+                _ => 0xff,
+            };
+
+            hash = fnv(hash, &[b & mask]);
+        }
+
+        self.sum = self.sum.wrapping_add(hash);
+        Ok(())
+    }
+
+    pub fn signature(&self) -> Signature {
+        Signature(self.sum)
+    }
+}
+
+/// Calls the specified function with the code and read operation of each
+/// command that is part of the configuration of the specified device
+pub fn configuration(device: Device, mut f: impl FnMut(u8, Operation)) {
+    for code in 0..=u8::MAX {
+        device.command(code, |cmd| {
+            if cmd.configuration() {
+                f(code, cmd.read_op());
+            }
+        });
+    }
+}
+
+///
+/// Computes the signature of the configuration of the specified device.
+/// Each configuration command is read via `read`, which is passed the
+/// command code, its read operation, and a buffer sized for that operation,
+/// and returns the length of the payload that it read.  (For a block read,
+/// this is the payload without its count.)
+///
+pub fn signature<E>(
+    device: Device,
+    mut read: impl FnMut(u8, Operation, &mut [u8]) -> Result<usize, E>,
+) -> Result<Signature, E> {
+    let mut signer = Signer::new(device);
+    let mut buf = [0u8; BLOCK_MAX];
+    let mut rval = Ok(());
+
+    configuration(device, |code, op| {
+        if rval.is_err() {
+            return;
+        }
+
+        let len = match op {
+            Operation::ReadByte => 1,
+            Operation::ReadWord => 2,
+            Operation::ReadWord32 => 4,
+            _ => BLOCK_MAX,
+        };
+
+        match read(code, op, &mut buf[..len]) {
+            Ok(n) => {
+                //
+                // We know that this is a configuration command, so this
+                // cannot fail.
+                //
+                let _ = signer.add(code, &buf[..n.min(len)]);
+            }
+            Err(err) => rval = Err(err),
+        }
+    });
+
+    rval.map(|_| signer.signature())
+}
@@ -0,0 +1,85 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Storing to (and restoring from) nonvolatile memory.  The `STORE_*` and
+//! `RESTORE_*` commands can take an appreciable amount of time to complete,
+//! during which the device may indicate that it is busy; and their failure
+//! is only indicated by the communications/memory/logic (CML) status.  The
+//! [`store`] helper encapsulates issuing such a command, waiting for it to
+//! complete (as dictated by [`Command::delay_after`]) and then polling the
+//! device status for completion.
+//!
+
+use crate::commands::STATUS_BYTE;
+use crate::commands::STATUS_CML;
+use crate::Command;
+use core::time::Duration;
+
+/// An error in storing to or restoring from nonvolatile memory
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StoreError<E> {
+    /// The command is not a `STORE_*` or `RESTORE_*` command
+    InvalidCommand,
+    /// An error from the caller's transport
+    Transport(E),
+    /// The device remained busy for all attempts
+    Timeout,
+    /// The device indicated a CML fault; the contents of STATUS_CML are
+    /// provided.
+    Fault(STATUS_CML::CommandData),
+}
+
+///
+/// Issues the specified `STORE_*` or `RESTORE_*` command via `send`, waits
+/// for the command's delay (if any) via `delay`, and then reads STATUS_BYTE
+/// via `status` until the device is no longer busy, waiting `interval`
+/// between each of (at most) `attempts` reads.  If the device then indicates
+/// a CML fault, STATUS_CML is read via `cml` and returned as part of
+/// [`StoreError::Fault`].
+///
+pub fn store<C: Command + Copy, E>(
+    cmd: C,
+    attempts: u32,
+    interval: Duration,
+    mut send: impl FnMut(C) -> Result<(), E>,
+    mut status: impl FnMut() -> Result<STATUS_BYTE::CommandData, E>,
+    mut cml: impl FnMut() -> Result<STATUS_CML::CommandData, E>,
+    mut delay: impl FnMut(Duration),
+) -> Result<(), StoreError<E>> {
+    let name = cmd.name();
+
+    if !name.starts_with("STORE_") && !name.starts_with("RESTORE_") {
+        return Err(StoreError::InvalidCommand);
+    }
+
+    send(cmd).map_err(StoreError::Transport)?;
+
+    if let Some(after) = cmd.delay_after() {
+        delay(after);
+    }
+
+    for attempt in 0..attempts {
+        if attempt != 0 {
+            delay(interval);
+        }
+
+        let status = status().map_err(StoreError::Transport)?;
+
+        if status.get_busy() == Some(STATUS_BYTE::Busy::Fault) {
+            continue;
+        }
+
+        if status.get_cml_fault() == Some(STATUS_BYTE::CMLFault::Fault) {
+            let cml = cml().map_err(StoreError::Transport)?;
+            return Err(StoreError::Fault(cml));
+        }
+
+        return Ok(());
+    }
+
+    Err(StoreError::Timeout)
+}
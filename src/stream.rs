@@ -0,0 +1,120 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Streaming interpretation of large blocks.  Some devices have blocks of
+//! data (e.g., fault logs) that are many dozens of bytes in size, and are
+//! read from the device in several transactions.  Rather than requiring
+//! that the entire block be materialized, a [`BlockStream`] can be fed the
+//! block in chunks as they arrive, calling the visitor for each field as
+//! soon as its last byte has been seen.  The stream only retains the last
+//! [`WINDOW`] bytes, so no field may be wider than that.
+//!
+
+use crate::Error;
+use crate::Field;
+use crate::Interpreter;
+use crate::VOutMode;
+use crate::Value;
+
+/// The number of trailing bytes that a [`BlockStream`] retains
+pub const WINDOW: usize = 16;
+
+///
+/// A field within a larger block:  its name, its byte offset into the block,
+/// its length, and the function that interprets it.
+///
+#[derive(Copy, Clone)]
+pub struct BlockField {
+    pub name: &'static str,
+    pub offset: usize,
+    pub len: usize,
+    pub interpret: Interpreter,
+}
+
+impl BlockField {
+    fn end(&self) -> usize {
+        self.offset + self.len
+    }
+}
+
+///
+/// A block that is being interpreted as it arrives, as dictated by a layout
+/// of [`BlockField`]s.  Fields may appear in the layout in any order and
+/// may overlap.
+///
+pub struct BlockStream<'a> {
+    layout: &'a [BlockField],
+    pos: usize,
+    window: [u8; WINDOW],
+}
+
+impl<'a> BlockStream<'a> {
+    /// Returns a new stream for the specified layout, or `None` if any
+    /// field in the layout is wider than [`WINDOW`].
+    pub fn new(layout: &'a [BlockField]) -> Option<Self> {
+        if layout.iter().any(|f| f.len == 0 || f.len > WINDOW) {
+            return None;
+        }
+
+        Some(Self {
+            layout,
+            pos: 0,
+            window: [0; WINDOW],
+        })
+    }
+
+    /// Returns the number of bytes that have been fed into the stream
+    pub fn len(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns true if no bytes have been fed into the stream
+    pub fn is_empty(&self) -> bool {
+        self.pos == 0
+    }
+
+    ///
+    /// Feeds the next chunk of the block into the stream, calling the
+    /// specified closure for each field and value for every field that is
+    /// completed by the chunk.  An error interpreting any field is returned
+    /// immediately; the stream should not be fed after an error.
+    ///
+    pub fn feed(
+        &mut self,
+        chunk: &[u8],
+        mode: impl VOutMode,
+        mut iter: impl FnMut(&BlockField, &dyn Field, &dyn Value),
+    ) -> Result<(), Error> {
+        let mode = || mode.vout_mode();
+
+        for &byte in chunk {
+            self.window.copy_within(1.., 0);
+            self.window[WINDOW - 1] = byte;
+            self.pos += 1;
+
+            for field in self.layout.iter().filter(|f| f.end() == self.pos) {
+                let payload = &self.window[WINDOW - field.len..];
+
+                (field.interpret)(payload, &mode, &mut |f, v| {
+                    iter(field, f, v)
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Completes the stream, returning [`Error::ShortData`] if any field in
+    /// the layout was not seen in its entirety.
+    pub fn finish(self) -> Result<(), Error> {
+        if self.layout.iter().any(|f| f.end() > self.pos) {
+            Err(Error::ShortData)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+///
+/// Texas Instruments-specific functionality.
+///
+use crate::commands::VOUT_MODE::Mode;
+use crate::units::{Amperes, Milliohms, Volts};
+use crate::{Coefficients, Error, VOutModeCommandData, Vid, VidCode};
+use num_traits::float::FloatCore;
+
+///
+/// Returns the VID protocol dictated by VOUT_MODE on the TPS536xx family
+/// of controllers, failing with [`Error::InvalidMode`] if VOUT_MODE does
+/// not indicate VID or indicates a protocol that we don't know.
+///
+pub fn vid_code(mode: &VOutModeCommandData) -> Result<VidCode, Error> {
+    if mode.get_mode() != Some(Mode::VID) {
+        return Err(Error::InvalidMode);
+    }
+
+    //
+    // The 5 mV protocols (VR12, VR13 and IMVP8) share a code mapping, as
+    // do the 10 mV protocols (VR12.5 and VR13).
+    //
+    match mode.0 & 0x1f {
+        0x01 | 0x05 | 0x07 => Ok(VidCode::Vr12),
+        0x02 | 0x04 => Ok(VidCode::Vr13),
+        _ => Err(Error::InvalidMode),
+    }
+}
+
+///
+/// Interprets a raw VOUT-related value (e.g., that of READ_VOUT) on the
+/// TPS536xx family of controllers when VOUT_MODE indicates VID.
+///
+pub fn vid_vout(raw: u16, mode: &VOutModeCommandData) -> Result<Volts, Error> {
+    Ok(Volts(Vid(raw, vid_code(mode)?).to_real()))
+}
+
+/// The calibration of an INA233:  the value of MFR_CALIBRATION, along with
+/// the resulting coefficients for current and power.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ina233Calibration {
+    pub calibration: u16,
+    pub current: Coefficients,
+    pub power: Coefficients,
+}
+
+//
+// Returns DIRECT coefficients (with an offset of zero) for a quantity whose
+// raw value is the real value divided by the specified LSB, choosing the
+// exponent to give the slope as much resolution as a 16-bit signed
+// coefficient allows.
+//
+fn lsb_coefficients(lsb: f32) -> Coefficients {
+    let mut m = 1.0 / lsb;
+    let mut r: i8 = 0;
+
+    while m > i16::MAX as f32 {
+        m /= 10.0;
+        r += 1;
+    }
+
+    while m * 10.0 <= i16::MAX as f32 {
+        m *= 10.0;
+        r -= 1;
+    }
+
+    Coefficients {
+        m: FloatCore::round(m) as i32,
+        b: 0,
+        R: r,
+    }
+}
+
+///
+/// Determines the calibration of an INA233 for the specified maximum
+/// expected current through the specified shunt resistance, per the
+/// datasheet:  the current LSB is the maximum current divided by 2^15, and
+/// MFR_CALIBRATION is 0.00512 divided by the product of the current LSB and
+/// the shunt resistance (in ohms).  Fails with [`Error::ValueOutOfRange`]
+/// if either argument isn't positive or if the calibration value can't be
+/// represented.
+///
+pub fn ina233_calibrate(
+    max: Amperes,
+    shunt: Milliohms,
+) -> Result<Ina233Calibration, Error> {
+    if !(max.0 > 0.0 && shunt.0 > 0.0) {
+        return Err(Error::ValueOutOfRange);
+    }
+
+    let lsb = max.0 / 32768.0;
+    let cal = FloatCore::round(5.12 / (lsb * shunt.0));
+
+    if !(1.0..=32767.0).contains(&cal) {
+        return Err(Error::ValueOutOfRange);
+    }
+
+    Ok(Ina233Calibration {
+        calibration: cal as u16,
+        current: lsb_coefficients(lsb),
+        power: lsb_coefficients(lsb * 25.0),
+    })
+}
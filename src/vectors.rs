@@ -0,0 +1,135 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Generation of representative payloads ("test vectors") for the commands
+//! of a device, for use as stimulus for hardware-in-the-loop testing or as
+//! input to regression tests of downstream decoders.  For each command,
+//! [`vectors`] first generates a baseline payload in which every field with
+//! sentinels takes its lowest sentinel value (and every other field is
+//! zero); it then varies each field in turn from the baseline, through the
+//! extremes of its width and (for fields with sentinels) through each of
+//! its sentinel values.
+//!
+
+use crate::{Bitpos, Device, Error, Operation};
+
+/// A kind of test vector
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Kind {
+    /// The baseline payload
+    Baseline,
+    /// The named field is all zeroes
+    Zero(&'static str),
+    /// The named field is all ones
+    Ones(&'static str),
+    /// The named field is the largest value if interpreted as signed
+    SignedMax(&'static str),
+    /// The named field is the smallest value if interpreted as signed
+    SignedMin(&'static str),
+    /// The named field takes the named sentinel value
+    Sentinel(&'static str, &'static str),
+}
+
+fn size(op: Operation) -> Option<usize> {
+    match op {
+        Operation::SendByte => Some(0),
+        Operation::ReadByte | Operation::WriteByte => Some(1),
+        Operation::ReadWord | Operation::WriteWord => Some(2),
+        Operation::ReadWord32 | Operation::WriteWord32 => Some(4),
+        _ => None,
+    }
+}
+
+fn mask(pos: Bitpos, width: u8) -> u32 {
+    (((1u64 << width) - 1) << pos.0) as u32
+}
+
+///
+/// Calls `vector` with each test vector for the specified command code on
+/// the specified device.  The size of the payload is determined by the
+/// command's read operation (or, for a command that can only be written,
+/// its write operation); commands with payloads of variable length (e.g.,
+/// block commands) have no vectors.  Fails with
+/// [`Error::InvalidCode`] if the device does not define the command.
+///
+pub fn vectors(
+    device: Device,
+    code: u8,
+    mut vector: impl FnMut(Kind, &[u8]),
+) -> Result<(), Error> {
+    let mut ops = None;
+
+    device.command(code, |cmd| ops = Some((cmd.read_op(), cmd.write_op())));
+
+    let (read, write) = ops.ok_or(Error::InvalidCode)?;
+
+    let len = match size(read).or_else(|| size(write)) {
+        Some(len) => len,
+        None => return Ok(()),
+    };
+
+    let emit = |vector: &mut dyn FnMut(Kind, &[u8]), kind, raw: u32| {
+        vector(kind, &raw.to_le_bytes()[..len]);
+    };
+
+    //
+    // Construct our baseline by setting each field with sentinels to its
+    // lowest sentinel value.  Fields that fail with InvalidField have no
+    // sentinels, and are left as zero.
+    //
+    let mut baseline = 0u32;
+
+    device.fields(code, |f| {
+        let (pos, _) = f.bits();
+        let mut lowest = None;
+
+        let _ = device.sentinels(code, pos, |v| {
+            lowest = Some(lowest.map_or(v.raw(), |l: u32| l.min(v.raw())));
+        });
+
+        if let Some(lowest) = lowest {
+            baseline |= lowest << pos.0;
+        }
+    })?;
+
+    emit(&mut vector, Kind::Baseline, baseline);
+
+    device.fields(code, |f| {
+        let (pos, width) = f.bits();
+        let name = f.name();
+
+        let width = match width.0 {
+            0 => (len * 8) as u8,
+            w => w,
+        };
+
+        let m = mask(pos, width);
+        let base = baseline & !m;
+        let mut sentinels = false;
+
+        let _ = device.sentinels(code, pos, |v| {
+            let raw = base | ((v.raw() << pos.0) & m);
+            emit(&mut vector, Kind::Sentinel(name, v.name()), raw);
+            sentinels = true;
+        });
+
+        if sentinels {
+            return;
+        }
+
+        emit(&mut vector, Kind::Zero(name), base);
+        emit(&mut vector, Kind::Ones(name), base | m);
+
+        if width > 1 {
+            let high = mask(Bitpos(pos.0 + width - 1), 1);
+            emit(&mut vector, Kind::SignedMax(name), base | (m & !high));
+            emit(&mut vector, Kind::SignedMin(name), base | high);
+        }
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,194 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! A JavaScript interface to the reflection interface, enabled via the
+//! `wasm-bindgen` feature, allowing for (e.g.) a browser-based decoder to
+//! be built on the same definitions.  Devices are named as by
+//! [`Device::name`]; results are returned as JSON strings.  Unlike the rest
+//! of the crate, this interface allocates.
+//!
+
+use crate::{commands, Device, Error, Field, Value};
+use alloc::format;
+use alloc::string::String;
+use core::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+fn error(err: Error) -> JsValue {
+    JsValue::from_str(&format!("{:?}", err))
+}
+
+fn device(name: &str) -> Result<Device, JsValue> {
+    Device::from_str(name).ok_or_else(|| error(Error::InvalidCode))
+}
+
+//
+// We emit JSON by hand rather than pull in a serialization framework; the
+// only strings we emit are our own names, descriptions and values, but we
+// escape them properly regardless.
+//
+fn string(out: &mut String, s: &str) {
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+fn field(out: &mut String, field: &dyn Field) {
+    let (pos, width) = field.bits();
+
+    out.push_str("{\"name\":");
+    string(out, field.name());
+    out.push_str(",\"desc\":");
+    string(out, field.desc());
+    let _ = write!(out, ",\"bitpos\":{},\"width\":{}", pos.0, width.0);
+}
+
+fn value(out: &mut String, value: &dyn Value) {
+    out.push_str("{\"name\":");
+    string(out, value.name());
+    out.push_str(",\"desc\":");
+    string(out, value.desc());
+    let _ = write!(out, ",\"raw\":{},\"value\":", value.raw());
+    string(out, &format!("{}", value));
+    out.push('}');
+}
+
+///
+/// Interprets the payload for the specified command code on the specified
+/// device, given the contents of VOUT_MODE.  Returns a JSON array of
+/// fields, each with its value.
+///
+#[wasm_bindgen]
+pub fn interpret(
+    device: &str,
+    code: u8,
+    payload: &[u8],
+    vout_mode: u8,
+) -> Result<String, JsValue> {
+    let device = self::device(device)?;
+    let mode = || commands::VOUT_MODE::CommandData(vout_mode);
+    let mut out = String::from("[");
+
+    device
+        .interpret(code, payload, mode, |f, v| {
+            if out.len() > 1 {
+                out.push(',');
+            }
+
+            field(&mut out, f);
+            out.push_str(",\"value\":");
+            value(&mut out, v);
+            out.push('}');
+        })
+        .map_err(error)?;
+
+    out.push(']');
+    Ok(out)
+}
+
+///
+/// Returns the schema for the specified command code on the specified
+/// device:  a JSON object with the command's name and read and write
+/// operations, and an array of its fields, each with its sentinel values
+/// (if any).
+///
+#[wasm_bindgen]
+pub fn schema(device: &str, code: u8) -> Result<String, JsValue> {
+    let device = self::device(device)?;
+    let mut out = String::new();
+
+    device.command(code, |cmd| {
+        out.push_str("{\"name\":");
+        string(&mut out, cmd.name());
+        let _ = write!(
+            out,
+            ",\"read\":\"{:?}\",\"write\":\"{:?}\",\"fields\":[",
+            cmd.read_op(),
+            cmd.write_op()
+        );
+    });
+
+    if out.is_empty() {
+        return Err(error(Error::InvalidCode));
+    }
+
+    let mut rval = Ok(());
+    let mut first = true;
+
+    device
+        .fields(code, |f| {
+            if !first {
+                out.push(',');
+            }
+
+            first = false;
+            field(&mut out, f);
+            out.push_str(",\"sentinels\":[");
+
+            let mut n = 0;
+
+            //
+            // Fields that have no sentinels (e.g., scalar fields) fail with
+            // InvalidField; we treat these as having none.
+            //
+            let r = device.sentinels(code, f.bits().0, |v| {
+                if n > 0 {
+                    out.push(',');
+                }
+
+                n += 1;
+                value(&mut out, v);
+            });
+
+            match r {
+                Ok(_) | Err(Error::InvalidField) => {}
+                Err(err) => rval = Err(err),
+            }
+
+            out.push_str("]}");
+        })
+        .map_err(error)?;
+
+    rval.map_err(error)?;
+
+    out.push_str("]}");
+    Ok(out)
+}
+
+/// Returns a JSON array of the commands defined for the specified device,
+/// each with its code and name
+#[wasm_bindgen]
+pub fn commands(device: &str) -> Result<String, JsValue> {
+    let device = self::device(device)?;
+    let mut out = String::from("[");
+
+    for code in 0..=u8::MAX {
+        device.command(code, |cmd| {
+            if out.len() > 1 {
+                out.push(',');
+            }
+
+            let _ = write!(out, "{{\"code\":{},\"name\":", code);
+            string(&mut out, cmd.name());
+            out.push('}');
+        });
+    }
+
+    out.push(']');
+    Ok(out)
+}
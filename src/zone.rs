@@ -0,0 +1,121 @@
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+//
+
+//!
+//! Zones.  PMBus 1.3 allows pages of devices to be assigned (via
+//! ZONE_CONFIG) to a read zone and a write zone; a host can then select a
+//! zone (via ZONE_ACTIVE) and address all of its members at once by writing
+//! to the zone write address, e.g. to turn a set of rails on or off
+//! simultaneously.  The helpers here express these operations as the
+//! addressed [`Step`]s of the [`sequence`](crate::sequence) module.
+//!
+
+use crate::commands::{OPERATION, ZONE_ACTIVE, ZONE_CONFIG};
+use crate::sequence::Step;
+use crate::CommandCode;
+
+/// The (7-bit) address to which zone reads are sent
+pub const ZONE_READ_ADDRESS: u8 = 0x28;
+
+/// The (7-bit) address to which zone writes are sent
+pub const ZONE_WRITE_ADDRESS: u8 = 0x37;
+
+/// The largest zone number
+pub const ZONE_MAX: u8 = 0x7f;
+
+/// A zone number
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Zone(u8);
+
+impl Zone {
+    /// Returns the specified zone, or `None` if it exceeds [`ZONE_MAX`]
+    pub fn new(zone: u8) -> Option<Self> {
+        if zone <= ZONE_MAX {
+            Some(Self(zone))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the zone number
+    pub fn number(&self) -> u8 {
+        self.0
+    }
+}
+
+fn zones(read: Zone, write: Zone) -> u16 {
+    u16::from(read.0) << 8 | u16::from(write.0)
+}
+
+fn decode(read: u16, write: u16) -> Option<(Zone, Zone)> {
+    let zone = |z: u16| {
+        if z <= u16::from(ZONE_MAX) {
+            Some(Zone(z as u8))
+        } else {
+            None
+        }
+    };
+    Some((zone(read)?, zone(write)?))
+}
+
+/// Decodes the read and write zones of a ZONE_CONFIG read, returning `None`
+/// if either is not a valid zone
+pub fn config(data: ZONE_CONFIG::CommandData) -> Option<(Zone, Zone)> {
+    decode(data.get_read_zone(), data.get_write_zone())
+}
+
+/// Decodes the active read and write zones of a ZONE_ACTIVE read, returning
+/// `None` if either is not a valid zone
+pub fn active(data: ZONE_ACTIVE::CommandData) -> Option<(Zone, Zone)> {
+    decode(data.get_read_zone(), data.get_write_zone())
+}
+
+/// Returns the steps to assign the specified page (if the device is paged)
+/// of the device at the specified address to the specified read and write
+/// zones
+pub fn assign(
+    address: u8,
+    page: Option<u8>,
+    read: Zone,
+    write: Zone,
+) -> impl Iterator<Item = (u8, Step)> {
+    let config = ZONE_CONFIG::CommandData(zones(read, write));
+
+    let steps = [
+        page.map(Step::Page),
+        Some(Step::WriteWord(CommandCode::ZONE_CONFIG, config.0)),
+    ];
+
+    IntoIterator::into_iter(steps)
+        .flatten()
+        .map(move |step| (address, step))
+}
+
+/// Returns the steps to turn the members of the specified zone on (if `on`
+/// is true) or off:  the zone is made active for both reads and writes,
+/// and OPERATION is then written to the zone write address
+pub fn operation(zone: Zone, on: bool) -> [(u8, Step); 2] {
+    let active = ZONE_ACTIVE::CommandData(zones(zone, zone));
+
+    let mut op = OPERATION::CommandData(0);
+
+    op.set_on_off_state(if on {
+        OPERATION::OnOffState::On
+    } else {
+        OPERATION::OnOffState::Off
+    });
+
+    [
+        (
+            ZONE_WRITE_ADDRESS,
+            Step::WriteWord(CommandCode::ZONE_ACTIVE, active.0),
+        ),
+        (
+            ZONE_WRITE_ADDRESS,
+            Step::WriteByte(CommandCode::OPERATION, op.0),
+        ),
+    ]
+}
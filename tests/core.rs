@@ -640,6 +640,27 @@ fn tps_read_all_data() {
     }
 }
 
+#[test]
+fn tps546d24a() {
+    use commands::tps546d24a::*;
+
+    let data = READ_ALL::CommandData::from_slice(&[
+        0x02, 0x00, 0x63, 0x02, 0xee, 0xad, 0xd8, 0xdb, 0xfe, 0xd2, 0x00, 0x00,
+        0x00, 0x00,
+    ])
+    .unwrap();
+
+    assert_eq!(data.get_read_vout(), 0x0263);
+    assert_eq!(Tps546D24A::DEVICE, Device::Tps546D24A);
+
+    for code in 0..=0xffu8 {
+        assert_eq!(
+            Device::Tps546D24A.format(code),
+            Device::Tps546B24A.format(code)
+        );
+    }
+}
+
 #[test]
 fn tps_passthrough() {
     //
@@ -669,6 +690,112 @@ fn tps_passthrough() {
     assert_eq!(result, Some(target));
 }
 
+#[test]
+fn bmr350() {
+    use commands::bmr350::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    validate!(READ_VOUT, mode, [0x00, 0x02], 1.0, Volts);
+    validate!(MFR_TRACK_OFFSET, mode, [0xc0, 0xff], -0.125, Volts);
+
+    let ramp = MFR_RAMP_CONFIG::CommandData::from_slice(&[0x03]).unwrap();
+    assert_eq!(
+        ramp.get_prebias_start(),
+        Some(MFR_RAMP_CONFIG::PrebiasStart::Enabled)
+    );
+    assert_eq!(ramp.get_soft_off(), Some(MFR_RAMP_CONFIG::SoftOff::Ramped));
+
+    let track = MFR_TRACK_CONFIG::CommandData::from_slice(&[0x84]).unwrap();
+    assert_eq!(track.get_enable(), Some(MFR_TRACK_CONFIG::Enable::Enabled));
+    assert_eq!(track.get_ratio(), Some(MFR_TRACK_CONFIG::Ratio::Half));
+    assert_eq!(
+        track.get_upper_limit(),
+        Some(MFR_TRACK_CONFIG::UpperLimit::Target)
+    );
+}
+
+#[test]
+fn bmr453() {
+    use commands::bmr453::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x15]).unwrap();
+    validate!(READ_VOUT, mode, [0x00, 0x60], 12.0, Volts);
+    validate!(READ_VIN, [0x60, 0xf8], 48.0, Volts);
+    validate!(READ_TEMPERATURE_1, [0x2d, 0x00], 45.0, Celsius);
+
+    let data = MFR_PGOOD_POLARITY::CommandData::from_slice(&[0x00]).unwrap();
+    assert_eq!(
+        data.get_polarity(),
+        Some(MFR_PGOOD_POLARITY::Polarity::ActiveLow)
+    );
+
+    let data = MFR_FAST_OCP_CFG::CommandData::from_slice(&[0x32, 0x04]);
+    let data = data.unwrap();
+    assert_eq!(data.get_samples(), 4);
+    assert_eq!(data.get_enable(), Some(MFR_FAST_OCP_CFG::Enable::Disabled));
+    assert_eq!(data.get_level(), 0x32);
+
+    //
+    // 0x51 is 10 ms units for Vout and Vin, 1 ms units for Iout and 10 ms
+    // units for temperature.
+    //
+    let data = MFR_RESPONSE_UNIT_CFG::CommandData::from_slice(&[0x51]);
+    let data = data.unwrap();
+    use MFR_RESPONSE_UNIT_CFG::*;
+    assert_eq!(data.get_v_out_unit(), Some(VOutUnit::Unit10ms));
+    assert_eq!(data.get_v_in_unit(), Some(VInUnit::Unit10ms));
+    assert_eq!(data.get_i_out_unit(), Some(IOutUnit::Unit1ms));
+    assert_eq!(data.get_temp_unit(), Some(TempUnit::Unit10ms));
+
+    let mut names = vec![];
+
+    Device::Bmr453
+        .interpret(0xd2, &[0x51], mode, |f, v| {
+            names.push((f.name(), v.name()));
+        })
+        .unwrap();
+
+    assert_eq!(
+        names,
+        [
+            ("VOutUnit", "Unit10ms"),
+            ("VInUnit", "Unit10ms"),
+            ("IOutUnit", "Unit1ms"),
+            ("TempUnit", "Unit10ms"),
+        ]
+    );
+}
+
+#[test]
+fn bmr456() {
+    use commands::bmr456::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x16]).unwrap();
+    validate!(READ_VOUT, mode, [0x00, 0x30], 12.0, Volts);
+    validate!(READ_IOUT, [0x28, 0xf0], 10.0, Amperes);
+
+    let data = MFR_FAST_OCP_CFG::CommandData::from_slice(&[0xe9, 0x02]);
+    let data = data.unwrap();
+    assert_eq!(data.get_samples(), 2);
+    assert_eq!(data.get_enable(), Some(MFR_FAST_OCP_CFG::Enable::Enabled));
+    assert_eq!(data.get_level(), 0x69);
+
+    let data = MFR_PGOOD_POLARITY::CommandData::from_slice(&[0x01]).unwrap();
+    assert_eq!(
+        data.get_polarity(),
+        Some(MFR_PGOOD_POLARITY::Polarity::ActiveHigh)
+    );
+
+    assert_eq!(Device::Bmr457.format(0xde), Device::Bmr456.format(0xde));
+    assert_eq!(
+        CommandCode::from_name("MFR_RESPONSE_UNIT_CFG"),
+        Some(CommandCode::MFR_RESPONSE_UNIT_CFG)
+    );
+}
+
 #[test]
 fn bmr480_default() {
     use commands::bmr480::*;
@@ -712,6 +839,38 @@ fn bmr491_default() {
     dump(&data);
 }
 
+#[test]
+fn generic_psu() {
+    use commands::generic_psu::*;
+    use units::*;
+
+    validate!(READ_PIN, [0x58, 0x02], 600.0, Watts);
+    validate!(READ_FAN_SPEED_1, [0xe8, 0x1b], 8000.0, RPM);
+
+    let data =
+        MFR_COLD_REDUNDANCY_CONFIG::CommandData::from_slice(&[0x02]).unwrap();
+    assert_eq!(
+        data.get_mode(),
+        Some(MFR_COLD_REDUNDANCY_CONFIG::Mode::Standby1)
+    );
+
+    //
+    // A concrete supply based on the generic profile shares its
+    // MFR-specific commands.
+    //
+    let code = CommandCode::MFR_COLD_REDUNDANCY_CONFIG as u8;
+    let mut names = vec![];
+
+    Device::D1U54P
+        .interpret(code, &[0x01], mode, |f, v| {
+            names.push((f.name(), v.name()));
+        })
+        .unwrap();
+
+    assert_eq!(names, [("Mode", "Active")]);
+    assert_eq!(Device::D1U54P.format(0x97), Some(Format::Linear11));
+}
+
 #[test]
 fn bmr480_iout() {
     use commands::bmr480::*;
@@ -996,6 +1155,150 @@ fn vout_command_mutate() {
     let rval = data.mutate(|| vout, |_, _| Some(Replacement::Float(150.0)));
 
     assert_eq!(rval, Err(Error::ValueOutOfRange));
+
+    let rval =
+        data.mutate(|| vout, |_, _| Some(Replacement::SaturatingFloat(150.0)));
+
+    assert_eq!(rval, Ok(()));
+    assert_eq!(data.0, 0xffff);
+}
+
+#[test]
+fn vout_command_positive_exponent() {
+    let mut vout = VOutModeCommandData::from_slice(&[0x00]).unwrap();
+    use commands::VOUT_COMMAND::*;
+
+    vout.set_parameter(3).unwrap();
+    assert_eq!(vout.get_parameter(), 3);
+
+    let mut data = CommandData::from_slice(&[0x0c, 0x00]).unwrap();
+    assert_eq!(data.get(vout), Ok(units::Volts(96.0)));
+
+    data.set(vout, units::Volts(100.0)).unwrap();
+    assert_eq!(data.0, 13);
+    assert_eq!(data.get(vout), Ok(units::Volts(104.0)));
+
+    vout.set_parameter(15).unwrap();
+    data.set(vout, units::Volts(32768.0 * 65535.0)).unwrap();
+    assert_eq!(data.0, 0xffff);
+
+    assert_eq!(
+        data.set(vout, units::Volts(32768.0 * 65536.0)),
+        Err(Error::ValueOutOfRange)
+    );
+
+    assert_eq!(
+        data.set(vout, units::Volts(-100000.0)),
+        Err(Error::ValueOutOfRange)
+    );
+}
+
+#[test]
+fn ulinear16_exponent() {
+    assert_eq!(ULinear16Exponent::new(-16), Some(ULinear16Exponent(-16)));
+    assert_eq!(ULinear16Exponent::new(15), Some(ULinear16Exponent(15)));
+    assert_eq!(ULinear16Exponent::new(-17), None);
+    assert_eq!(ULinear16Exponent::new(16), None);
+
+    let mut vout = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    assert_eq!(
+        ULinear16Exponent::from_mode(&vout),
+        Ok(ULinear16Exponent(-9))
+    );
+
+    vout.set_mode(commands::VOUT_MODE::Mode::Direct);
+    assert_eq!(ULinear16Exponent::from_mode(&vout), Err(Error::InvalidMode));
+
+    vout.set_mode(commands::VOUT_MODE::Mode::VID);
+    assert_eq!(ULinear16Exponent::from_mode(&vout), Err(Error::InvalidMode));
+
+    assert!(ULinear16::from_real(1.0, ULinear16Exponent(16)).is_none());
+}
+
+#[test]
+fn cached_vout_mode() {
+    use commands::VOUT_COMMAND::*;
+
+    let vout = VOutModeCommandData::from_slice(&[0x97]).unwrap();
+    assert_eq!(vout.exponent(), ULinear16Exponent(-9));
+
+    let cached = CachedVOutMode::from_slice(&[0x97]).unwrap();
+    assert_eq!(cached.exponent(), ULinear16Exponent(-9));
+
+    let mut data = CommandData::from_slice(&[0x63, 0x02]).unwrap();
+    assert_eq!(data.get(cached), Ok(units::Volts(1.1933594)));
+    assert_eq!(data.get(vout), Ok(units::Volts(1.1933594)));
+    assert_eq!(data.get(|| vout), Ok(units::Volts(1.1933594)));
+
+    data.set(cached, units::Volts(1.5)).unwrap();
+    assert_eq!(data.0, 0x0300);
+
+    let mut found = None;
+
+    Device::Common
+        .interpret(0x21, &[0x00, 0x03], cached, |_, v| {
+            found = Some(format!("{}", v));
+        })
+        .unwrap();
+
+    assert_eq!(found.as_deref(), Some("1.50V"));
+}
+
+#[test]
+fn ulinear16_saturation() {
+    let exp = ULinear16Exponent(-12);
+
+    let val = ULinear16::try_from_real(20.0, exp, Saturation::Fail);
+    assert_eq!(val.map(|v| v.0), Err(Error::ValueOutOfRange));
+
+    let val = ULinear16::try_from_real(20.0, exp, Saturation::Clamp);
+    assert_eq!(val.map(|v| v.0), Ok(u16::MAX));
+
+    let val = ULinear16::try_from_real(-0.5, exp, Saturation::Fail);
+    assert_eq!(val.map(|v| v.0), Err(Error::ValueOutOfRange));
+
+    let val = ULinear16::try_from_real(-0.5, exp, Saturation::Clamp);
+    assert_eq!(val.map(|v| v.0), Ok(0));
+
+    let val = ULinear16::try_from_real(f32::NAN, exp, Saturation::Clamp);
+    assert_eq!(val.map(|v| v.0), Err(Error::ValueOutOfRange));
+
+    let val = ULinear16::try_from_real(1.5, exp, Saturation::Clamp);
+    assert_eq!(val.map(|v| v.0), Ok(0x1800));
+}
+
+#[test]
+fn set_saturating() {
+    let val = Linear11::try_from_real(1e9, Saturation::Fail);
+    assert_eq!(val.map(|v| v.0), Err(Error::ValueOutOfRange));
+
+    let val = Linear11::try_from_real(1e9, Saturation::Clamp);
+    assert_eq!(val.map(|v| v.0), Ok(0x7bff));
+
+    let val = Linear11::try_from_real(f32::NEG_INFINITY, Saturation::Clamp);
+    assert_eq!(val.map(|v| v.0), Ok(0x7c00));
+
+    let val = Linear11::try_from_real(f32::NAN, Saturation::Clamp);
+    assert_eq!(val.map(|v| v.0), Err(Error::ValueOutOfRange));
+
+    let val = Half::try_from_real(1e6, Saturation::Clamp);
+    assert_eq!(val.map(|v| v.0), Ok(0x7bff));
+
+    let val = Half::try_from_real(-1e6, Saturation::Clamp);
+    assert_eq!(val.map(|v| v.0), Ok(0xfbff));
+
+    let mut data = commands::MAX_DUTY::CommandData(0);
+    assert_eq!(data.set(units::Percent(1e9)), Err(Error::ValueOutOfRange));
+    assert_eq!(data.set_saturating(units::Percent(1e9)), Ok(()));
+    assert_eq!(data.0, 0x7bff);
+
+    let vout = VOutModeCommandData::from_slice(&[0x97]).unwrap();
+    let mut data = commands::VOUT_COMMAND::CommandData(0);
+
+    assert_eq!(data.set_saturating(vout, units::Volts(150.0)), Ok(()));
+    assert_eq!(data.0, 0xffff);
+    assert_eq!(data.set_saturating(vout, units::Volts(1.5)), Ok(()));
+    assert_eq!(data.get(vout), Ok(units::Volts(1.5)));
 }
 
 #[test]
@@ -1068,6 +1371,41 @@ fn sentinels() {
     );
 }
 
+#[test]
+fn value_accessors() {
+    use commands::OPERATION::*;
+
+    let data = CommandData::from_slice(&[0x88]).unwrap();
+    let mut found = false;
+
+    data.interpret(mode, |field, val| {
+        if field.name() == "OnOffState" {
+            assert_eq!(val.sentinel_name(), Some("On"));
+            assert_eq!(val.raw(), 1);
+            found = true;
+        }
+
+        if let Some(name) = val.sentinel_name() {
+            assert_eq!(name, val.name());
+        }
+
+        assert_eq!(val.as_float(), None);
+    })
+    .unwrap();
+
+    assert!(found);
+
+    let vout = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    let data = commands::VOUT_COMMAND::CommandData(0x0266);
+
+    data.interpret(vout, |_, val| {
+        assert_eq!(val.sentinel_name(), None);
+        assert_eq!(val.raw(), 0x0266);
+        assert_eq!(val.as_float(), Some(1.1992188));
+    })
+    .unwrap();
+}
+
 #[test]
 fn device_sentinels() {
     Device::Common
@@ -1254,87 +1592,608 @@ fn adm1272_direct() {
 }
 
 #[test]
-fn raa228926_defaults() {
-    use commands::raa228926::*;
+fn adm1272_configured() {
+    use commands::adm1272::*;
     use units::*;
 
-    let vout = VOutModeCommandData::from_slice(&[0x40]).unwrap();
+    //
+    // In the 100V range with the 30 mV sense range, the datasheet
+    // coefficients are those used above (for a 1 mΩ sense resistor).
+    //
+    let mut config = PMON_CONFIG::CommandData(0);
+    config.set_v_range(PMON_CONFIG::VRange::Range100V);
+    config.set_i_range(PMON_CONFIG::IRange::Range30mV);
 
-    validate!(VOUT_COMMAND, vout, [0x84, 0x03], 0.9, Volts);
-    validate!(VOUT_MAX, vout, [0xea, 0x0b], 3.05, Volts);
-    validate!(VOUT_MARGIN_HIGH, vout, [0xb1, 0x03], 0.945, Volts);
-    validate!(VOUT_MARGIN_LOW, vout, [0x57, 0x03], 0.855, Volts);
-    validate!(
-        VOUT_TRANSITION_RATE,
-        [0xc4, 0x09],
-        0.025,
-        VoltsPerMicrosecond
-    );
-    validate!(FREQUENCY_SWITCH, [0x58, 0x02], 600, Kilohertz);
-    validate!(VIN_ON, [0xbc, 0x02], 7, Volts);
-    validate!(VIN_OFF, [0xf4, 0x01], 5, Volts);
-    validate!(VOUT_OV_FAULT_LIMIT, vout, [0x1c, 0x0c], 3.1, Volts);
-    validate!(IOUT_OC_FAULT_LIMIT, [0x2c, 0x01], 30, Amperes);
-    validate!(OT_FAULT_LIMIT, [0x7d, 0x00], 125, Celsius);
-    validate!(OT_WARN_LIMIT, [0x6e, 0x00], 110, Celsius);
-    validate!(UT_FAULT_LIMIT, [0xd8, 0xff], -40, Celsius);
-    validate!(VIN_OV_FAULT_LIMIT, [0x40, 0x06], 16, Volts);
-    validate!(VIN_OV_WARN_LIMIT, [0x08, 0x07], 18, Volts);
-    validate!(IIN_OC_FAULT_LIMIT, [0x98, 0x3a], 150, Amperes);
+    let sense = Milliohms(1.0);
 
-    validate!(TON_RISE, [0xf4, 0x01], 0.500, Milliseconds);
-    validate!(TOFF_FALL, [0xf4, 0x01], 0.500, Milliseconds);
+    let vin = READ_VIN::CommandData::from_slice(&[0x6d, 0x07]).unwrap();
+    assert_eq!(vin.get_configured(&config), Ok(Volts(46.799606)));
 
-    validate!(PEAK_OC_LIMIT, [0x58, 0x02], 60, Amperes);
-    validate!(PEAK_UC_LIMIT, [0xa8, 0xfd], -60, Amperes);
-    validate!(HS_BUS_CURRENT_SCALE, [0x00, 0x40], 1.0, Unitless);
-    validate!(IOUT_ALERT_THRESHOLD, [0xc8, 0x00], 20.0, Amperes);
+    let pin = READ_PIN::CommandData::from_slice(&[0x10, 0x01]).unwrap();
+    assert_eq!(pin.get_configured(&config, sense), Ok(Watts(25.818699)));
 
-    let ocuc = PEAK_OCUC_COUNT::CommandData::from_slice(&[0x06, 0x06]).unwrap();
-    assert_eq!(ocuc.get_uc_limit(), 6);
-    assert_eq!(ocuc.get_oc_limit(), 6);
-    dump(&ocuc);
+    let iout = READ_IOUT::CommandData::from_slice(&[0x24, 0x08]).unwrap();
+    assert_eq!(iout.get_configured(&config, sense), Ok(Amperes(0.54298645)));
+
+    //
+    // Halving the sense resistance doubles the current for the same code.
+    //
+    let iout = iout.get_configured(&config, Milliohms(0.5)).unwrap();
+    assert_eq!(iout, Amperes(0.54298645 * 2.0));
+
+    config.set_v_range(PMON_CONFIG::VRange::Range60V);
+    config.set_i_range(PMON_CONFIG::IRange::Range15mV);
+
+    assert_eq!(
+        READ_VOUT::CommandData::coefficients(&config),
+        Some(Coefficients {
+            m: 6770,
+            b: 0,
+            R: -2
+        })
+    );
+
+    assert_eq!(
+        PEAK_IOUT::CommandData::coefficients(&config),
+        Some(Coefficients {
+            m: 1326,
+            b: 20480,
+            R: -1
+        })
+    );
+
+    assert_eq!(
+        PIN_OP_WARN_LIMIT::CommandData::coefficients(&config),
+        Some(Coefficients {
+            m: 3512,
+            b: 0,
+            R: -2
+        })
+    );
 }
 
 #[test]
-fn raa228926_filt() {
-    use commands::raa228926::*;
+fn adm1275_configured() {
+    use commands::adm1275::*;
     use units::*;
 
-    let filt =
-        SUM_OC_FILT_COUNT::CommandData::from_slice(&[0x96, 0x06]).unwrap();
+    let mut config = PMON_CONFIG::CommandData(0);
+    config.set_v_range(PMON_CONFIG::VRange::High);
 
-    dump(&filt);
-    assert_eq!(filt.get_delay(), Microseconds(100.0));
-    assert_eq!(filt.get_filter(), Microseconds(10.666667));
+    let vin = READ_VIN::CommandData::from_slice(&[0x00, 0x08]).unwrap();
+    assert_eq!(vin.get_configured(&config), Ok(Volts(10.667222)));
 
-    let filt =
-        IOUT_ALERT_FILT_COUNT::CommandData::from_slice(&[0x00, 0x06]).unwrap();
+    let iout = READ_IOUT::CommandData::from_slice(&[0x00, 0x09]).unwrap();
+    let sense = Milliohms(2.0);
+    assert_eq!(iout.get_configured(&config, sense), Ok(Amperes(1.5892193)));
 
-    assert_eq!(filt.get_filter(), Microseconds(10.666667));
-    dump(&filt);
+    //
+    // The current coefficients don't depend on the voltage range.
+    //
+    config.set_v_range(PMON_CONFIG::VRange::Low);
+    assert_eq!(iout.get_configured(&config, sense), Ok(Amperes(1.5892193)));
+
+    assert_eq!(
+        READ_VOUT::CommandData::coefficients(&config),
+        Some(Coefficients {
+            m: 6720,
+            b: 0,
+            R: -1
+        })
+    );
+
+    let status = STATUS_MFR_SPECIFIC::CommandData::from_slice(&[0x1]).unwrap();
+    assert_eq!(
+        status.get_i_out_warn_2(),
+        Some(STATUS_MFR_SPECIFIC::IOutWarn2::Warning)
+    );
+
+    assert_eq!(Device::Adm1275.units(0x8b), Some(units::Units::Volts));
 }
 
 #[test]
-fn raa229618_defaults() {
-    use commands::raa229618::*;
+fn ina233() {
+    use commands::ina233::*;
     use units::*;
 
-    let vout = VOutModeCommandData::from_slice(&[0x40]).unwrap();
+    let cal = ti::ina233_calibrate(Amperes(10.0), Milliohms(2.0)).unwrap();
+    assert_eq!(cal.calibration, 8389);
+    assert_eq!(
+        cal.current,
+        Coefficients {
+            m: 3277,
+            b: 0,
+            R: 0
+        }
+    );
+    assert_eq!(
+        cal.power,
+        Coefficients {
+            m: 13107,
+            b: 0,
+            R: -2
+        }
+    );
 
-    validate!(VOUT_COMMAND, vout, [0x84, 0x03], 0.9, Volts);
-    validate!(VOUT_MAX, vout, [0xea, 0x0b], 3.05, Volts);
-    validate!(VOUT_MARGIN_HIGH, vout, [0xb1, 0x03], 0.945, Volts);
-    validate!(VOUT_MARGIN_LOW, vout, [0x57, 0x03], 0.855, Volts);
-    validate!(
-        VOUT_TRANSITION_RATE,
-        [0xc4, 0x09],
-        0.025,
-        VoltsPerMicrosecond
+    let iout = READ_IOUT::CommandData::from_slice(&[0xcd, 0x0c]).unwrap();
+    let Amperes(i) = iout.get(&cal.current).unwrap();
+    assert!((i - 1.0).abs() < 0.001, "{}", i);
+
+    assert_eq!(
+        ti::ina233_calibrate(Amperes(10.0), Milliohms(0.0)),
+        Err(Error::ValueOutOfRange)
     );
-    validate!(FREQUENCY_SWITCH, [0x58, 0x02], 600, Kilohertz);
-    validate!(VIN_ON, [0xbc, 0x02], 7, Volts);
-    validate!(VIN_OFF, [0xf4, 0x01], 5, Volts);
+
+    validate!(READ_VIN, [0x80, 0x25], 12.0, Volts);
+    validate!(MFR_READ_VSHUNT, [0xd0, 0x07], 0.005, Volts);
+
+    let adc = MFR_ADC_CONFIG::CommandData::from_slice(&[0x27, 0x41]).unwrap();
+    assert_eq!(adc.get_mode(), 7);
+    assert_eq!(adc.get_bus_conversion_time(), 4);
+}
+
+#[test]
+fn ir35217() {
+    use commands::ir35217::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x16]).unwrap();
+    validate!(READ_VOUT, mode, [0x00, 0x02], 0.5, Volts);
+    validate!(READ_IOUT, [0xc8, 0xf0], 50.0, Amperes);
+
+    assert_eq!(Ir35217::DEVICE, Device::Ir35217);
+    assert_eq!(Device::Ir35217.format(0xd0), Some(Format::Raw));
+}
+
+#[test]
+fn ir38063() {
+    use commands::ir38063::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    validate!(READ_VOUT, mode, [0x00, 0x03], 1.5, Volts);
+    validate!(READ_TEMPERATURE_1, [0x2d, 0x00], 45.0, Celsius);
+
+    assert_eq!(Device::Ir38063.format(0xd0), Some(Format::Raw));
+}
+
+#[test]
+fn isl68137() {
+    use commands::isl68137::*;
+    use units::*;
+
+    validate!(READ_VIN, [0xb0, 0x04], 12.0, Volts);
+    validate!(READ_IOUT, [0x2c, 0x01], 30.0, Amperes);
+
+    let op = OPERATION::CommandData::from_slice(&[0xb0]).unwrap();
+    assert_eq!(
+        op.get_voltage_command_source(),
+        Some(OPERATION::VoltageCommandSource::AVS_VOUT_COMMAND)
+    );
+}
+
+#[test]
+fn isl69247() {
+    use commands::isl69247::*;
+    use units::*;
+
+    validate!(PHASE_TEMPERATURE, [0x2d, 0x00], 45.0, Celsius);
+
+    let uptime =
+        UptimeCounter::CommandData::from_slice(&[0x64, 0x00, 0x00, 0x00])
+            .unwrap();
+    assert_eq!(uptime.get(), Ok(Seconds(10.0)));
+
+    for code in 0..=0xffu8 {
+        assert_eq!(
+            Device::Isl69247.format(code),
+            Device::Isl68224.format(code)
+        );
+    }
+}
+
+#[test]
+fn lm25066_configured() {
+    use commands::lm25066::*;
+    use units::*;
+
+    let mut setup = DEVICE_SETUP::CommandData(0);
+    setup.set_current_limit(DEVICE_SETUP::CurrentLimit::High);
+
+    let vin = READ_VIN::CommandData::from_slice(&[0x46, 0x0a]).unwrap();
+    let Volts(v) = vin.get_configured(&setup).unwrap();
+    assert!((v - 12.0).abs() < 0.01, "{}", v);
+
+    assert_eq!(
+        MFR_READ_IIN::CommandData::coefficients(&setup),
+        Some(Coefficients {
+            m: 13661,
+            b: -5200,
+            R: -2
+        })
+    );
+
+    setup.set_current_limit(DEVICE_SETUP::CurrentLimit::Low);
+
+    assert_eq!(
+        READ_AVG_PIN::CommandData::coefficients(&setup),
+        Some(Coefficients {
+            m: 369,
+            b: -1900,
+            R: -2
+        })
+    );
+
+    validate!(READ_TEMPERATURE_1, [0x90, 0x01], 25.0, Celsius);
+
+    let avg = SAMPLES_FOR_AVG::CommandData::from_slice(&[0x0c]).unwrap();
+    assert_eq!(avg.get_samples(), 12);
+}
+
+#[test]
+fn lm5066i_configured() {
+    use commands::lm5066i::*;
+
+    let mut setup = DEVICE_SETUP::CommandData(0);
+    setup.set_current_limit(DEVICE_SETUP::CurrentLimit::Low);
+
+    assert_eq!(
+        MFR_READ_IIN::CommandData::coefficients(&setup),
+        Some(Coefficients {
+            m: 7645,
+            b: 100,
+            R: -2
+        })
+    );
+
+    assert_eq!(
+        Device::Lm5066I.format(CommandCode::READ_VOUT as u8),
+        Some(Format::Direct(Coefficients {
+            m: 4602,
+            b: 500,
+            R: -2
+        }))
+    );
+
+    assert_eq!(
+        Device::Lm5066I.format(CommandCode::SAMPLES_FOR_AVG as u8),
+        Some(Format::Bitfield)
+    );
+    assert_eq!(
+        Device::Lm5066I.format(CommandCode::DIAGNOSTIC_WORD_READ as u8),
+        Some(Format::Bitfield)
+    );
+}
+
+#[test]
+fn lm25066_diagnostic() {
+    use commands::lm25066::DIAGNOSTIC_WORD_READ::*;
+
+    //
+    // Power good with a Vin undervoltage warning, latched off after a
+    // circuit breaker fault.
+    //
+    let data = CommandData::from_slice(&[0x01, 0x2a]).unwrap();
+    assert_eq!(data.get_power_good(), Some(PowerGood::PowerGood));
+    assert_eq!(data.get_v_in_uv_warning(), Some(VInUVWarning::Warning));
+    assert_eq!(data.get_timer_latched_off(), Some(TimerLatchedOff::Latched));
+    assert_eq!(
+        data.get_circuit_breaker_fault(),
+        Some(CircuitBreakerFault::Fault)
+    );
+    assert_eq!(data.get_device_off(), Some(DeviceOff::On));
+    assert_eq!(data.get_v_out_uv_warning(), Some(VOutUVWarning::NoWarning));
+
+    let code = commands::lm25066::CommandCode::DIAGNOSTIC_WORD_READ as u8;
+    let mut faults = vec![];
+
+    Device::Lm5066I
+        .interpret(code, &[0x01, 0x2a], mode, |f, v| {
+            if v.name() == "Fault" || v.name() == "Warning" {
+                faults.push(f.name());
+            }
+        })
+        .unwrap();
+
+    assert_eq!(faults, ["VInUVWarning", "CircuitBreakerFault"]);
+}
+
+#[test]
+fn ltc2974() {
+    use commands::ltc2974::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x13]).unwrap();
+
+    let vout = MFR_VOUT_PEAK::CommandData::from_slice(&[0x00, 0x40]).unwrap();
+    assert_eq!(vout.get(mode), Ok(Volts(2.0)));
+
+    let iout = MFR_IOUT_PEAK::CommandData::from_slice(&[0x00, 0xc2]).unwrap();
+    assert_eq!(iout.get(), Ok(Amperes(2.0)));
+
+    let common = MFR_COMMON::CommandData::from_slice(&[0xe0]).unwrap();
+    assert_eq!(common.get_not_busy(), Some(MFR_COMMON::NotBusy::NotBusy));
+
+    let code = CommandCode::MFR_VOUT_MIN_MEASURED as u8;
+    assert_eq!(code, 0xfb);
+    assert_eq!(Device::Ltc2974.units(code), Some(units::Units::Volts));
+    assert_eq!(Device::Ltc2974.format(0xee), Some(Format::Raw));
+}
+
+#[test]
+fn ltc2977() {
+    use commands::ltc2977::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x13]).unwrap();
+
+    let vout =
+        MFR_VOUT_MIN_MEASURED::CommandData::from_slice(&[0x00, 0x30]).unwrap();
+    assert_eq!(vout.get(mode), Ok(Volts(1.5)));
+
+    assert_eq!(Device::Ltc2977.format(0xe0), Some(Format::Raw));
+    assert_eq!(
+        Device::Ltc2977.format(CommandCode::READ_IOUT as u8),
+        Some(Format::Raw)
+    );
+}
+
+#[test]
+fn ltc3880() {
+    use commands::ltc3880::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x14]).unwrap();
+
+    let vout = MFR_VOUT_PEAK::CommandData::from_slice(&[0x00, 0x10]).unwrap();
+    assert_eq!(vout.get(mode), Ok(Volts(1.0)));
+
+    let temp =
+        MFR_TEMPERATURE_1_PEAK::CommandData::from_slice(&[0x40, 0xd2]).unwrap();
+    assert_eq!(temp.get(), Ok(Celsius(9.0)));
+
+    assert_eq!(CommandCode::MFR_PWM_MODE as u8, 0xd4);
+    assert_eq!(CommandCode::MFR_FAULT_LOG.read_op(), Operation::ReadBlock);
+    assert_eq!(
+        CommandCode::MFR_FAULT_LOG_CLEAR.write_op(),
+        Operation::SendByte
+    );
+}
+
+#[test]
+fn ltc3884() {
+    use commands::ltc3884::*;
+    use units::*;
+
+    let iin = MFR_IIN_PEAK::CommandData::from_slice(&[0x00, 0xc2]).unwrap();
+    assert_eq!(iin.get(), Ok(Amperes(2.0)));
+
+    //
+    // Definitions inherited from the LTC3880 are those of the LTC3880.
+    //
+    let common: commands::ltc3880::MFR_COMMON::CommandData =
+        MFR_COMMON::CommandData::from_slice(&[0xe0]).unwrap();
+    assert_eq!(common.get_not_busy(), Some(MFR_COMMON::NotBusy::NotBusy));
+
+    let peak: commands::ltc3880::MFR_IOUT_PEAK::CommandData =
+        MFR_IOUT_PEAK::CommandData::from_slice(&[0x40, 0xd2]).unwrap();
+    assert_eq!(peak.get(), Ok(Amperes(9.0)));
+
+    assert_eq!(CommandCode::MFR_PWM_MODE as u8, 0xd4);
+    assert_eq!(CommandCode::MFR_ADC_CONTROL as u8, 0xd8);
+
+    let code = CommandCode::MFR_TEMPERATURE_1_PEAK as u8;
+    assert_eq!(Device::Ltc3884.units(code), Some(units::Units::Celsius));
+    assert_eq!(Device::Ltc3884.format(0xe1), Some(Format::Linear11));
+    assert_eq!(Device::Ltc3880.format(0xe1), Some(Format::Raw));
+}
+
+#[test]
+fn ltm4664() {
+    use commands::ltm4664::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x14]).unwrap();
+
+    let vout = READ_VOUT::CommandData::from_slice(&[0x00, 0x0c]).unwrap();
+    assert_eq!(vout.get(mode), Ok(Volts(0.75)));
+
+    assert_eq!(Ltm4664::DEVICE, Device::Ltm4664);
+    assert_eq!(Ltm4664::DESC, Device::Ltm4664.desc());
+
+    for code in 0..=0xffu8 {
+        assert_eq!(Device::Ltm4664.format(code), Device::Ltc3880.format(code));
+    }
+}
+
+#[test]
+fn vid() {
+    assert_eq!(Vid(0x5b, VidCode::Vr13).to_real(), 1.4);
+    assert_eq!(Vid(0x01, VidCode::Vr12).to_real(), 0.25);
+    assert_eq!(Vid(0x02, VidCode::Vr11).to_real(), 1.6);
+    assert_eq!(Vid(0xd8, VidCode::Amd625mV).to_real(), 0.2);
+    assert_eq!(Vid(0x00, VidCode::Vr13).to_real(), 0.0);
+
+    assert_eq!(Vid::from_real(1.4, VidCode::Vr13).unwrap().0, 0x5b);
+    assert_eq!(Vid::from_real(1.0, VidCode::Imvp9).unwrap().0, 0x51);
+    assert!(Vid::from_real(3.5, VidCode::Vr13).is_none());
+
+    let clamp = Saturation::Clamp;
+    let vid = Vid::try_from_real(3.5, VidCode::Vr13, clamp).unwrap();
+    assert_eq!(vid.0, 0xff);
+    let vid = Vid::try_from_real(0.1, VidCode::Vr13, clamp).unwrap();
+    assert_eq!(vid.0, 0x01);
+    let vid = Vid::try_from_real(2.0, VidCode::Vr11, clamp).unwrap();
+    assert_eq!(vid.0, 0x02);
+
+    assert_eq!(
+        Vid::try_from_real(3.5, VidCode::Vr13, Saturation::Fail),
+        Err(Error::ValueOutOfRange)
+    );
+    assert_eq!(
+        Vid::try_from_real(f32::NAN, VidCode::Vr13, clamp),
+        Err(Error::ValueOutOfRange)
+    );
+}
+
+#[test]
+fn max16601() {
+    use commands::max16601::*;
+    use units::*;
+
+    let mut vout = READ_VOUT::CommandData::from_slice(&[0x5b, 0x00]).unwrap();
+    assert_eq!(vout.get(), Ok(Volts(1.4)));
+
+    vout.set(Volts(1.0)).unwrap();
+    assert_eq!(vout.0, 0x33);
+    assert_eq!(vout.set(Volts(3.5)), Err(Error::ValueOutOfRange));
+
+    vout.set_saturating(Volts(3.5)).unwrap();
+    assert_eq!(vout.0, 0xff);
+
+    let id = PHASE_ID::CommandData::from_slice(&[0x80]).unwrap();
+    assert_eq!(id.get_core_rail(), Some(PHASE_ID::CoreRail::CoreRail));
+
+    let dvid = SETPT_DVID::CommandData::from_slice(&[0x00]).unwrap();
+    assert_eq!(dvid.get_dac_mode(), Some(SETPT_DVID::DacMode::Mode5mV));
+
+    assert_eq!(
+        Device::Max16601.format(CommandCode::READ_VOUT as u8),
+        Some(Format::Vid(VidCode::Vr13))
+    );
+    assert_eq!(
+        Device::Max16601.format(CommandCode::PHASE_REPORTING as u8),
+        Some(Format::Raw)
+    );
+}
+
+#[test]
+fn max20730() {
+    use commands::max20730::*;
+    use units::*;
+
+    let iout = READ_IOUT::CommandData::from_slice(&[0x6c, 0x02]).unwrap();
+    assert_eq!(iout.get(), Ok(Amperes(8.0)));
+
+    let temp =
+        READ_TEMPERATURE_1::CommandData::from_slice(&[0x68, 0x02]).unwrap();
+    assert_eq!(temp.get(), Ok(Celsius(13.0)));
+
+    let mut vin = READ_VIN::CommandData(0);
+    vin.set(Volts(12.0)).unwrap();
+    assert_eq!(vin.0, 433);
+
+    let devset = MFR_DEVSET1::CommandData::from_slice(&[0x0c, 0x00]).unwrap();
+    assert_eq!(devset.get_fsw(), 3);
+
+    assert_eq!(
+        Device::Max20730.format(CommandCode::READ_IOUT as u8),
+        Some(Format::Direct(Coefficients {
+            m: 153,
+            b: 4976,
+            R: -1
+        }))
+    );
+}
+
+#[test]
+fn max34451() {
+    use commands::max34451::*;
+    use units::*;
+
+    validate!(READ_VOUT, [0xdc, 0x05], 1.5, Volts);
+    validate!(READ_TEMPERATURE_1, [0xc4, 0x09], 25.0, Celsius);
+    validate!(MFR_VOUT_MIN_MEASURED, [0x84, 0x03], 0.9, Volts);
+
+    let mut margin = VOUT_MARGIN_HIGH::CommandData(0);
+    margin.set(Volts(1.05)).unwrap();
+    assert_eq!(margin.0, 1050);
+
+    let mode = MFR_MODE::CommandData::from_slice(&[0x00, 0x80]).unwrap();
+    assert_eq!(
+        mode.get_force_nv_fault_log(),
+        Some(MFR_MODE::ForceNvFaultLog::Force)
+    );
+
+    assert_eq!(
+        Device::Max34451.format(CommandCode::MFR_NV_FAULT_LOG as u8),
+        Some(Format::Raw)
+    );
+}
+
+#[test]
+fn mp2971() {
+    use commands::mp2971::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    validate!(READ_VOUT, mode, [0x00, 0x01], 0.5, Volts);
+    validate!(READ_TEMPERATURE_1, [0x2d, 0x00], 45.0, Celsius);
+
+    assert_eq!(
+        Device::Mp2971.format(CommandCode::READ_VOUT as u8),
+        Some(Format::VOutMode)
+    );
+    assert_eq!(
+        Device::Mp2971.format(CommandCode::PAGE as u8),
+        Device::Mp2975.format(CommandCode::PAGE as u8)
+    );
+}
+
+#[test]
+fn mp2975() {
+    use commands::mp2975::*;
+    use units::*;
+
+    let vout = READ_VOUT::CommandData::from_slice(&[0x33, 0x00]).unwrap();
+    assert_eq!(vout.get(), Ok(Volts(1.0)));
+
+    let temp =
+        READ_TEMPERATURE_1::CommandData::from_slice(&[0x2d, 0x00]).unwrap();
+    assert_eq!(temp.get(), Ok(Celsius(45.0)));
+
+    assert_eq!(
+        Device::Mp2975.format(CommandCode::READ_VOUT as u8),
+        Some(Format::Vid(VidCode::Vr13))
+    );
+    assert_eq!(Device::Mp2975.format(0xe5), Some(Format::Raw));
+}
+
+#[test]
+fn mp5023() {
+    use commands::mp5023::*;
+    use units::*;
+
+    validate!(READ_VIN, [0x80, 0x01], 12.0, Volts);
+    validate!(READ_IOUT, [0x50, 0x00], 5.0, Amperes);
+    validate!(READ_TEMPERATURE_1, [0x5a, 0x00], 45.0, Celsius);
+    validate!(READ_POUT, [0x3c, 0x00], 60.0, Watts);
+
+    let mut vout = READ_VOUT::CommandData(0);
+    vout.set(Volts(5.0)).unwrap();
+    assert_eq!(vout.0, 160);
+}
+
+#[test]
+fn raa228926_defaults() {
+    use commands::raa228926::*;
+    use units::*;
+
+    let vout = VOutModeCommandData::from_slice(&[0x40]).unwrap();
+
+    validate!(VOUT_COMMAND, vout, [0x84, 0x03], 0.9, Volts);
+    validate!(VOUT_MAX, vout, [0xea, 0x0b], 3.05, Volts);
+    validate!(VOUT_MARGIN_HIGH, vout, [0xb1, 0x03], 0.945, Volts);
+    validate!(VOUT_MARGIN_LOW, vout, [0x57, 0x03], 0.855, Volts);
+    validate!(
+        VOUT_TRANSITION_RATE,
+        [0xc4, 0x09],
+        0.025,
+        VoltsPerMicrosecond
+    );
+    validate!(FREQUENCY_SWITCH, [0x58, 0x02], 600, Kilohertz);
+    validate!(VIN_ON, [0xbc, 0x02], 7, Volts);
+    validate!(VIN_OFF, [0xf4, 0x01], 5, Volts);
     validate!(VOUT_OV_FAULT_LIMIT, vout, [0x1c, 0x0c], 3.1, Volts);
     validate!(IOUT_OC_FAULT_LIMIT, [0x2c, 0x01], 30, Amperes);
     validate!(OT_FAULT_LIMIT, [0x7d, 0x00], 125, Celsius);
@@ -1344,47 +2203,126 @@ fn raa229618_defaults() {
     validate!(VIN_OV_WARN_LIMIT, [0x08, 0x07], 18, Volts);
     validate!(IIN_OC_FAULT_LIMIT, [0x98, 0x3a], 150, Amperes);
 
-    // Either the value or the factor is incorrect in the datasheet!
-    // validate!(TON_RISE, [0x32, 0x00], 0.500, Milliseconds);
-    // validate!(TOFF_FALL, [0x32, 0x00], 0.500, Milliseconds);
+    validate!(TON_RISE, [0xf4, 0x01], 0.500, Milliseconds);
+    validate!(TOFF_FALL, [0xf4, 0x01], 0.500, Milliseconds);
 
-    validate!(BOOTRATE, [0xf4, 0x01], 0.005, VoltsPerMicrosecond);
     validate!(PEAK_OC_LIMIT, [0x58, 0x02], 60, Amperes);
     validate!(PEAK_UC_LIMIT, [0xa8, 0xfd], -60, Amperes);
     validate!(HS_BUS_CURRENT_SCALE, [0x00, 0x40], 1.0, Unitless);
+    validate!(IOUT_ALERT_THRESHOLD, [0xc8, 0x00], 20.0, Amperes);
 
     let ocuc = PEAK_OCUC_COUNT::CommandData::from_slice(&[0x06, 0x06]).unwrap();
     assert_eq!(ocuc.get_uc_limit(), 6);
     assert_eq!(ocuc.get_oc_limit(), 6);
     dump(&ocuc);
-
-    let comp =
-        COMPPROP::CommandData::from_slice(&[0xc4, 0x07, 0x09, 0xd9]).unwrap();
-    dump(&comp);
 }
 
 #[test]
-fn raa229618_filt() {
-    use commands::raa229618::*;
+fn raa228926_filt() {
+    use commands::raa228926::*;
     use units::*;
 
-    let fast =
-        FAST_OC_FILT_COUNT::CommandData::from_slice(&[0x96, 0x06]).unwrap();
+    let filt =
+        SUM_OC_FILT_COUNT::CommandData::from_slice(&[0x96, 0x06]).unwrap();
 
-    dump(&fast);
-    assert_eq!(fast.get_delay(), Microseconds(100.0));
-    assert_eq!(fast.get_filter(), Microseconds(10.666667));
+    dump(&filt);
+    assert_eq!(filt.get_delay(), Microseconds(100.0));
+    assert_eq!(filt.get_filter(), Microseconds(10.666667));
 
-    let slow =
-        SLOW_OC_FILT_COUNT::CommandData::from_slice(&[0x06, 0x06]).unwrap();
+    let filt =
+        IOUT_ALERT_FILT_COUNT::CommandData::from_slice(&[0x00, 0x06]).unwrap();
 
-    assert_eq!(slow.get_delay(), Microseconds(1024.2));
-    assert_eq!(slow.get_filter(), Microseconds(10.666667));
-    dump(&slow);
+    assert_eq!(filt.get_filter(), Microseconds(10.666667));
+    dump(&filt);
 }
 
 #[test]
-fn raa229618_loopcfg() {
+fn raa228926_config() {
+    use commands::raa228926::*;
+    use units::*;
+
+    validate!(IOUT_ALERT_THRESHOLD, [0xe8, 0x03], 100.0, Amperes);
+
+    assert_eq!(
+        CommandCode::from_u8(0xea),
+        Some(CommandCode::IOUT_ALERT_THRESHOLD)
+    );
+    assert_eq!(
+        commands::raa229618::CommandCode::from_u8(0xea),
+        Some(commands::raa229618::CommandCode::SLOW_IOUT_OC_LIMIT)
+    );
+}
+
+#[test]
+fn raa229618_defaults() {
+    use commands::raa229618::*;
+    use units::*;
+
+    let vout = VOutModeCommandData::from_slice(&[0x40]).unwrap();
+
+    validate!(VOUT_COMMAND, vout, [0x84, 0x03], 0.9, Volts);
+    validate!(VOUT_MAX, vout, [0xea, 0x0b], 3.05, Volts);
+    validate!(VOUT_MARGIN_HIGH, vout, [0xb1, 0x03], 0.945, Volts);
+    validate!(VOUT_MARGIN_LOW, vout, [0x57, 0x03], 0.855, Volts);
+    validate!(
+        VOUT_TRANSITION_RATE,
+        [0xc4, 0x09],
+        0.025,
+        VoltsPerMicrosecond
+    );
+    validate!(FREQUENCY_SWITCH, [0x58, 0x02], 600, Kilohertz);
+    validate!(VIN_ON, [0xbc, 0x02], 7, Volts);
+    validate!(VIN_OFF, [0xf4, 0x01], 5, Volts);
+    validate!(VOUT_OV_FAULT_LIMIT, vout, [0x1c, 0x0c], 3.1, Volts);
+    validate!(IOUT_OC_FAULT_LIMIT, [0x2c, 0x01], 30, Amperes);
+    validate!(OT_FAULT_LIMIT, [0x7d, 0x00], 125, Celsius);
+    validate!(OT_WARN_LIMIT, [0x6e, 0x00], 110, Celsius);
+    validate!(UT_FAULT_LIMIT, [0xd8, 0xff], -40, Celsius);
+    validate!(VIN_OV_FAULT_LIMIT, [0x40, 0x06], 16, Volts);
+    validate!(VIN_OV_WARN_LIMIT, [0x08, 0x07], 18, Volts);
+    validate!(IIN_OC_FAULT_LIMIT, [0x98, 0x3a], 150, Amperes);
+
+    // Either the value or the factor is incorrect in the datasheet!
+    // validate!(TON_RISE, [0x32, 0x00], 0.500, Milliseconds);
+    // validate!(TOFF_FALL, [0x32, 0x00], 0.500, Milliseconds);
+
+    validate!(BOOTRATE, [0xf4, 0x01], 0.005, VoltsPerMicrosecond);
+    validate!(PEAK_OC_LIMIT, [0x58, 0x02], 60, Amperes);
+    validate!(PEAK_UC_LIMIT, [0xa8, 0xfd], -60, Amperes);
+    validate!(HS_BUS_CURRENT_SCALE, [0x00, 0x40], 1.0, Unitless);
+
+    let ocuc = PEAK_OCUC_COUNT::CommandData::from_slice(&[0x06, 0x06]).unwrap();
+    assert_eq!(ocuc.get_uc_limit(), 6);
+    assert_eq!(ocuc.get_oc_limit(), 6);
+    dump(&ocuc);
+
+    let comp =
+        COMPPROP::CommandData::from_slice(&[0xc4, 0x07, 0x09, 0xd9]).unwrap();
+    dump(&comp);
+}
+
+#[test]
+fn raa229618_filt() {
+    use commands::raa229618::*;
+    use units::*;
+
+    let fast =
+        FAST_OC_FILT_COUNT::CommandData::from_slice(&[0x96, 0x06]).unwrap();
+
+    dump(&fast);
+    assert_eq!(fast.get_delay(), Microseconds(100.0));
+    assert_eq!(fast.get_filter(), Microseconds(10.666667));
+
+    let slow =
+        SLOW_OC_FILT_COUNT::CommandData::from_slice(&[0x06, 0x06]).unwrap();
+
+    assert_eq!(slow.get_delay(), Microseconds(1024.2));
+    assert_eq!(slow.get_filter(), Microseconds(10.666667));
+    dump(&slow);
+}
+
+#[test]
+fn raa229618_loopcfg() {
     use commands::raa229618::LOOPCFG::*;
 
     let loopcfg = CommandData::from_slice(&[0xf6, 0x71, 0x20, 0x10]).unwrap();
@@ -1400,3 +2338,3888 @@ fn raa229618_loopcfg() {
 
     dump(&loopcfg);
 }
+
+#[test]
+fn raa229618_phase_dma() {
+    use commands::raa229618::*;
+    use units::*;
+
+    validate!(PHASE_CURRENT, [0x7d, 0x00], 12.5, Amperes);
+
+    for code in [CommandCode::DMAFIX, CommandCode::DMASEQ] {
+        assert_eq!(code.read_op(), Operation::ReadWord32);
+        assert_eq!(code.write_op(), Operation::WriteWord32);
+    }
+
+    assert_eq!(CommandCode::DMAADDR.read_op(), Operation::ReadWord);
+    assert_eq!(
+        Device::Raa229618.format(CommandCode::DMAADDR as u8),
+        Device::Isl68224.format(CommandCode::DMAADDR as u8)
+    );
+}
+
+#[test]
+fn tps25990() {
+    use commands::tps25990::*;
+    use units::*;
+
+    validate!(READ_TEMPERATURE_1, [0x64, 0x01], 25.0, Celsius);
+    validate!(READ_VIN_PEAK, [0x83, 0x14], 100.0, Volts);
+
+    let mut vin = READ_VIN::CommandData(0);
+    vin.set(Volts(12.0)).unwrap();
+    assert_eq!(vin.0, 630);
+
+    let pk = PK_MIN_AVG::CommandData::from_slice(&[0xa4]).unwrap();
+    assert_eq!(pk.get_reset_peak(), Some(PK_MIN_AVG::ResetPeak::Reset));
+    assert_eq!(pk.get_reset_min(), Some(PK_MIN_AVG::ResetMin::Reset));
+    assert_eq!(pk.get_average_count(), 4);
+
+    assert_eq!(Device::Tps25990.format(0xd4), Some(Format::Raw));
+}
+
+#[test]
+fn tps53659() {
+    use commands::tps53659::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x21]).unwrap();
+    assert_eq!(ti::vid_code(&mode), Ok(VidCode::Vr12));
+    assert_eq!(ti::vid_vout(0x97, &mode), Ok(Volts(1.0)));
+
+    let mode = VOutModeCommandData::from_slice(&[0x24]).unwrap();
+    assert_eq!(ti::vid_vout(0x33, &mode), Ok(Volts(1.0)));
+
+    let mode = VOutModeCommandData::from_slice(&[0x23]).unwrap();
+    assert_eq!(ti::vid_code(&mode), Err(Error::InvalidMode));
+
+    validate!(READ_IOUT, [0xc8, 0xf0], 50.0, Amperes);
+}
+
+#[test]
+fn tps53688() {
+    use commands::tps53688::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x22]).unwrap();
+    assert_eq!(ti::vid_vout(0x33, &mode), Ok(Volts(1.0)));
+
+    let iout = READ_IOUT::CommandData::from_slice(&[0xc8, 0xf0]).unwrap();
+    assert_eq!(iout.get(), Ok(Amperes(50.0)));
+
+    for code in 0..=0xffu8 {
+        assert_eq!(
+            Device::Tps53688.format(code),
+            Device::Tps53659.format(code)
+        );
+    }
+}
+
+#[test]
+fn ucd9090() {
+    use commands::ucd9090::*;
+    use units::*;
+
+    let gpio = GPIO_CONFIG::CommandData::from_slice(&[0x0b]).unwrap();
+    assert_eq!(gpio.get_status(), Some(GPIO_CONFIG::Status::High));
+    assert_eq!(gpio.get_output_value(), Some(GPIO_CONFIG::OutputValue::Low));
+    assert_eq!(gpio.get_enable(), Some(GPIO_CONFIG::Enable::Enabled));
+
+    let mode = VOutModeCommandData::from_slice(&[0x13]).unwrap();
+    validate!(READ_VOUT, mode, [0x00, 0x28], 1.25, Volts);
+
+    assert_eq!(CommandCode::LOGGED_FAULTS.read_op(), Operation::ReadBlock);
+    assert_eq!(
+        Device::Ucd9090.format(CommandCode::MONITOR_CONFIG as u8),
+        Some(Format::Raw)
+    );
+}
+
+#[test]
+fn ucd90320() {
+    use commands::ucd90320::*;
+
+    let gpio = GPIO_CONFIG::CommandData::from_slice(&[0x03]).unwrap();
+    assert_eq!(
+        gpio.get_output_enable(),
+        Some(GPIO_CONFIG::OutputEnable::Enabled)
+    );
+
+    for code in 0..=0xffu8 {
+        assert_eq!(Device::Ucd90320.format(code), Device::Ucd9090.format(code));
+    }
+}
+
+#[test]
+fn tda38640() {
+    use commands::tda38640::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    validate!(READ_VOUT, mode, [0x00, 0x02], 1.0, Volts);
+    validate!(MFR_VOUT_OFFSET, mode, [0xf0, 0xff], -0.03125, Volts);
+    validate!(VOUT_SCALE_LOOP, [0x01, 0xf0], 0.25, Unitless);
+
+    let mode = VOutModeCommandData::from_slice(&[0x21]).unwrap();
+    assert_eq!(infineon::vid_code(&mode), Ok(VidCode::Vr12));
+    assert_eq!(infineon::vid_vout(0x97, &mode), Ok(Volts(1.0)));
+
+    let data = MFR_SVID_CONFIG::CommandData::from_slice(&[0x03]).unwrap();
+    assert_eq!(data.get_mode(), Some(MFR_SVID_CONFIG::Mode::SVID));
+    assert_eq!(data.get_protocol(), Some(MFR_SVID_CONFIG::Protocol::Vr13));
+
+    let data = MFR_SVID_ADDRESS::CommandData::from_slice(&[0x02]).unwrap();
+    assert_eq!(data.get_address(), 2);
+}
+
+#[test]
+fn xdpe12284() {
+    use commands::xdpe12284::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x22]).unwrap();
+    assert_eq!(infineon::vid_code(&mode), Ok(VidCode::Vr13));
+    assert_eq!(infineon::vid_vout(0x5b, &mode), Ok(Volts(1.4)));
+
+    let mode = VOutModeCommandData::from_slice(&[0x30]).unwrap();
+    assert_eq!(infineon::vid_code(&mode), Ok(VidCode::Amd625mV));
+
+    let mode = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    assert_eq!(infineon::vid_code(&mode), Err(Error::InvalidMode));
+
+    let vout = READ_VOUT::CommandData::from_slice(&[0x5b, 0x00]).unwrap();
+    assert_eq!(vout.get(mode), Ok(Volts(0.177734375)));
+
+    assert_eq!(
+        Device::Xdpe12284.format(CommandCode::MFR_FW_CMD_DATA as u8),
+        Some(Format::Raw)
+    );
+}
+
+#[test]
+fn xdpe15284() {
+    use commands::xdpe15284::*;
+    use units::*;
+
+    let mode = VOutModeCommandData::from_slice(&[0x16]).unwrap();
+    validate!(READ_VOUT, mode, [0x00, 0x03], 0.75, Volts);
+
+    assert_eq!(Xdpe15284::DESC, Device::Xdpe15284.desc());
+
+    for code in 0..=0xffu8 {
+        assert_eq!(
+            Device::Xdpe15284.format(code),
+            Device::Xdpe12284.format(code)
+        );
+    }
+}
+
+#[test]
+fn linear11_roundtrip() {
+    //
+    // Every representable value should encode back to a word that decodes
+    // to exactly the same value (though not necessarily the same word, as
+    // there are many redundant encodings).
+    //
+    for raw in 0..=u16::MAX {
+        let x = Linear11(raw).to_real();
+        let encoded = Linear11::from_real(x).unwrap();
+        assert_eq!(encoded.to_real(), x, "raw 0x{:04x}", raw);
+    }
+}
+
+#[test]
+fn linear11_from_real_sweep() {
+    //
+    // Sweep across the range of Linear11, verifying that no exponent gets
+    // closer to our value than the one that we picked.
+    //
+    let best = |x: f32| {
+        (-16..=15)
+            .filter_map(|n| {
+                let exp = f32::powi(2.0, n);
+                let y = (x / exp).round();
+
+                if (-1024.0..=1023.0).contains(&y) {
+                    Some((x - y * exp).abs())
+                } else {
+                    None
+                }
+            })
+            .fold(f32::INFINITY, f32::min)
+    };
+
+    let mut x = 1.0 / 65536.0;
+
+    while x < 1023.0 * 32768.0 {
+        for val in [x, -x] {
+            let encoded = Linear11::from_real(val).unwrap();
+            let err = (encoded.to_real() - val).abs();
+            assert!(err <= best(val), "{}: error of {}", val, err);
+        }
+
+        x *= 1.0001;
+    }
+}
+
+#[test]
+fn linear11_from_real_edges() {
+    assert_eq!(Linear11::from_real(0.0).unwrap().to_real(), 0.0);
+    assert_eq!(Linear11::from_real(1023.75).unwrap().to_real(), 1024.0);
+    assert_eq!(Linear11::from_real(-1024.0).unwrap().to_real(), -1024.0);
+    assert_eq!(Linear11::from_real(1023.0 * 32768.0).unwrap().0, 0x7bff);
+    assert!(Linear11::from_real(1024.0 * 32768.0).is_none());
+    assert!(Linear11::from_real(f32::NAN).is_none());
+    assert!(Linear11::from_real(f32::INFINITY).is_none());
+}
+
+#[test]
+fn linear11_range() {
+    assert_eq!(Linear11::MAX.0, 0x7bff);
+    assert_eq!(Linear11::MAX.to_real(), 1023.0 * 32768.0);
+    assert_eq!(Linear11::MIN.0, 0x7c00);
+    assert_eq!(Linear11::MIN.to_real(), -1024.0 * 32768.0);
+
+    assert_eq!(Linear11::resolution_at(0.0), Some(f32::powi(2.0, -16)));
+    assert_eq!(Linear11::resolution_at(1.0), Some(f32::powi(2.0, -9)));
+    assert_eq!(Linear11::resolution_at(-1.0), Some(f32::powi(2.0, -10)));
+    assert_eq!(Linear11::resolution_at(1000.0), Some(1.0));
+    assert_eq!(Linear11::resolution_at(1023.75), Some(2.0));
+    assert_eq!(
+        Linear11::resolution_at(Linear11::MAX.to_real()),
+        Some(32768.0)
+    );
+    assert_eq!(Linear11::resolution_at(1024.0 * 32768.0), None);
+    assert_eq!(Linear11::resolution_at(f32::NAN), None);
+}
+
+#[test]
+fn linear11_millis() {
+    //
+    // Our integer conversion should agree with the floating point one for
+    // every word whose value fits.
+    //
+    for raw in 0..=u16::MAX {
+        let x = Linear11(raw).to_real() * 1000.0;
+
+        if x.abs() < i32::MAX as f32 {
+            let millis = Linear11(raw).to_millis();
+            assert!((millis as f32 - x).abs() <= 0.5, "raw 0x{:04x}", raw);
+        }
+    }
+
+    assert_eq!(Linear11(0x7bff).to_millis(), i32::MAX);
+    assert_eq!(Linear11(0x7c00).to_millis(), i32::MIN);
+
+    //
+    // Halves are rounded away from zero, regardless of sign:  with an
+    // exponent of -4, a mantissa of 1 is 62.5 millis.
+    //
+    assert_eq!(Linear11(0xe001).to_millis(), 63);
+    assert_eq!(Linear11(0xe7ff).to_millis(), -63);
+
+    //
+    // And encoding from millis should pick the same word as encoding from
+    // the equivalent real value.
+    //
+    let mut millis = 1;
+
+    while millis < 1 << 24 {
+        for m in [millis, -millis] {
+            let expected = Linear11::from_real(m as f32 / 1000.0).unwrap();
+            let encoded = Linear11::from_millis(m).unwrap();
+            assert_eq!(encoded.0, expected.0, "{} millis", m);
+        }
+
+        millis += millis / 1000 + 1;
+    }
+
+    assert_eq!(Linear11::from_millis(0).unwrap().to_millis(), 0);
+    assert_eq!(Linear11::from_millis(1200).unwrap().to_millis(), 1199);
+    assert_eq!(Linear11::from_millis(-12_500).unwrap().to_millis(), -12_500);
+}
+
+#[test]
+fn ulinear16_micros() {
+    for exp in [-16, -12, -9, 0, 3, 15] {
+        let exp = ULinear16Exponent(exp);
+
+        for raw in (0..=u16::MAX).step_by(7) {
+            let val = ULinear16(raw, exp);
+            let x = val.to_real() as f64 * 1_000_000.0;
+            let micros = val.to_micros();
+            assert!((micros as f64 - x).abs() <= 0.5, "0x{:04x}", raw);
+
+            let encoded = ULinear16::from_micros(micros, exp).unwrap();
+            assert_eq!(encoded.0, raw, "0x{:04x} at {:?}", raw, exp);
+        }
+    }
+
+    let exp = ULinear16Exponent(-9);
+    assert_eq!(ULinear16::from_micros(1_200_000, exp).unwrap().0, 0x0266);
+    assert!(ULinear16::from_micros(-1_000, exp).is_none());
+    assert!(ULinear16::from_micros(128_000_000, exp).is_none());
+    assert!(ULinear16::from_micros(0, ULinear16Exponent(16)).is_none());
+}
+
+#[test]
+fn slinear16() {
+    let exp = ULinear16Exponent(-9);
+
+    assert_eq!(SLinear16(0x0266, exp).to_real(), 1.1992188);
+    assert_eq!(SLinear16(0xff9a, exp).to_real(), -0.19921875);
+    assert_eq!(SLinear16::from_real(-0.2, exp).unwrap().0, 0xff9a);
+    assert_eq!(SLinear16::from_real(-64.0, exp).unwrap().0, 0x8000);
+    assert!(SLinear16::from_real(64.0, exp).is_none());
+
+    let clamped = SLinear16::try_from_real(-100.0, exp, Saturation::Clamp);
+    assert_eq!(clamped.unwrap().0, 0x8000);
+
+    assert_eq!(SLinear16(0xff9a, exp).to_micros(), -199_219);
+    assert_eq!(SLinear16::from_micros(-200_000, exp).unwrap().0, 0xff9a);
+
+    //
+    // Commands with signed VOUT_MODE values are decoded as SLINEAR16.
+    //
+    let vout = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    let mut trim = commands::VOUT_TRIM::CommandData(0xff9a);
+    assert_eq!(trim.get(vout), Ok(units::Volts(-0.19921875)));
+
+    trim.set(vout, units::Volts(-0.05)).unwrap();
+    assert_eq!(trim.0, 0xffe6);
+
+    let offset = commands::bmr480::MFR_VOUT_OFFSET_MONITOR::CommandData(0xfffe);
+    assert_eq!(offset.get(vout), Ok(units::Volts(-0.00390625)));
+
+    let vout = commands::VOUT_COMMAND::CommandData(0xff9a);
+    assert_eq!(
+        vout.get(VOutModeCommandData::from_slice(&[0x17]).unwrap()),
+        Ok(units::Volts(127.80078))
+    );
+}
+
+#[test]
+fn direct_micros() {
+    let coefficients = [
+        Coefficients {
+            m: 6427,
+            b: 0,
+            R: -2,
+        },
+        Coefficients {
+            m: 663,
+            b: 20480,
+            R: -1,
+        },
+        Coefficients { m: 42, b: -3, R: 1 },
+        Coefficients { m: -5, b: 12, R: 0 },
+    ];
+
+    for c in coefficients {
+        for raw in (0..=u16::MAX).step_by(3) {
+            let val = Direct(raw, c);
+            let x = val.to_real() as f64 * 1_000_000.0;
+            let micros = val.to_micros();
+            let tolerance = (x.abs() * 1e-6).max(0.5);
+            assert!((micros as f64 - x).abs() <= tolerance, "0x{:04x}", raw);
+
+            let encoded = Direct::from_micros(micros, c).unwrap();
+            assert_eq!(encoded.0, raw, "0x{:04x} with {:?}", raw, c);
+        }
+    }
+
+    let c = Coefficients {
+        m: 6427,
+        b: 0,
+        R: -2,
+    };
+    assert_eq!(Direct::from_micros(12_000_000, c).unwrap().0, 771);
+    assert!(Direct::from_micros(i64::MAX, c).is_none());
+    assert_eq!(Direct(0, Coefficients { m: 0, b: 0, R: 0 }).to_micros(), 0);
+}
+
+#[test]
+fn rounding() {
+    use Rounding::*;
+
+    let lin = |x, r| Linear11::from_real_rounded(x, r).unwrap().to_real();
+    assert_eq!(lin(1.2, Nearest), 1.1992188);
+    assert_eq!(lin(1.2, Down), 1.1992188);
+    assert_eq!(lin(1.2, Up), 1.2011719);
+    assert_eq!(lin(-1.2, TowardZero), -1.1992188);
+    assert_eq!(lin(-1.2, Down), -1.2011719);
+    assert_eq!(lin(1.0, Up), 1.0);
+
+    let exp = ULinear16Exponent(-9);
+    let ulin = |x, r| {
+        ULinear16::try_from_real_rounded(x, exp, Saturation::Fail, r)
+            .unwrap()
+            .0
+    };
+    assert_eq!(ulin(1.2, Nearest), 614);
+    assert_eq!(ulin(1.2, Down), 614);
+    assert_eq!(ulin(1.2, Up), 615);
+
+    let half = |x, r| Half::from_real_rounded(x, r).map(|h| h.0);
+    assert_eq!(half(0.1, Nearest), Some(0x2e66));
+    assert_eq!(half(0.1, Up), Some(0x2e67));
+    assert_eq!(half(0.1, Down), Some(0x2e66));
+    assert_eq!(half(-0.1, Up), Some(0xae66));
+    assert_eq!(half(-0.1, Down), Some(0xae67));
+    assert_eq!(half(1e-9, Up), Some(0x0001));
+    assert_eq!(half(-1e-9, Down), Some(0x8001));
+    assert_eq!(half(70000.0, TowardZero), Some(0x7bff));
+    assert_eq!(half(70000.0, Up), None);
+
+    //
+    // With a negative slope, rounding the value up rounds the datum down.
+    //
+    let c = Coefficients { m: -5, b: 0, R: 0 };
+    let direct = |x, r| Direct::from_real_rounded(x, c, r).to_real();
+    assert_eq!(direct(1.1, Up), 1.2);
+    assert_eq!(direct(1.1, Down), 1.0);
+
+    //
+    // Negative data are encoded in two's complement, not clamped to zero.
+    //
+    let c5 = Coefficients { m: 5, b: 0, R: 0 };
+    assert_eq!(Direct::from_real(-1.0, c5).0, 0xfffb);
+    assert_eq!(Direct::from_real(1.1, c5).0, 6);
+
+    let direct32 = |x, r| Direct32::from_real_rounded(x, c, r).to_real();
+    assert_eq!(direct32(1.1, Up), 1.2);
+    assert_eq!(direct32(1.1, Down), 1.0);
+    assert_eq!(direct32(-1.1, TowardZero), -1.0);
+
+    //
+    // And the generated setters round as they are told.
+    //
+    let vout = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    let mut low = commands::VOUT_MARGIN_LOW::CommandData(0);
+    low.set_rounded(vout, units::Volts(1.2), Down).unwrap();
+    assert_eq!(low.0, 614);
+
+    let mut high = commands::VOUT_MARGIN_HIGH::CommandData(0);
+    high.set_rounded(vout, units::Volts(1.2), Up).unwrap();
+    assert_eq!(high.0, 615);
+
+    let mut oc = commands::bmr480::IOUT_OC_FAULT_LIMIT::CommandData(0);
+    oc.set_rounded(units::Amperes(1.2), Up).unwrap();
+    assert_eq!(oc.get(), Ok(units::Amperes(1.2011719)));
+
+    //
+    // A fixed point value is set by truncation, as it always has been;
+    // rounding it otherwise must be asked for.
+    //
+    let mut rc = commands::bmr491::MFR_RC_LEVEL::CommandData(0);
+    rc.set(units::Volts(1.26)).unwrap();
+    assert_eq!(rc.0, 12);
+    rc.set(units::Volts(1.29)).unwrap();
+    assert_eq!(rc.0, 12);
+    rc.set_rounded(units::Volts(1.26), Nearest).unwrap();
+    assert_eq!(rc.0, 13);
+    rc.set_rounded(units::Volts(1.26), Down).unwrap();
+    assert_eq!(rc.0, 12);
+    rc.set_saturating(units::Volts(30.0)).unwrap();
+    assert_eq!(rc.0, 0xff);
+
+    let c = Coefficients { m: 5, b: 0, R: 0 };
+    let mut ov = commands::adm1272::VIN_OV_WARN_LIMIT::CommandData(0);
+    ov.set_rounded(&c, units::Volts(1.1), Up).unwrap();
+    assert_eq!(ov.0, 6);
+    ov.set_rounded(&c, units::Volts(1.1), Down).unwrap();
+    assert_eq!(ov.0, 5);
+}
+
+#[test]
+fn set_checked() {
+    let vout = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    let mut data = commands::VOUT_COMMAND::CommandData(0);
+
+    let err = data.set_checked(vout, units::Volts(1.2)).unwrap();
+    assert_eq!(data.0, 614);
+    assert!((err.0 + 0.00078125).abs() < 1e-6, "{:?}", err);
+
+    let err = data.set_checked(vout, units::Volts(1.0)).unwrap();
+    assert_eq!(err, units::Volts(0.0));
+
+    assert_eq!(
+        data.set_checked(vout, units::Volts(-1.0)),
+        Err(Error::ValueOutOfRange)
+    );
+
+    let mut oc = commands::bmr480::IOUT_OC_FAULT_LIMIT::CommandData(0);
+    let err = oc.set_checked(units::Amperes(40.1)).unwrap();
+    assert_eq!(oc.get(), Ok(units::Amperes(40.125)));
+    assert!((err.0 - 0.025).abs() < 1e-5, "{:?}", err);
+}
+
+#[test]
+fn direct32() {
+    let c = Coefficients { m: 1, b: 0, R: 2 };
+
+    assert_eq!(Direct32(1_234_567, c).to_real(), 12345.67);
+    assert_eq!(Direct32::from_real(12345.67, c).0, 1_234_567);
+    assert_eq!(Direct32(0xffff_ff9c, c).to_real(), -1.0);
+    assert_eq!(Direct32::from_real(-1.0, c).0, 0xffff_ff9c);
+
+    //
+    // Values well beyond the reach of a 16-bit datum survive the trip.
+    //
+    let c = Coefficients { m: 3, b: 0, R: 0 };
+    let raw = 0x4000_0001;
+    let x = Direct32(raw, c).to_real();
+    assert_eq!(x, 357913941.67);
+    assert_eq!(Direct32::from_real(x, c).to_real(), x);
+
+    let c = Coefficients { m: 1, b: 0, R: 0 };
+    let mut kwh = commands::READ_KWH_IN::CommandData::from_slice(&[
+        0xa0, 0x86, 0x01, 0x00,
+    ])
+    .unwrap();
+    assert_eq!(kwh.get(&c), Ok(units::KilowattHours(100000.0)));
+
+    kwh.set(&c, units::KilowattHours(250000.0)).unwrap();
+    assert_eq!(kwh.0, 250000);
+}
+
+#[test]
+fn half_roundtrip() {
+    //
+    // Unlike Linear11, every finite half-precision value has exactly one
+    // encoding, so every finite word should encode back to itself.
+    //
+    for raw in 0..=u16::MAX {
+        let x = Half(raw).to_real();
+
+        if !x.is_finite() {
+            assert_eq!(raw & 0x7c00, 0x7c00, "raw 0x{:04x}", raw);
+            continue;
+        }
+
+        assert_eq!(Half::from_real(x).unwrap().0, raw, "raw 0x{:04x}", raw);
+    }
+}
+
+#[test]
+fn half_from_real_edges() {
+    assert_eq!(Half(0x3c00).to_real(), 1.0);
+    assert_eq!(Half(0xc000).to_real(), -2.0);
+    assert_eq!(Half(0x7bff).to_real(), 65504.0);
+    assert_eq!(Half(0x0001).to_real(), f32::powi(2.0, -24));
+    assert_eq!(Half(0x7c00).to_real(), f32::INFINITY);
+    assert!(Half(0x7e00).to_real().is_nan());
+
+    assert_eq!(Half::from_real(1.0).unwrap().0, 0x3c00);
+    assert_eq!(Half::from_real(0.1).unwrap().0, 0x2e66);
+    assert_eq!(Half::from_real(65504.0).unwrap().0, 0x7bff);
+    assert_eq!(Half::from_real(65519.0).unwrap().0, 0x7bff);
+    assert!(Half::from_real(65520.0).is_none());
+    assert!(Half::from_real(f32::NAN).is_none());
+    assert!(Half::from_real(f32::INFINITY).is_none());
+
+    //
+    // Ties go to even, both among normals and among subnormals.
+    //
+    assert_eq!(
+        Half::from_real(1.0 + f32::powi(2.0, -11)).unwrap().0,
+        0x3c00
+    );
+    assert_eq!(
+        Half::from_real(1.0 + 3.0 * f32::powi(2.0, -11)).unwrap().0,
+        0x3c02
+    );
+    assert_eq!(Half::from_real(f32::powi(2.0, -25)).unwrap().0, 0x0000);
+    assert_eq!(
+        Half::from_real(3.0 * f32::powi(2.0, -25)).unwrap().0,
+        0x0002
+    );
+    assert_eq!(Half::from_real(f32::powi(2.0, -30)).unwrap().0, 0x0000);
+    assert_eq!(Half::from_real(-f32::powi(2.0, -30)).unwrap().0, 0x8000);
+}
+
+#[test]
+fn vout_command_half() {
+    use commands::VOUT_COMMAND::*;
+
+    //
+    // VOUT_MODE of 0x60 denotes IEEE half-precision.
+    //
+    let vout = VOutModeCommandData::from_slice(&[0x60]).unwrap();
+    let mut data = CommandData::from_slice(&[0xcd, 0x3c]).unwrap();
+    assert_eq!(data.get(vout), Ok(units::Volts(1.2001953)));
+
+    data.set(vout, units::Volts(0.85)).unwrap();
+    assert_eq!(data.0, 0x3acd);
+    assert_eq!(data.get(vout), Ok(units::Volts(0.85009766)));
+
+    assert_eq!(
+        data.set(vout, units::Volts(100000.0)),
+        Err(Error::ValueOutOfRange)
+    );
+}
+
+#[test]
+fn device_garbage() {
+    use std::fmt::Write;
+
+    //
+    // A simple xorshift generator suffices to give us garbage payloads
+    // without taking on a dependency.
+    //
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+
+    let mut rand = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    //
+    // We want to be sure that we exercise every VOUT_MODE, including those
+    // that are invalid for the command.
+    //
+    let modes = [0x00u8, 0x17, 0x1f, 0x40, 0x60, 0x97, 0xff];
+
+    devices(|d| {
+        for code in 0..=0xffu8 {
+            //
+            // A block may be many dozens of bytes in size, so we sweep its
+            // every possible length (up to and past the 255 bytes that a
+            // block can hold); for other commands, we sweep the lengths
+            // around that dictated by the operation.
+            //
+            let mut block = false;
+
+            d.command(code, |cmd| {
+                block = [cmd.read_op(), cmd.write_op()].iter().any(|op| {
+                    matches!(op, Operation::ReadBlock | Operation::WriteBlock)
+                });
+            });
+
+            let lens: Vec<usize> = match block {
+                true => (0..=256).collect(),
+                false => (0..=8).chain([255, 256]).collect(),
+            };
+
+            for len in lens {
+                for _ in 0..4 {
+                    let mut payload = [0u8; 256];
+                    payload.iter_mut().for_each(|b| *b = rand() as u8);
+
+                    let m = modes[rand() as usize % modes.len()];
+                    let mode =
+                        || VOutModeCommandData::from_slice(&[m]).unwrap();
+                    let mut s = String::new();
+
+                    let _ = d.interpret(code, &payload[..len], mode, |f, v| {
+                        write!(
+                            s,
+                            "{} {} {} {}",
+                            f.name(),
+                            f.desc(),
+                            v,
+                            v.desc()
+                        )
+                        .unwrap();
+                        let _ = (f.bits(), f.bitfield(), v.raw(), v.scalar());
+                    });
+
+                    let r = rand();
+
+                    let _ = d.mutate(
+                        code,
+                        &mut payload[..len],
+                        mode,
+                        |_, _| match r % 4 {
+                            0 => None,
+                            1 => Some(Replacement::Integer(r as u32)),
+                            2 => Some(Replacement::Boolean(r & 8 != 0)),
+                            _ => Some(Replacement::Float(f32::from_bits(
+                                (r >> 32) as u32,
+                            ))),
+                        },
+                    );
+                }
+            }
+
+            let _ = d.fields(code, |f| {
+                let _ = (f.name(), f.desc(), f.bits(), f.bitfield());
+            });
+
+            for pos in 0..32 {
+                let _ = d.sentinels(code, Bitpos(pos), |v| {
+                    let _ = (v.name(), v.desc(), v.raw(), v.scalar());
+                });
+            }
+        }
+    });
+}
+
+#[test]
+fn from_slice_length() {
+    use commands::{STATUS_WORD, VOUT_COMMAND};
+
+    //
+    // A payload that is too long is as wrong as one that is too short...
+    //
+    assert!(STATUS_WORD::CommandData::from_slice(&[0x00]).is_none());
+    assert!(STATUS_WORD::CommandData::from_slice(&[0x00, 0x00]).is_some());
+    assert!(STATUS_WORD::CommandData::from_slice(&[0x00; 3]).is_none());
+    assert!(VOUT_COMMAND::CommandData::from_slice(&[0x00; 3]).is_none());
+
+    let code = CommandCode::STATUS_WORD as u8;
+    let interpret =
+        |payload| Device::Common.interpret(code, payload, mode, |_, _| {});
+
+    assert_eq!(interpret(&[0x00]), Err(Error::ShortData));
+    assert_eq!(interpret(&[0x00, 0x00, 0x00]), Err(Error::InvalidLength));
+
+    //
+    // ...save for a block, whose structure need only describe a prefix.
+    //
+    let data =
+        commands::bmr480::MFR_ISHARE_THRESHOLD::CommandData::from_slice(&[
+            0x10, 0x10, 0x00, 0x64, 0x00, 0x00, 0x00, 0x01, 0xff,
+        ]);
+    assert!(data.is_some());
+}
+
+#[test]
+fn device_extended_none() {
+    //
+    // Devices that lack extended commands shouldn't know about any of them.
+    //
+    for d in [Device::Common, Device::Tps546B24A] {
+        assert_eq!(
+            d.interpret_extended(0x01, &[0x00], mode, |_, _| {}),
+            Err(Error::InvalidCode)
+        );
+
+        d.command_extended(0x01, |_| panic!("unexpected command"));
+    }
+}
+
+#[test]
+fn pin_accuracy() {
+    use commands::*;
+
+    let accuracy = MFR_PIN_ACCURACY::CommandData::from_slice(&[25]).unwrap();
+    assert_eq!(accuracy.get(), Ok(units::Percent(2.5)));
+
+    let pin = Reading::new(units::Watts(400.0), Some(accuracy.get().unwrap()));
+    assert_eq!(
+        pin.bounds(),
+        Some((units::Watts(390.0), units::Watts(410.0)))
+    );
+
+    let unknown = Reading::new(units::Watts(400.0), None);
+    assert_eq!(unknown.bounds(), None);
+}
+
+#[test]
+fn reading_combine() {
+    let vin = Reading::new(units::Volts(12.0), Some(units::Percent(1.0)));
+    let iin = Reading::new(units::Amperes(-2.0), Some(units::Percent(1.5)));
+
+    let pin = vin.combine(&iin, units::Watts(-24.0));
+    assert_eq!(pin.tolerance, Some(units::Percent(2.5)));
+    assert_eq!(
+        pin.bounds(),
+        Some((units::Watts(-24.6), units::Watts(-23.4)))
+    );
+
+    let iin = Reading::new(units::Amperes(2.0), None);
+    assert_eq!(vin.combine(&iin, units::Watts(24.0)).tolerance, None);
+}
+
+#[test]
+fn device_interpreter() {
+    let payload = [0x97, 0x02, 0x5a, 0xa5];
+    let mode = || VOutModeCommandData::from_slice(&[0x17]).unwrap();
+
+    devices(|d| {
+        for code in 0..=0xffu8 {
+            let mut valid = false;
+            d.command(code, |_| valid = true);
+
+            let interpret = d.interpreter(code);
+            assert_eq!(interpret.is_some(), valid, "{:?} 0x{:02x}", d, code);
+
+            if let Some(interpret) = interpret {
+                let mut lhs = vec![];
+                let mut rhs = vec![];
+
+                let l = interpret(&payload, &mode, &mut |f, v| {
+                    lhs.push((f.name(), v.raw()));
+                });
+
+                let r = d.interpret(code, &payload, mode, |f, v| {
+                    rhs.push((f.name(), v.raw()));
+                });
+
+                assert_eq!(l, r);
+                assert_eq!(lhs, rhs);
+            }
+        }
+    });
+}
+
+#[test]
+fn device_interpret_all() {
+    use std::cell::Cell;
+
+    let vout_mode = [0x17];
+    let vout_command = [0x66, 0x02];
+    let operation = [0x84];
+
+    let batch = [
+        (CommandCode::OPERATION as u8, &operation[..]),
+        (CommandCode::VOUT_COMMAND as u8, &vout_command[..]),
+        (CommandCode::VOUT_MODE as u8, &vout_mode[..]),
+    ];
+
+    //
+    // With VOUT_MODE in the batch, we should never need to call our mode
+    // function.
+    //
+    let mut seen = vec![];
+
+    Device::Tps546B24A
+        .interpret_all(&batch, mode, |code, f, v| {
+            seen.push((code, f.name(), std::format!("{}", v)));
+        })
+        .unwrap();
+
+    assert!(seen.contains(&(0x21, "scalar", "1.20V".to_string())));
+    assert!(seen.iter().any(|(code, _, _)| *code == 0x01));
+
+    //
+    // Without it, we should call it exactly once.
+    //
+    let calls = Cell::new(0);
+
+    let mode = || {
+        calls.set(calls.get() + 1);
+        VOutModeCommandData::from_slice(&vout_mode).unwrap()
+    };
+
+    let batch = [
+        (CommandCode::VOUT_COMMAND as u8, &vout_command[..]),
+        (CommandCode::VOUT_MAX as u8, &vout_command[..]),
+        (CommandCode::OPERATION as u8, &operation[..]),
+    ];
+
+    Device::Tps546B24A
+        .interpret_all(&batch, mode, |_, _, _| {})
+        .unwrap();
+
+    assert_eq!(calls.get(), 1);
+
+    let batch = [(CommandCode::VOUT_COMMAND as u8, &vout_command[..1])];
+
+    assert_eq!(
+        Device::Common.interpret_all(&batch, mode, |_, _, _| {}),
+        Err(Error::ShortData)
+    );
+}
+
+#[test]
+fn shared_modules() {
+    //
+    // A common command that a device doesn't override should be the very
+    // same type as the common definition.
+    //
+    let operation: commands::OPERATION::CommandData =
+        commands::tps546b24a::OPERATION::CommandData::from_slice(&[0x84])
+            .unwrap();
+
+    assert_eq!(
+        operation.get_on_off_state(),
+        Some(commands::OPERATION::OnOffState::On)
+    );
+
+    let vout: commands::VOUT_COMMAND::CommandData =
+        commands::tps546b24a::VOUT_COMMAND::CommandData(0x0266);
+
+    let mode = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    assert_eq!(vout.get(mode), Ok(units::Volts(1.1992188)));
+}
+
+fn telemetry<D: PmbusDevice>() -> Vec<&'static str> {
+    D::TELEMETRY.iter().map(|cmd| cmd.name()).collect()
+}
+
+#[test]
+fn pmbus_device() {
+    use commands::adm1272::Adm1272;
+    use commands::tps546b24a::Tps546B24A;
+
+    assert_eq!(Adm1272::DEVICE, Device::Adm1272);
+    assert_eq!(Adm1272::NAME, Device::Adm1272.name());
+    assert_eq!(Adm1272::DESC, Device::Adm1272.desc());
+    assert_eq!(Device::from_str(Tps546B24A::NAME), Some(Tps546B24A::DEVICE));
+
+    assert_eq!(
+        telemetry::<Tps546B24A>(),
+        ["READ_VIN", "READ_VOUT", "READ_IOUT", "READ_TEMPERATURE_1"]
+    );
+
+    assert_eq!(Tps546B24A::command(0x88), Some(Tps546B24A::TELEMETRY[0]));
+    assert_eq!(Tps546B24A::command(0x8d), Some(Tps546B24A::TELEMETRY[3]));
+    assert!(telemetry::<Adm1272>().contains(&"READ_VIN"));
+
+    let mut vin = None;
+
+    Tps546B24A::interpret(
+        Tps546B24A::TELEMETRY[0],
+        &[0x30, 0xd3],
+        mode,
+        |_, v| {
+            vin = Some(v.to_string());
+        },
+    )
+    .unwrap();
+
+    assert_eq!(vin, Some("12.75V".to_string()));
+}
+
+#[test]
+fn value_format() {
+    use commands::tps546b24a::Tps546B24A;
+
+    let mode = || commands::VOUT_MODE::CommandData(0);
+    let mut values = vec![];
+
+    Tps546B24A::interpret(
+        Tps546B24A::TELEMETRY[0],
+        &[0x30, 0xd3],
+        mode,
+        |_, v| {
+            let opts = ValueFormat {
+                precision: Some(3),
+                units: false,
+                ..Default::default()
+            };
+
+            values.push(format!("{}", v));
+            values.push(format!("{:.1}", v));
+            values.push(format!("{}", v.format(ValueFormat::default())));
+            values.push(format!("{}", v.format(opts)));
+            assert_eq!(v.units(), "V");
+        },
+    )
+    .unwrap();
+
+    assert_eq!(values, ["12.75V", "12.8V", "12.75V", "12.750"]);
+
+    let hex = ValueFormat {
+        radix: Some(Radix::Hex),
+        ..Default::default()
+    };
+
+    let decimal = ValueFormat {
+        radix: Some(Radix::Decimal),
+        ..Default::default()
+    };
+
+    let mut values = vec![];
+
+    Device::Common
+        .interpret(
+            CommandCode::READ_EIN as u8,
+            &[0x34, 0x12, 0x02, 0x10, 0x00, 0x00],
+            mode,
+            |f, v| {
+                values.push((
+                    f.name(),
+                    v.format(ValueFormat::default()).to_string(),
+                    v.format(decimal).to_string(),
+                ))
+            },
+        )
+        .unwrap();
+
+    assert_eq!(values[0], ("SampleCount", "0x10".into(), "16".into()));
+
+    let mut values = vec![];
+
+    Device::Adm1272
+        .interpret(CommandCode::OPERATION as u8, &[0x84], mode, |_, v| {
+            values.push((
+                v.to_string(),
+                v.format(hex).to_string(),
+                v.format(decimal).to_string(),
+                v.units(),
+            ))
+        })
+        .unwrap();
+
+    let (display, hex, decimal, units) = &values[0];
+    assert!(display.starts_with("0b1 = "));
+    assert_eq!(hex, &display.replace("0b1", "0x1"));
+    assert_eq!(decimal, &display.replace("0b1", "1"));
+    assert_eq!(*units, "");
+}
+
+#[test]
+fn poller() {
+    use commands::tps546b24a::{CommandCode, Tps546B24A};
+    use poll::{Poll, Poller};
+
+    let mut poller = Poller::<Tps546B24A>::new();
+    assert_eq!(poller.pass_len(), 4);
+
+    let pass: Vec<_> = poller.by_ref().collect();
+    assert_eq!(pass.len(), 4);
+    assert!(pass.iter().all(|p| p.page.is_none()));
+    assert_eq!(poller.next(), None);
+
+    poller.restart();
+    assert_eq!(
+        poller.next(),
+        Some(Poll {
+            page: None,
+            command: CommandCode::READ_VIN
+        })
+    );
+
+    let paged: Vec<_> = Poller::<Tps546B24A>::paged(2).collect();
+    assert_eq!(paged.len(), 8);
+    assert_eq!(paged[3].page, Some(0));
+    assert_eq!(paged[4].page, Some(1));
+    assert_eq!(paged[4].command, CommandCode::READ_VIN);
+
+    assert_eq!(Poller::<Tps546B24A>::paged(0).next(), None);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poller_readings() {
+    use commands::tps546b24a::{CommandCode, Tps546B24A};
+    use embedded_hal_async::delay::DelayNs;
+    use futures_util::StreamExt;
+    use poll::{readings, PollError, Poller, Reading, Transport};
+    use std::future::Future;
+    use std::task::{Context, Poll, Waker};
+    use units::Units;
+
+    fn block_on<F: Future>(f: F) -> F::Output {
+        let mut f = std::pin::pin!(f);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        loop {
+            if let Poll::Ready(rval) = f.as_mut().poll(&mut cx) {
+                return rval;
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct Device {
+        reads: Vec<u8>,
+        writes: Vec<(u8, Vec<u8>)>,
+    }
+
+    impl Transport for Device {
+        type Error = u8;
+
+        async fn read(
+            &mut self,
+            code: u8,
+            _op: Operation,
+            buf: &mut [u8],
+        ) -> Result<usize, u8> {
+            self.reads.push(code);
+
+            let payload: &[u8] = match CommandCode::from_u8(code) {
+                Some(CommandCode::VOUT_MODE) => &[0x17],
+                Some(CommandCode::READ_VIN) => &[0x60, 0xe8],
+                Some(CommandCode::READ_VOUT) => &[0x00, 0x02],
+                Some(CommandCode::READ_IOUT) => &[0x02, 0x00],
+                _ => return Err(code),
+            };
+
+            buf[..payload.len()].copy_from_slice(payload);
+            Ok(payload.len())
+        }
+
+        async fn write(
+            &mut self,
+            code: u8,
+            _op: Operation,
+            payload: &[u8],
+        ) -> Result<(), u8> {
+            self.writes.push((code, payload.to_vec()));
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Delay(Vec<u32>);
+
+    impl DelayNs for Delay {
+        async fn delay_ns(&mut self, ns: u32) {
+            self.0.push(ns);
+        }
+    }
+
+    let (mut device, mut delay) = (Device::default(), Delay::default());
+    let poller = Poller::<Tps546B24A>::new();
+
+    let pass: Vec<_> = block_on(
+        readings(poller, &mut device, &mut delay, 100)
+            .take(5)
+            .collect(),
+    );
+
+    let reading = |command, value, units| {
+        Ok(Reading {
+            page: None,
+            command,
+            value: Some(value),
+            units: Some(units),
+        })
+    };
+
+    assert_eq!(pass[0], reading(CommandCode::READ_VIN, 12.0, Units::Volts));
+    assert_eq!(pass[1], reading(CommandCode::READ_VOUT, 1.0, Units::Volts));
+    assert_eq!(
+        pass[2],
+        reading(CommandCode::READ_IOUT, 2.0, Units::Amperes)
+    );
+    assert_eq!(pass[3], Err(PollError::Transport(0x8d)));
+    assert_eq!(pass[4], reading(CommandCode::READ_VIN, 12.0, Units::Volts));
+
+    assert_eq!(delay.0, [100_000_000]);
+    assert_eq!(device.reads, [0x88, 0x20, 0x8b, 0x8c, 0x8d, 0x88]);
+    assert!(device.writes.is_empty());
+
+    //
+    // A paged device has PAGE written before each page is read, and has
+    // VOUT_MODE read for each page.
+    //
+    let (mut device, mut delay) = (Device::default(), Delay::default());
+    let poller = Poller::<Tps546B24A>::paged(2);
+
+    let pass: Vec<_> = block_on(
+        readings(poller, &mut device, &mut delay, 100)
+            .take(8)
+            .collect(),
+    );
+
+    assert_eq!(pass[4].map(|r| r.page), Ok(Some(1)));
+    assert_eq!(device.writes, [(0x00, vec![0]), (0x00, vec![1])]);
+    assert_eq!(device.reads.iter().filter(|&&c| c == 0x20).count(), 2);
+    assert!(delay.0.is_empty());
+}
+
+#[test]
+fn encode_write() {
+    let mut buf = [0u8; 8];
+
+    let len = encode::write_command(
+        &mut buf,
+        0x40,
+        CommandCode::OPERATION,
+        &[0x80],
+        false,
+    )
+    .unwrap();
+    assert_eq!(&buf[..len], &[0x01, 0x80]);
+
+    let len = encode::write_command(
+        &mut buf,
+        0x40,
+        CommandCode::CLEAR_FAULTS,
+        &[],
+        true,
+    )
+    .unwrap();
+    assert_eq!(len, 2);
+    assert_eq!(buf[0], 0x03);
+
+    //
+    // The PEC covers the address byte as well as the transaction; verify
+    // it against a bitwise CRC-8 computed here.
+    //
+    let crc8 = |bytes: &[u8]| {
+        let mut crc = 0u8;
+
+        for &byte in bytes {
+            crc ^= byte;
+
+            for _ in 0..8 {
+                crc = if crc & 0x80 != 0 {
+                    (crc << 1) ^ 0x07
+                } else {
+                    crc << 1
+                };
+            }
+        }
+
+        crc
+    };
+
+    assert_eq!(crc8(b"123456789"), 0xf4);
+    assert_eq!(buf[1], crc8(&[0x80, 0x03]));
+
+    let len = encode::write(
+        &mut buf,
+        0x5a,
+        0xb0,
+        Operation::WriteBlock,
+        &[1, 2, 3],
+        true,
+    )
+    .unwrap();
+    assert_eq!(&buf[..len - 1], &[0xb0, 3, 1, 2, 3]);
+    assert_eq!(buf[len - 1], crc8(&[0xb4, 0xb0, 3, 1, 2, 3]));
+
+    let len = encode::write_command(
+        &mut buf,
+        0x40,
+        CommandCode::VOUT_COMMAND,
+        &[0x66, 0x02],
+        false,
+    )
+    .unwrap();
+    assert_eq!(&buf[..len], &[0x21, 0x66, 0x02]);
+
+    assert_eq!(
+        encode::write_command(
+            &mut buf,
+            0x40,
+            CommandCode::VOUT_COMMAND,
+            &[0x66],
+            false
+        ),
+        Err(Error::InvalidLength)
+    );
+
+    assert_eq!(
+        encode::write_command(
+            &mut buf,
+            0x40,
+            CommandCode::READ_VIN,
+            &[0, 0],
+            false
+        ),
+        Err(Error::InvalidOperation)
+    );
+
+    assert_eq!(
+        encode::write(&mut buf, 0x40, 0xb0, Operation::WriteBlock, &[], false),
+        Err(Error::InvalidLength)
+    );
+
+    assert_eq!(
+        encode::write(
+            &mut buf[..4],
+            0x40,
+            0xb0,
+            Operation::WriteBlock,
+            &[1, 2, 3],
+            false
+        ),
+        Err(Error::BufferTooSmall)
+    );
+}
+
+#[test]
+fn encode_group() {
+    use decoder::*;
+    use encode::GroupCommand;
+
+    let mut buf = [0u8; 16];
+
+    let commands = [
+        GroupCommand::new(0x40, CommandCode::OPERATION, &[0x80]),
+        GroupCommand::new(0x41, CommandCode::VOUT_COMMAND, &[0x66, 0x02]),
+    ];
+
+    let len = encode::group(&mut buf, &commands, false).unwrap();
+    assert_eq!(&buf[..len], [0x80, 0x01, 0x80, 0x82, 0x21, 0x66, 0x02]);
+
+    //
+    // With PEC, each command carries its own.
+    //
+    let len = encode::group(&mut buf, &commands, true).unwrap();
+    assert_eq!(
+        &buf[..len],
+        [0x80, 0x01, 0x80, 0x97, 0x82, 0x21, 0x66, 0x02, 0xb0]
+    );
+
+    //
+    // An analyzer sees each command of the group as its own transaction.
+    //
+    let mut analyzer = Analyzer::new(Device::Common);
+    let mut events = vec![];
+
+    for (i, &byte) in buf[..len].iter().enumerate() {
+        if i == 0 || i == 4 {
+            events.push(Event::Start);
+            events.push(Event::Address {
+                address: byte >> 1,
+                read: false,
+            });
+        } else {
+            events.push(Event::Byte(byte));
+        }
+
+        events.push(Event::Ack);
+    }
+
+    events.push(Event::Stop);
+
+    let mut seen = vec![];
+
+    for event in events {
+        analyzer.feed(event, |t| {
+            let t = t.unwrap();
+            seen.push((t.address, t.code, t.payload.to_vec(), t.pec));
+        });
+    }
+
+    assert_eq!(
+        seen,
+        [
+            (0x40, 0x01, vec![0x80], protocol::PecStatus::Valid),
+            (0x41, 0x21, vec![0x66, 0x02], protocol::PecStatus::Valid),
+        ]
+    );
+
+    assert_eq!(
+        encode::group(&mut buf, &[], false),
+        Err(Error::InvalidLength)
+    );
+
+    assert_eq!(
+        encode::group(
+            &mut buf,
+            &[
+                GroupCommand::new(0x40, CommandCode::OPERATION, &[0x80]),
+                GroupCommand::new(0x40, CommandCode::ON_OFF_CONFIG, &[0x1f]),
+            ],
+            false
+        ),
+        Err(Error::InvalidOperation)
+    );
+
+    assert_eq!(
+        encode::group(
+            &mut buf,
+            &[
+                GroupCommand::new(0x40, CommandCode::OPERATION, &[0x80]),
+                GroupCommand::new(0x41, CommandCode::READ_VIN, &[0, 0]),
+            ],
+            false
+        ),
+        Err(Error::InvalidOperation)
+    );
+
+    assert_eq!(
+        encode::group(&mut buf[..5], &commands, false),
+        Err(Error::BufferTooSmall)
+    );
+}
+
+#[test]
+fn pec() {
+    let mut pec = pec::Pec::new();
+    pec.update(b"1234");
+    pec.update(b"56789");
+    assert_eq!(pec.value(), 0xf4);
+
+    assert_eq!(
+        pec::compute_command(
+            0x40,
+            CommandCode::READ_VOUT,
+            false,
+            &[0x66, 0x02]
+        ),
+        Ok(0xc9)
+    );
+    assert_eq!(
+        pec::compute_command(
+            0x40,
+            CommandCode::VOUT_COMMAND,
+            true,
+            &[0x66, 0x02]
+        ),
+        Ok(0x9c)
+    );
+
+    //
+    // The PEC computed for a write must match that encoded with it.
+    //
+    let mut buf = [0u8; 8];
+    let len = encode::write_command(
+        &mut buf,
+        0x40,
+        CommandCode::VOUT_COMMAND,
+        &[0x66, 0x02],
+        true,
+    )
+    .unwrap();
+    assert_eq!(buf[len - 1], 0x9c);
+
+    assert_eq!(
+        pec::verify_command(0x5a, CommandCode::MFR_ID, false, b"ABC", 0xdd),
+        Ok(())
+    );
+    assert_eq!(
+        pec::verify_command(0x5a, CommandCode::MFR_ID, false, b"ABD", 0xdd),
+        Err(Error::BadPec)
+    );
+
+    assert_eq!(
+        pec::compute(
+            0x5a,
+            0xd0,
+            Operation::ProcessCall,
+            &[0x12, 0x34, 0x56, 0x78]
+        ),
+        Ok(0xb9)
+    );
+
+    assert_eq!(
+        pec::compute(0x40, 0x8b, Operation::ReadWord, &[0x66]),
+        Err(Error::InvalidLength)
+    );
+    assert_eq!(
+        pec::compute(0x40, 0x8b, Operation::ReadBlock, &[0; 256]),
+        Err(Error::InvalidLength)
+    );
+    assert_eq!(
+        pec::compute(0x40, 0xfe, Operation::Extended, &[]),
+        Err(Error::InvalidOperation)
+    );
+}
+
+#[test]
+fn protocol() {
+    use protocol::*;
+
+    let mut buf = [0u8; 8];
+
+    let layout = encode_command(
+        &mut buf,
+        0x40,
+        CommandCode::READ_VOUT,
+        false,
+        &[],
+        true,
+    )
+    .unwrap();
+    assert_eq!(layout.write, 1);
+    assert_eq!(layout.read, Some(Read::Fixed(3)));
+    assert_eq!(buf[0], 0x8b);
+
+    let layout = encode_command(
+        &mut buf,
+        0x40,
+        CommandCode::OPERATION,
+        true,
+        &[0x80],
+        true,
+    )
+    .unwrap();
+    assert_eq!(layout.read, None);
+    assert_eq!(&buf[..layout.write], [0x01, 0x80, 0x97]);
+
+    let layout =
+        encode_command(&mut buf, 0x5a, CommandCode::MFR_ID, false, &[], false)
+            .unwrap();
+    assert_eq!(layout.read, Some(Read::Block));
+
+    let layout = encode(
+        &mut buf,
+        0x5a,
+        0xd0,
+        Operation::ProcessCall,
+        &[0x12, 0x34],
+        false,
+    )
+    .unwrap();
+    assert_eq!(&buf[..layout.write], [0xd0, 0x12, 0x34]);
+    assert_eq!(layout.read, Some(Read::Fixed(2)));
+
+    assert_eq!(
+        encode(&mut buf, 0x40, 0x8b, Operation::ReadWord, &[0], false),
+        Err(Error::InvalidLength)
+    );
+    assert_eq!(
+        encode(&mut buf, 0x40, 0x8b, Operation::Illegal, &[], false),
+        Err(Error::InvalidOperation)
+    );
+
+    //
+    // Decoding recovers the code and payload, and checks the PEC.
+    //
+    let d = decode(
+        &mut buf,
+        0x40,
+        Operation::ReadWord,
+        &[0x8b],
+        &[0x66, 0x02, 0xc9],
+        true,
+    )
+    .unwrap();
+    assert_eq!(d.code, 0x8b);
+    assert_eq!(d.pec, PecStatus::Valid);
+    assert_eq!(&buf[..d.len], [0x66, 0x02]);
+
+    let d = decode(
+        &mut buf,
+        0x40,
+        Operation::ReadWord,
+        &[0x8b],
+        &[0x66, 0x03, 0xc9],
+        true,
+    )
+    .unwrap();
+    assert_eq!(d.pec, PecStatus::Invalid);
+
+    let d = decode(
+        &mut buf,
+        0x5a,
+        Operation::ReadBlock,
+        &[0x99],
+        &[3, b'A', b'B', b'C', 0xdd],
+        true,
+    )
+    .unwrap();
+    assert_eq!(d.pec, PecStatus::Valid);
+    assert_eq!(&buf[..d.len], b"ABC");
+
+    let d = decode(
+        &mut buf,
+        0x40,
+        Operation::WriteBlock,
+        &[0xb0, 2, 0xaa, 0xbb],
+        &[],
+        false,
+    )
+    .unwrap();
+    assert_eq!(d.pec, PecStatus::Absent);
+    assert_eq!(&buf[..d.len], [0xaa, 0xbb]);
+
+    let d = decode(
+        &mut buf,
+        0x5a,
+        Operation::ProcessCall,
+        &[0xd0, 0x12, 0x34],
+        &[0x56, 0x78],
+        false,
+    )
+    .unwrap();
+    assert_eq!(&buf[..d.len], [0x12, 0x34, 0x56, 0x78]);
+
+    assert_eq!(
+        decode(
+            &mut buf,
+            0x40,
+            Operation::WriteBlock,
+            &[0xb0, 3, 1],
+            &[],
+            false
+        ),
+        Err(Error::InvalidLength)
+    );
+    assert_eq!(
+        decode(&mut buf, 0x40, Operation::ReadWord, &[], &[0, 0], false),
+        Err(Error::ShortData)
+    );
+
+    //
+    // Whatever is encoded must decode to the same code and payload.
+    //
+    let mut wire = [0u8; 8];
+    let layout = encode(
+        &mut wire,
+        0x22,
+        0xb0,
+        Operation::WriteBlock,
+        &[1, 2, 3],
+        true,
+    )
+    .unwrap();
+    let d = decode(
+        &mut buf,
+        0x22,
+        Operation::WriteBlock,
+        &wire[..layout.write],
+        &[],
+        true,
+    )
+    .unwrap();
+    assert_eq!((d.code, d.pec), (0xb0, PecStatus::Valid));
+    assert_eq!(&buf[..d.len], [1, 2, 3]);
+}
+
+#[test]
+fn decoder() {
+    use decoder::*;
+    use protocol::PecStatus;
+
+    let mut analyzer = Analyzer::new(Device::Xdpe12284);
+
+    let events = |written: &[u8], read: &[u8]| {
+        let mut events = vec![
+            Event::Start,
+            Event::Address {
+                address: 0x40,
+                read: false,
+            },
+            Event::Ack,
+        ];
+
+        for &byte in written {
+            events.push(Event::Byte(byte));
+            events.push(Event::Ack);
+        }
+
+        if !read.is_empty() {
+            events.push(Event::Start);
+            events.push(Event::Address {
+                address: 0x40,
+                read: true,
+            });
+            events.push(Event::Ack);
+
+            for (i, &byte) in read.iter().enumerate() {
+                events.push(Event::Byte(byte));
+                events.push(if i == read.len() - 1 {
+                    Event::Nack
+                } else {
+                    Event::Ack
+                });
+            }
+        }
+
+        events.push(Event::Stop);
+        events
+    };
+
+    let feed = |analyzer: &mut Analyzer, events: Vec<Event>| {
+        let mut rval = vec![];
+
+        for event in events {
+            analyzer.feed(event, |t| {
+                rval.push(t.map(|t| {
+                    let mut value = None;
+                    let interpreted = t.interpret(|_, v| {
+                        value = v.as_float();
+                    });
+
+                    (
+                        t.code,
+                        t.op,
+                        t.heuristic,
+                        t.pec,
+                        t.page,
+                        interpreted,
+                        value,
+                    )
+                }))
+            });
+        }
+
+        rval
+    };
+
+    //
+    // Set the page, and then read VOUT_MODE on it.
+    //
+    let t = feed(&mut analyzer, events(&[0x00, 0x01], &[]));
+    assert_eq!(t.len(), 1);
+    assert_eq!(analyzer.page(0x40), Some(1));
+
+    let t = feed(&mut analyzer, events(&[0x20], &[0x17]));
+    assert_eq!(t[0].as_ref().unwrap().1, Operation::ReadByte);
+    assert_eq!(
+        analyzer.vout_mode(0x40),
+        VOutModeCommandData::from_slice(&[0x17])
+    );
+
+    //
+    // A read of READ_VOUT (with PEC) can now be interpreted.
+    //
+    let t = feed(&mut analyzer, events(&[0x8b], &[0x00, 0x02, 0x42]));
+    assert_eq!(
+        t,
+        [Ok((
+            0x8b,
+            Operation::ReadWord,
+            false,
+            PecStatus::Valid,
+            Some(1),
+            Ok(()),
+            Some(1.0)
+        ))]
+    );
+
+    //
+    // But VOUT_MODE is not known for page 0.
+    //
+    feed(&mut analyzer, events(&[0x00, 0x00], &[]));
+    assert_eq!(analyzer.vout_mode(0x40), None);
+
+    let t = feed(&mut analyzer, events(&[0x8b], &[0x00, 0x02]));
+    assert_eq!(t[0].as_ref().unwrap().3, PecStatus::Absent);
+    assert_eq!(t[0].as_ref().unwrap().5, Err(Error::InvalidMode));
+
+    //
+    // A transaction that doesn't match the command is decoded
+    // heuristically.
+    //
+    let t = feed(&mut analyzer, events(&[0x8b], &[0x02]));
+    assert_eq!(t[0].as_ref().unwrap().1, Operation::ReadByte);
+    assert!(t[0].as_ref().unwrap().2);
+
+    //
+    // A transaction to an address that doesn't acknowledge is discarded,
+    // as is a read without a command code.
+    //
+    let t = feed(
+        &mut analyzer,
+        vec![
+            Event::Start,
+            Event::Address {
+                address: 0x41,
+                read: false,
+            },
+            Event::Nack,
+            Event::Stop,
+            Event::Start,
+            Event::Address {
+                address: 0x40,
+                read: true,
+            },
+            Event::Ack,
+            Event::Byte(0x17),
+            Event::Nack,
+            Event::Stop,
+        ],
+    );
+    assert!(t.is_empty());
+    assert_eq!(analyzer.page(0x40), Some(0));
+}
+
+#[test]
+fn delay_after() {
+    //
+    // No delays are specified in common PMBus; they are device-specific.
+    //
+    for code in 0..=255u8 {
+        if let Some(cmd) = CommandCode::from_u8(code) {
+            assert_eq!(cmd.delay_after(), None);
+        }
+    }
+
+    let cmd = commands::tps546b24a::CommandCode::STORE_USER_ALL;
+    assert_eq!(cmd.delay_after(), None);
+}
+
+#[test]
+fn store_poll() {
+    use core::cell::Cell;
+    use core::time::Duration;
+    use store::{store, StoreError};
+
+    let interval = Duration::from_millis(1);
+    let busy = commands::STATUS_BYTE::CommandData(0x80);
+    let idle = commands::STATUS_BYTE::CommandData(0x00);
+    let cml = commands::STATUS_BYTE::CommandData(0x02);
+    let memory = commands::STATUS_CML::CommandData(0x10);
+
+    //
+    // A device that is busy for two reads and then completes.
+    //
+    let reads = Cell::new(0);
+    let sent = Cell::new(None);
+    let mut waited = Duration::ZERO;
+
+    let rval = store(
+        CommandCode::STORE_USER_ALL,
+        5,
+        interval,
+        |cmd| {
+            sent.set(Some(cmd));
+            Ok::<(), ()>(())
+        },
+        || {
+            reads.set(reads.get() + 1);
+            Ok(if reads.get() <= 2 { busy } else { idle })
+        },
+        || panic!("unexpected read of STATUS_CML"),
+        |d| waited += d,
+    );
+
+    assert_eq!(rval, Ok(()));
+    assert_eq!(sent.get(), Some(CommandCode::STORE_USER_ALL));
+    assert_eq!(reads.get(), 3);
+    assert_eq!(waited, interval * 2);
+
+    let rval = store(
+        CommandCode::RESTORE_USER_ALL,
+        3,
+        interval,
+        |_| Ok::<(), ()>(()),
+        || Ok(busy),
+        || panic!("unexpected read of STATUS_CML"),
+        |_| {},
+    );
+
+    assert_eq!(rval, Err(StoreError::Timeout));
+
+    let rval = store(
+        CommandCode::STORE_USER_ALL,
+        3,
+        interval,
+        |_| Ok::<(), ()>(()),
+        || Ok(cml),
+        || Ok(memory),
+        |_| {},
+    );
+
+    assert_eq!(rval, Err(StoreError::Fault(memory)));
+
+    if let Err(StoreError::Fault(cml)) = rval {
+        assert_eq!(
+            cml.get_memory_fault(),
+            Some(commands::STATUS_CML::MemoryFault::Fault)
+        );
+    }
+
+    let rval = store(
+        CommandCode::STORE_USER_ALL,
+        3,
+        interval,
+        |_| Err("nak"),
+        || Ok(idle),
+        || Ok(memory),
+        |_| {},
+    );
+
+    assert_eq!(rval, Err(StoreError::Transport("nak")));
+
+    let rval = store(
+        CommandCode::OPERATION,
+        3,
+        interval,
+        |_| Ok::<(), ()>(()),
+        || Ok(idle),
+        || Ok(memory),
+        |_| {},
+    );
+
+    assert_eq!(rval, Err(StoreError::InvalidCommand));
+}
+
+#[test]
+fn clear_faults() {
+    use commands::STATUS_WORD;
+    use sequence::{verify_cleared, ClearFaults, Step, StickyFault};
+
+    assert_eq!(
+        ClearFaults::new().collect::<Vec<_>>(),
+        [
+            Step::Send(CommandCode::CLEAR_FAULTS),
+            Step::Read(CommandCode::STATUS_WORD),
+        ]
+    );
+
+    let steps: Vec<_> = ClearFaults::paged(2).collect();
+
+    assert_eq!(
+        steps,
+        [
+            Step::Page(0),
+            Step::Send(CommandCode::CLEAR_FAULTS),
+            Step::Read(CommandCode::STATUS_WORD),
+            Step::Page(1),
+            Step::Send(CommandCode::CLEAR_FAULTS),
+            Step::Read(CommandCode::STATUS_WORD),
+        ]
+    );
+
+    assert_eq!(ClearFaults::paged(0).next(), None);
+
+    //
+    // POWER_GOOD# and OFF are status, not faults.
+    //
+    assert_eq!(verify_cleared(None, STATUS_WORD::CommandData(0)), Ok(()));
+    assert_eq!(
+        verify_cleared(None, STATUS_WORD::CommandData(0x0840)),
+        Ok(())
+    );
+
+    let status = STATUS_WORD::CommandData(0x8020);
+
+    assert_eq!(
+        verify_cleared(Some(1), status),
+        Err(StickyFault {
+            page: Some(1),
+            status
+        })
+    );
+
+    assert_eq!(
+        status.get_output_voltage_fault(),
+        Some(STATUS_WORD::OutputVoltageFault::Fault)
+    );
+}
+
+#[test]
+fn power_sequence() {
+    use commands::STATUS_WORD;
+    use core::time::Duration;
+    use sequence::{Direction, PowerSequence, Rail, Step};
+
+    let ms = Duration::from_millis;
+
+    let rails = [
+        Rail {
+            address: 0x40,
+            page: None,
+            delay: ms(5),
+        },
+        Rail {
+            address: 0x60,
+            page: Some(1),
+            delay: ms(2),
+        },
+    ];
+
+    let up: Vec<_> = PowerSequence::new(&rails, Direction::Up).collect();
+
+    assert_eq!(
+        up,
+        [
+            (0x40, Step::WriteByte(CommandCode::ON_OFF_CONFIG, 0x18)),
+            (0x40, Step::WriteByte(CommandCode::OPERATION, 0x80)),
+            (0x40, Step::Read(CommandCode::STATUS_WORD)),
+            (0x40, Step::Delay(ms(5))),
+            (0x60, Step::Page(1)),
+            (0x60, Step::WriteByte(CommandCode::ON_OFF_CONFIG, 0x18)),
+            (0x60, Step::WriteByte(CommandCode::OPERATION, 0x80)),
+            (0x60, Step::Read(CommandCode::STATUS_WORD)),
+            (0x60, Step::Delay(ms(2))),
+        ]
+    );
+
+    let down: Vec<_> = PowerSequence::new(&rails, Direction::Down).collect();
+
+    assert_eq!(
+        down,
+        [
+            (0x60, Step::Page(1)),
+            (0x60, Step::WriteByte(CommandCode::OPERATION, 0x00)),
+            (0x60, Step::Read(CommandCode::STATUS_WORD)),
+            (0x60, Step::Delay(ms(2))),
+            (0x40, Step::WriteByte(CommandCode::OPERATION, 0x00)),
+            (0x40, Step::Read(CommandCode::STATUS_WORD)),
+            (0x40, Step::Delay(ms(5))),
+        ]
+    );
+
+    assert_eq!(PowerSequence::new(&[], Direction::Down).next(), None);
+
+    let on = STATUS_WORD::CommandData(0x0000);
+    let off = STATUS_WORD::CommandData(0x0840);
+    let no_pgood = STATUS_WORD::CommandData(0x0800);
+
+    assert!(Direction::Up.verify(on));
+    assert!(!Direction::Up.verify(off));
+    assert!(!Direction::Up.verify(no_pgood));
+    assert!(Direction::Down.verify(off));
+    assert!(!Direction::Down.verify(on));
+}
+
+#[test]
+fn plausibility() {
+    use commands::OPERATION;
+    use plausibility::*;
+    use units::{Amperes, Celsius};
+
+    let mut stuck = StuckDetector::new(3);
+
+    assert_eq!(stuck.check(0xd330), Ok(()));
+    assert_eq!(stuck.check(0xd330), Ok(()));
+    assert_eq!(stuck.check(0xd330), Err(Implausible::Stuck(3)));
+    assert_eq!(stuck.check(0xd330), Err(Implausible::Stuck(4)));
+    assert_eq!(stuck.check(0xd331), Ok(()));
+    assert_eq!(stuck.check(0xd331), Ok(()));
+
+    stuck.reset();
+    assert_eq!(stuck.check(0xd331), Ok(()));
+
+    assert_eq!(check_temperature(Celsius(45.5)), Ok(()));
+    assert_eq!(check_temperature(Celsius(-40.0)), Ok(()));
+    assert_eq!(
+        check_temperature(Celsius(511.0)),
+        Err(Implausible::Temperature(Celsius(511.0)))
+    );
+    assert!(check_temperature(Celsius(f32::NAN)).is_err());
+
+    let on = OPERATION::CommandData(0x80);
+    let off = OPERATION::CommandData(0x00);
+    let threshold = Amperes(0.5);
+
+    assert_eq!(check_current(on, Amperes(20.0), threshold), Ok(()));
+    assert_eq!(check_current(off, Amperes(0.25), threshold), Ok(()));
+    assert_eq!(check_current(off, Amperes(-0.25), threshold), Ok(()));
+    assert_eq!(
+        check_current(off, Amperes(3.0), threshold),
+        Err(Implausible::CurrentWhileOff(Amperes(3.0)))
+    );
+}
+
+#[test]
+fn vout_ramp() {
+    use core::time::Duration;
+    use sequence::{Step, VoutRamp};
+    use units::{Volts, VoltsPerMillisecond};
+
+    let mode = VOutModeCommandData::from_slice(&[0x17]).unwrap();
+    let rate = VoltsPerMillisecond(1.0);
+
+    let encode = |v| {
+        let mut data = commands::VOUT_COMMAND::CommandData(0);
+        data.set(mode, Volts(v)).unwrap();
+        data.0
+    };
+
+    let ramp = |from, to, step| {
+        VoutRamp::new(mode, Volts(from), Volts(to), Volts(step), rate)
+            .unwrap()
+            .collect::<Vec<_>>()
+    };
+
+    let writes = |steps: &[Step]| {
+        steps
+            .iter()
+            .filter_map(|s| match s {
+                Step::WriteWord(CommandCode::VOUT_COMMAND, raw) => Some(*raw),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let delay = |steps: &[Step]| {
+        steps
+            .iter()
+            .filter_map(|s| match s {
+                Step::Delay(d) => Some(*d),
+                _ => None,
+            })
+            .sum::<Duration>()
+    };
+
+    let up = ramp(1.0, 1.2, 0.05);
+    assert_eq!(up.len(), 8);
+    assert_eq!(
+        writes(&up),
+        [encode(1.05), encode(1.1), encode(1.15), encode(1.2)]
+    );
+    assert!(matches!(up[1], Step::Delay(_)));
+
+    //
+    // The total delay reflects the voltages as encoded:  (614 - 512) / 512
+    // volts at 1 V/ms.
+    //
+    let expected = Duration::from_nanos(199_218);
+    assert!(delay(&up).as_nanos().abs_diff(expected.as_nanos()) < 10);
+
+    let down = ramp(1.2, 1.0, 0.05);
+    assert_eq!(
+        writes(&down),
+        [encode(1.15), encode(1.1), encode(1.05), encode(1.0)]
+    );
+
+    //
+    // Steps smaller than the resolution of VOUT_MODE are elided.
+    //
+    let fine = ramp(1.0, 1.004, 0.0005);
+    let w = writes(&fine);
+    assert!(w.len() < 8);
+    assert_eq!(w.last(), Some(&encode(1.004)));
+    assert!(w.windows(2).all(|w| w[0] != w[1]));
+
+    assert!(ramp(1.0, 1.0, 0.05).is_empty());
+
+    let bad = |step, rate| {
+        VoutRamp::new(
+            mode,
+            Volts(1.0),
+            Volts(1.2),
+            Volts(step),
+            VoltsPerMillisecond(rate),
+        )
+        .err()
+    };
+
+    assert_eq!(bad(0.0, 1.0), Some(Error::ValueOutOfRange));
+    assert_eq!(bad(0.05, 0.0), Some(Error::ValueOutOfRange));
+    assert_eq!(bad(f32::NAN, 1.0), Some(Error::ValueOutOfRange));
+}
+
+#[test]
+fn brownout() {
+    use brownout::*;
+    use commands::bmr480::{
+        VIN_OFF, VIN_ON, VIN_UV_FAULT_LIMIT, VIN_UV_WARN_LIMIT,
+    };
+    use units::Volts;
+
+    let profile = BrownoutProfile {
+        vin_on: VIN_ON::CommandData(0xf088).get().ok(),
+        vin_off: VIN_OFF::CommandData(0xf080).get().ok(),
+        uv_warn: VIN_UV_WARN_LIMIT::CommandData(0xf08c).get().ok(),
+        uv_fault: VIN_UV_FAULT_LIMIT::CommandData(0xf084).get().ok(),
+    };
+
+    assert_eq!(profile.vin_on, Some(Volts(34.0)));
+    assert_eq!(profile.vin_off, Some(Volts(32.0)));
+    assert_eq!(profile.uv_warn, Some(Volts(35.0)));
+    assert_eq!(profile.uv_fault, Some(Volts(33.0)));
+
+    let mut b = Brownout::new(profile).unwrap();
+
+    assert_eq!(b.update(Volts(48.0)), VinState::Normal);
+    assert_eq!(b.update(Volts(34.5)), VinState::Warning);
+    assert_eq!(b.update(Volts(32.5)), VinState::Fault);
+    assert_eq!(b.update(Volts(31.0)), VinState::Off);
+
+    //
+    // Once off, the regulator remains off until VIN_ON is exceeded.
+    //
+    assert_eq!(b.update(Volts(33.5)), VinState::Off);
+    assert_eq!(b.update(Volts(34.5)), VinState::Warning);
+    assert_eq!(b.update(Volts(48.0)), VinState::Normal);
+
+    assert!(VinState::Off > VinState::Fault);
+    assert!(VinState::Warning > VinState::Normal);
+
+    //
+    // Thresholds that aren't present aren't considered.
+    //
+    let mut b = Brownout::new(BrownoutProfile {
+        uv_warn: Some(Volts(10.0)),
+        ..Default::default()
+    })
+    .unwrap();
+
+    assert_eq!(b.update(Volts(0.0)), VinState::Warning);
+    assert_eq!(b.update(Volts(12.0)), VinState::Normal);
+
+    let bad = BrownoutProfile {
+        vin_on: Some(Volts(30.0)),
+        vin_off: Some(Volts(32.0)),
+        ..profile
+    };
+
+    assert_eq!(bad.validate(), Err(Error::ValueOutOfRange));
+    assert!(Brownout::new(bad).is_err());
+
+    let bad = BrownoutProfile {
+        uv_fault: Some(Volts(36.0)),
+        ..profile
+    };
+
+    assert_eq!(bad.validate(), Err(Error::ValueOutOfRange));
+}
+
+#[test]
+fn hotswap_inrush() {
+    use commands::adm1272::Adm1272;
+    use core::time::Duration;
+    use hotswap::*;
+    use units::Amperes;
+
+    let mut inrush = Inrush {
+        current_limit: Amperes(40.0),
+        max_current: Amperes(10.0),
+        fault_timer: None,
+        retry: Retry::After(Duration::from_millis(500)),
+    };
+
+    let mut writes = vec![];
+
+    let configure = |inrush: &Inrush, writes: &mut Vec<(u8, Vec<u8>)>| {
+        Adm1272::configure_inrush(
+            inrush,
+            |code, buf| {
+                assert_eq!(code, 0xd8);
+                buf.copy_from_slice(&[0x37, 0x3f]);
+                Ok::<(), ()>(())
+            },
+            |code, payload| {
+                writes.push((code, payload.to_vec()));
+                Ok(())
+            },
+        )
+    };
+
+    configure(&inrush, &mut writes).unwrap();
+
+    //
+    // 10A of 40A is 4/16, encoded as 3; OCRetry (bit 1) is cleared for
+    // retry; 500ms is encoded as 4.
+    //
+    assert_eq!(
+        writes,
+        [
+            (0xf6, vec![0x03, 0x00]),
+            (0xd8, vec![0x35, 0x3f]),
+            (0xcc, vec![0x04]),
+        ]
+    );
+
+    writes.clear();
+    inrush.retry = Retry::Latch;
+    inrush.max_current = Amperes(100.0);
+    configure(&inrush, &mut writes).unwrap();
+
+    assert_eq!(writes, [(0xf6, vec![0x0f, 0x00]), (0xd8, vec![0x37, 0x3f])]);
+
+    writes.clear();
+
+    let invalid = |err| Err(HotSwapError::Invalid(err));
+
+    inrush.max_current = Amperes(1.0);
+    assert_eq!(
+        configure(&inrush, &mut writes),
+        invalid(Error::ValueOutOfRange)
+    );
+
+    inrush.max_current = Amperes(10.0);
+    inrush.retry = Retry::After(Duration::from_secs(30));
+    assert_eq!(
+        configure(&inrush, &mut writes),
+        invalid(Error::ValueOutOfRange)
+    );
+
+    inrush.retry = Retry::Latch;
+    inrush.fault_timer = Some(Duration::from_millis(10));
+    assert_eq!(configure(&inrush, &mut writes), invalid(Error::Unsupported));
+
+    assert!(writes.is_empty());
+
+    //
+    // A failure to read DEVICE_CONFIG must leave the device untouched.
+    //
+    inrush.fault_timer = None;
+
+    let rval = Adm1272::configure_inrush(
+        &inrush,
+        |_, _| Err(()),
+        |code, payload| {
+            writes.push((code, payload.to_vec()));
+            Ok(())
+        },
+    );
+
+    assert_eq!(rval, Err(HotSwapError::Transport(())));
+    assert!(writes.is_empty());
+}
+
+#[test]
+fn hotswap_inrush_adm1275() {
+    use commands::adm1275::Adm1275;
+    use core::time::Duration;
+    use hotswap::*;
+    use units::Amperes;
+
+    let mut inrush = Inrush {
+        current_limit: Amperes(40.0),
+        max_current: Amperes(40.0),
+        fault_timer: None,
+        retry: Retry::Latch,
+    };
+
+    let configure = |inrush: &Inrush| {
+        Adm1275::configure_inrush(
+            inrush,
+            |_, _| -> Result<(), ()> { panic!("unexpected read") },
+            |_, _| panic!("unexpected write"),
+        )
+    };
+
+    //
+    // Nothing on the ADM1275 is programmable:  we accept a configuration
+    // that it satisfies as it stands, and reject any other.
+    //
+    assert_eq!(configure(&inrush), Ok(()));
+
+    let unsupported = Err(HotSwapError::Invalid(Error::Unsupported));
+
+    inrush.max_current = Amperes(10.0);
+    assert_eq!(configure(&inrush), unsupported);
+
+    inrush.max_current = Amperes(40.0);
+    inrush.retry = Retry::After(Duration::from_millis(500));
+    assert_eq!(configure(&inrush), unsupported);
+}
+
+#[test]
+fn hotswap_inrush_lm25066() {
+    use commands::lm25066::Lm25066;
+    use commands::lm5066i::Lm5066I;
+    use core::time::Duration;
+    use hotswap::*;
+    use units::Amperes;
+
+    let mut inrush = Inrush {
+        current_limit: Amperes(40.0),
+        max_current: Amperes(30.0),
+        fault_timer: None,
+        retry: Retry::After(Duration::from_millis(500)),
+    };
+
+    let mut writes = vec![];
+
+    let read = |code, buf: &mut [u8]| {
+        assert_eq!(code, 0xd9);
+        buf.copy_from_slice(&[0x00]);
+        Ok::<(), ()>(())
+    };
+
+    //
+    // 30A of 40A selects the low current limit (bit 4), set by register
+    // (bit 2), with unlimited retries (bits 7:5).
+    //
+    Lm25066::configure_inrush(&inrush, read, |code, payload| {
+        writes.push((code, payload.to_vec()));
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(writes, [(0xd9, vec![0xf4])]);
+
+    writes.clear();
+    inrush.max_current = Amperes(40.0);
+    inrush.retry = Retry::Latch;
+
+    Lm25066::configure_inrush(&inrush, read, |code, payload| {
+        writes.push((code, payload.to_vec()));
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(writes, [(0xd9, vec![0x24])]);
+
+    //
+    // 21.2A of 40A is below the LM25066's low limit, but not the
+    // LM5066I's.
+    //
+    writes.clear();
+    inrush.max_current = Amperes(21.2);
+
+    assert_eq!(
+        Lm25066::configure_inrush(&inrush, read, |_, _| Ok(())),
+        Err(HotSwapError::Invalid(Error::ValueOutOfRange))
+    );
+
+    Lm5066I::configure_inrush(&inrush, read, |code, payload| {
+        writes.push((code, payload.to_vec()));
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(writes, [(0xd9, vec![0x34])]);
+
+    inrush.fault_timer = Some(Duration::from_millis(10));
+    assert_eq!(
+        Lm5066I::configure_inrush(&inrush, read, |_, _| Ok(())),
+        Err(HotSwapError::Invalid(Error::Unsupported))
+    );
+}
+
+#[test]
+fn fan_target() {
+    use commands::{FAN_CONFIG_1_2, FAN_CONFIG_3_4};
+    use fan::*;
+    use units::{Percent, RPM};
+
+    //
+    // Fan 1 is installed, commanded in RPM with 2 pulses per revolution;
+    // fan 2 is installed, commanded in duty cycle with 1 pulse per
+    // revolution.
+    //
+    let config = FAN_CONFIG_1_2::CommandData(0b1101_1000);
+    let fan1 = FanConfig::fan1(config).unwrap();
+    let fan2 = FanConfig::fan2(config).unwrap();
+
+    assert_eq!(
+        fan1,
+        FanConfig {
+            installed: true,
+            mode: FanMode::Rpm,
+            pulses: 2
+        }
+    );
+
+    assert_eq!(
+        fan2,
+        FanConfig {
+            installed: true,
+            mode: FanMode::Duty,
+            pulses: 1
+        }
+    );
+
+    let fan3 = FanConfig::fan3(FAN_CONFIG_3_4::CommandData(0)).unwrap();
+    assert!(!fan3.installed);
+
+    let max = Some(RPM(12000.0));
+    let rpm = FanTarget::Rpm(RPM(6000.0));
+    let duty = FanTarget::Duty(Percent(25.0));
+
+    assert_eq!(fan1.command(rpm, None), Ok(6000.0));
+    assert_eq!(fan1.command(duty, max), Ok(3000.0));
+    assert_eq!(fan1.command(duty, None), Err(Error::Unsupported));
+    assert_eq!(fan2.command(duty, None), Ok(25.0));
+    assert_eq!(fan2.command(rpm, max), Ok(50.0));
+    assert_eq!(fan2.command(rpm, None), Err(Error::Unsupported));
+    assert_eq!(fan3.command(duty, max), Err(Error::Unsupported));
+
+    assert_eq!(
+        fan2.command(FanTarget::Duty(Percent(120.0)), None),
+        Err(Error::ValueOutOfRange)
+    );
+
+    assert_eq!(
+        fan1.command(FanTarget::Rpm(RPM(-1.0)), max),
+        Err(Error::ValueOutOfRange)
+    );
+
+    assert_eq!(
+        fan1.command(FanTarget::Rpm(RPM(13000.0)), max),
+        Err(Error::ValueOutOfRange)
+    );
+
+    let raw = fan1.command_linear11(rpm, None).unwrap();
+    assert_eq!(raw.to_real(), 6000.0);
+    assert_eq!(fan1.target(raw.to_real()), rpm);
+    assert_eq!(fan2.target(25.0), duty);
+}
+
+#[test]
+fn duty_cycle() {
+    use units::Percent;
+
+    let mut max = commands::MAX_DUTY::CommandData(0);
+    max.set(Percent(95.0)).unwrap();
+    assert_eq!(max.get(), Ok(Percent(95.0)));
+
+    let duty =
+        commands::READ_DUTY_CYCLE::CommandData::from_slice(&[0x64, 0xe8])
+            .unwrap();
+    assert_eq!(duty.get(), Ok(Percent(12.5)));
+
+    //
+    // A device's own definition stands in for the common one.
+    //
+    let duty = commands::bmr480::READ_DUTY_CYCLE::CommandData(duty.0);
+    assert_eq!(duty.get(), Ok(Percent(12.5)));
+}
+
+#[test]
+fn iout_calibration() {
+    use calibration::*;
+    use commands::tps546b24a::{IOUT_CAL_GAIN, IOUT_CAL_OFFSET};
+    use units::{Amperes, Unitless};
+
+    let quantize_gain = |g| {
+        let mut data = IOUT_CAL_GAIN::CommandData(0);
+        data.set(Unitless(g))?;
+        Ok(data.get()?.0)
+    };
+
+    let quantize_offset = |o| {
+        let mut data = IOUT_CAL_OFFSET::CommandData(0);
+        data.set(o)?;
+        data.get()
+    };
+
+    //
+    // Simulate a device with a true sense resistance of 0.55 mOhm and a
+    // sense offset of 0.2 mV, calibrated with a gain of 0.5 mOhm and no
+    // offset.
+    //
+    let report = |cal: IoutCalibration, i: f32| {
+        Amperes((0.55 * i + 0.2) / cal.gain + cal.offset.0)
+    };
+
+    let current = IoutCalibration {
+        gain: 0.5,
+        offset: Amperes(0.0),
+    };
+
+    let measurements: Vec<_> = [0.0, 5.0, 10.0, 20.0]
+        .iter()
+        .map(|&i| IoutMeasurement {
+            reference: Amperes(i),
+            reported: report(current, i),
+        })
+        .collect();
+
+    let cal = calibrate(&measurements, current, quantize_gain, quantize_offset)
+        .unwrap();
+
+    assert_eq!(Ok(cal.gain), quantize_gain(cal.gain));
+    assert_eq!(Ok(cal.offset), quantize_offset(cal.offset));
+    assert!((cal.gain - 0.55).abs() < 0.001);
+
+    for i in [1.0, 7.5, 15.0, 25.0] {
+        let reported = report(cal, i);
+        assert!((reported.0 - i).abs() < 0.02, "{} => {:?}", i, reported);
+    }
+
+    //
+    // A single reference current (or a decreasing reported current) can't
+    // be calibrated.
+    //
+    let degenerate = [IoutMeasurement {
+        reference: Amperes(5.0),
+        reported: Amperes(6.0),
+    }; 3];
+
+    assert_eq!(
+        calibrate(&degenerate, current, quantize_gain, quantize_offset),
+        Err(Error::ValueOutOfRange)
+    );
+
+    let inverted = [
+        IoutMeasurement {
+            reference: Amperes(0.0),
+            reported: Amperes(6.0),
+        },
+        IoutMeasurement {
+            reference: Amperes(5.0),
+            reported: Amperes(1.0),
+        },
+    ];
+
+    assert_eq!(
+        calibrate(&inverted, current, quantize_gain, quantize_offset),
+        Err(Error::ValueOutOfRange)
+    );
+
+    assert_eq!(
+        calibrate(&[], current, quantize_gain, quantize_offset),
+        Err(Error::ValueOutOfRange)
+    );
+}
+
+#[test]
+fn zone_operation() {
+    use commands::{ZONE_ACTIVE, ZONE_CONFIG};
+    use sequence::Step;
+    use zone::*;
+
+    assert_eq!(Zone::new(0x80), None);
+
+    let (read, write) = (Zone::new(3).unwrap(), Zone::new(5).unwrap());
+
+    let steps: Vec<_> = assign(0x40, Some(1), read, write).collect();
+
+    assert_eq!(
+        steps,
+        [
+            (0x40, Step::Page(1)),
+            (0x40, Step::WriteWord(CommandCode::ZONE_CONFIG, 0x0305)),
+        ]
+    );
+
+    assert_eq!(assign(0x41, None, read, write).count(), 1);
+
+    assert_eq!(
+        operation(write, true),
+        [
+            (
+                ZONE_WRITE_ADDRESS,
+                Step::WriteWord(CommandCode::ZONE_ACTIVE, 0x0505)
+            ),
+            (
+                ZONE_WRITE_ADDRESS,
+                Step::WriteByte(CommandCode::OPERATION, 0x80)
+            ),
+        ]
+    );
+
+    assert_eq!(
+        operation(write, false)[1],
+        (
+            ZONE_WRITE_ADDRESS,
+            Step::WriteByte(CommandCode::OPERATION, 0x00)
+        )
+    );
+
+    assert_eq!(
+        config(ZONE_CONFIG::CommandData::from_slice(&[0x05, 0x03]).unwrap()),
+        Some((read, write))
+    );
+
+    assert_eq!(active(ZONE_ACTIVE::CommandData(0x8005)), None);
+}
+
+#[test]
+fn alert_service() {
+    use alert::*;
+    use commands::STATUS_WORD;
+    use std::collections::VecDeque;
+
+    let mut responses: VecDeque<_> = [0x80u8, 0xa0].iter().copied().collect();
+    let mut reads = vec![];
+    let mut reports = vec![];
+
+    let count = service(
+        8,
+        || Ok::<_, ()>(responses.pop_front()),
+        |address, code, buf| {
+            reads.push((address, code));
+            match (address, CommandCode::from_u8(code).unwrap()) {
+                (0x40, CommandCode::STATUS_WORD) => {
+                    buf.copy_from_slice(&[0x22, 0x80])
+                }
+                (0x50, CommandCode::STATUS_WORD) => {
+                    buf.copy_from_slice(&[0x00, 0x00])
+                }
+                (_, CommandCode::STATUS_VOUT) => buf[0] = 0x80,
+                (_, CommandCode::STATUS_CML) => buf[0] = 0x40,
+                _ => return Err(()),
+            }
+            Ok(())
+        },
+        |report| reports.push(report),
+    );
+
+    assert_eq!(count, Ok(2));
+    assert_eq!(
+        reads,
+        [(0x40, 0x79), (0x40, 0x7a), (0x40, 0x7e), (0x50, 0x79)]
+    );
+
+    assert_eq!(reports[0].address, 0x40);
+    assert_eq!(
+        reports[0].status.get_output_voltage_fault(),
+        Some(STATUS_WORD::OutputVoltageFault::Fault)
+    );
+    assert_eq!(reports[0].vout.map(|s| s.0), Some(0x80));
+    assert_eq!(reports[0].cml.map(|s| s.0), Some(0x40));
+    assert_eq!(reports[0].iout, None);
+    assert_eq!(reports[1].address, 0x50);
+    assert_eq!(reports[1].vout, None);
+
+    //
+    // A device that never deasserts is bounded by the maximum.
+    //
+    let count =
+        service(3, || Ok::<_, ()>(Some(0x80)), |_, _, _| Ok(()), |_| {});
+    assert_eq!(count, Ok(3));
+}
+
+#[test]
+fn units_arithmetic() {
+    use units::{Amperes, Volts, Watts};
+
+    let vout = Volts(12.0);
+    let iout = Amperes(2.5);
+
+    assert_eq!(vout * iout, Watts(30.0));
+    assert_eq!(iout * vout, Watts(30.0));
+    assert_eq!(Watts(30.0) / vout, iout);
+    assert_eq!(Watts(30.0) / iout, vout);
+
+    assert_eq!(vout + Volts(0.5), Volts(12.5));
+    assert_eq!(vout - Volts(0.5), Volts(11.5));
+    assert_eq!(-vout, Volts(-12.0));
+    assert_eq!(vout * 2.0, Volts(24.0));
+    assert_eq!(2.0 * vout, Volts(24.0));
+    assert_eq!(vout / 4.0, Volts(3.0));
+    assert_eq!(Volts(13.2) / vout, 1.1);
+
+    let margin = Volts(11.4);
+    assert!(vout > margin);
+    assert!(margin < vout);
+    assert_eq!(Volts(f32::NAN).partial_cmp(&vout), None);
+}
+
+#[test]
+fn units_conversions() {
+    use units::{Amperes, Celsius, Volts, Watts};
+
+    assert_eq!(Volts(1.2).as_millivolts(), 1200.0);
+    assert_eq!(Volts::from_millivolts(850.0), Volts(0.85));
+    assert_eq!(Volts(0.5).as_microvolts(), 500000.0);
+    assert_eq!(Amperes::from_milliamperes(250.0), Amperes(0.25));
+    assert_eq!(Amperes(0.25).as_milliamperes(), 250.0);
+    assert_eq!(Watts(1500.0).as_kilowatts(), 1.5);
+
+    assert_eq!(Celsius(100.0).as_fahrenheit(), 212.0);
+    assert_eq!(Celsius::from_fahrenheit(-40.0), Celsius(-40.0));
+    assert_eq!(Celsius(0.0).as_kelvin(), 273.15);
+    assert_eq!(Celsius::from_kelvin(273.15), Celsius(0.0));
+}
+
+#[test]
+fn units_derived() {
+    use units::*;
+
+    assert_eq!(Amperes(2.0) * Ohms(0.5), Volts(1.0));
+    assert_eq!(Volts(1.0) / Ohms(0.5), Amperes(2.0));
+    assert_eq!(Volts(1.0) / Amperes(2.0), Ohms(0.5));
+    assert_eq!(Milliohms(250.0).as_ohms(), 0.25);
+    assert_eq!(Ohms::from_milliohms(250.0), Ohms(0.25));
+
+    let energy = Watts(120.0) * Seconds(30.0);
+    assert_eq!(energy, Joules(3600.0));
+    assert_eq!(energy.as_watt_hours(), 1.0);
+    assert_eq!(WattHours(1.0).as_joules(), 3600.0);
+    assert_eq!(WattHours(1500.0).as_kilowatt_hours(), 1.5);
+    assert_eq!(KilowattHours(1.5).as_watt_hours(), 1500.0);
+
+    let mut gain = commands::IOUT_CAL_GAIN::CommandData(0);
+    gain.set(Milliohms(0.5)).unwrap();
+    assert_eq!(gain.get(), Ok(Milliohms(0.5)));
+
+    let mut rate = commands::VOUT_TRANSITION_RATE::CommandData(0);
+    rate.set(VoltsPerMillisecond(1.25)).unwrap();
+    assert_eq!(rate.get(), Ok(VoltsPerMillisecond(1.25)));
+}
+
+#[cfg(feature = "uom")]
+#[test]
+fn uom_conversions() {
+    use units::{Celsius, Volts};
+    use uom::si::electric_potential::millivolt;
+    use uom::si::f32::{ElectricPotential, ThermodynamicTemperature};
+    use uom::si::thermodynamic_temperature::kelvin;
+
+    let v: ElectricPotential = Volts(1.2).into();
+    assert_eq!(v.get::<millivolt>(), 1200.0);
+    assert_eq!(
+        Volts::from(ElectricPotential::new::<millivolt>(500.0)),
+        Volts(0.5)
+    );
+
+    let t: ThermodynamicTemperature = Celsius(25.0).into();
+    assert!((t.get::<kelvin>() - 298.15).abs() < 0.01);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_roundtrip() {
+    use pmbus::serialize::FieldValue;
+    use ron::de::from_str;
+    use ron::ser::to_string;
+
+    let coefficients = Coefficients {
+        m: 6427,
+        b: 0,
+        R: -2,
+    };
+    let s = to_string(&coefficients).unwrap();
+    assert_eq!(from_str::<Coefficients>(&s).unwrap(), coefficients);
+
+    let volts = units::Volts(1.2);
+    assert_eq!(
+        from_str::<units::Volts>(&to_string(&volts).unwrap()),
+        Ok(volts)
+    );
+
+    let code = CommandCode::READ_VOUT;
+    let s = to_string(&code).unwrap();
+    assert_eq!(s, "READ_VOUT");
+    assert_eq!(from_str::<CommandCode>(&s), Ok(code));
+
+    let device = Device::Adm1272;
+    assert_eq!(from_str::<Device>(&to_string(&device).unwrap()), Ok(device));
+
+    let mut fields = vec![];
+
+    Device::Adm1272
+        .interpret(
+            CommandCode::OPERATION as u8,
+            &[0x84],
+            || commands::VOUT_MODE::CommandData(0),
+            |f, v| fields.push(FieldValue::new(f, v)),
+        )
+        .unwrap();
+
+    let s = to_string(&fields).unwrap();
+    let decoded: Vec<FieldValue> = from_str(&s).unwrap();
+    assert_eq!(decoded, fields);
+    assert_eq!(decoded[0].name, "OnOffState");
+    assert_eq!(decoded[0].raw, 1);
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn ffi_interface() {
+    use pmbus::ffi::*;
+    use std::collections::HashMap;
+    use std::ffi::c_void;
+
+    unsafe extern "C" fn collect(arg: *mut c_void, field: *const PmbusField) {
+        let fields = &mut *(arg as *mut Vec<(String, Option<String>)>);
+        let field = &*field;
+        let str = |ptr, len| {
+            String::from_utf8(std::slice::from_raw_parts(ptr, len).to_vec())
+                .unwrap()
+        };
+
+        fields.push((
+            str(field.name, field.name_len),
+            match field.value.is_null() {
+                true => None,
+                false => Some(str(field.value, field.value_len)),
+            },
+        ));
+    }
+
+    let device = b"adm1272\0".as_ptr() as *const _;
+    let mut fields: Vec<(String, Option<String>)> = vec![];
+    let arg = &mut fields as *mut _ as *mut c_void;
+    let code = CommandCode::OPERATION as u8;
+
+    let rval = unsafe { pmbus_fields(device, code, Some(collect), arg) };
+    assert_eq!(rval, 0);
+    assert!(fields.iter().any(|(n, v)| n == "OnOffState" && v.is_none()));
+
+    let mut payload = [0x04u8];
+    let field = b"OnOffState\0".as_ptr() as *const _;
+
+    let rval = unsafe {
+        pmbus_set_field_integer(
+            device,
+            code,
+            payload.as_mut_ptr(),
+            1,
+            0,
+            field,
+            1,
+        )
+    };
+    assert_eq!(rval, 0);
+    assert_eq!(payload, [0x84]);
+
+    let mut raw = 0;
+    let rval = unsafe {
+        pmbus_get_field(device, code, payload.as_ptr(), 1, 0, field, &mut raw)
+    };
+    assert_eq!((rval, raw), (0, 1));
+
+    fields.clear();
+    let rval = unsafe {
+        pmbus_decode(device, code, payload.as_ptr(), 1, 0, Some(collect), arg)
+    };
+    assert_eq!(rval, 0);
+    assert!(fields.iter().any(|(n, v)| n == "OnOffState" && v.is_some()));
+
+    let (mut buf, mut len) = ([0u8; 8], 0);
+    let rval = unsafe {
+        pmbus_encode(
+            device,
+            0x10,
+            code,
+            payload.as_ptr(),
+            1,
+            false,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut len,
+        )
+    };
+    assert_eq!(rval, 0);
+    assert_eq!(&buf[..len], [0x01, 0x84]);
+
+    let bogus = b"nonesuch\0".as_ptr() as *const _;
+    let rval = unsafe { pmbus_fields(bogus, code, Some(collect), arg) };
+    assert_eq!(rval, error_code(Error::InvalidCode));
+
+    //
+    // The error codes must match those defined in the header.
+    //
+    let defines: HashMap<_, _> = include_str!("../include/pmbus.h")
+        .lines()
+        .filter_map(|l| l.strip_prefix("#define\tPMBUS_ERR_"))
+        .filter_map(|l| l.split_once('\t'))
+        .map(|(name, val)| (name, val.trim_matches(&['\t', '(', ')'][..])))
+        .collect();
+
+    let errors = [
+        (Error::ShortData, "SHORT_DATA"),
+        (Error::InvalidCode, "INVALID_CODE"),
+        (Error::ValueOutOfRange, "VALUE_OUT_OF_RANGE"),
+        (Error::InvalidMode, "INVALID_MODE"),
+        (Error::InvalidSentinel, "INVALID_SENTINEL"),
+        (Error::MissingCoefficients, "MISSING_COEFFICIENTS"),
+        (Error::InvalidReplacement, "INVALID_REPLACEMENT"),
+        (Error::OverflowReplacement, "OVERFLOW_REPLACEMENT"),
+        (Error::InvalidField, "INVALID_FIELD"),
+        (Error::InvalidOperation, "INVALID_OPERATION"),
+        (Error::InvalidLength, "INVALID_LENGTH"),
+        (Error::BufferTooSmall, "BUFFER_TOO_SMALL"),
+        (Error::Unsupported, "UNSUPPORTED"),
+        (Error::UnsupportedRevision, "UNSUPPORTED_REVISION"),
+        (Error::BadPec, "BAD_PEC"),
+    ];
+
+    assert_eq!(defines.len(), errors.len());
+
+    for (err, name) in errors {
+        assert_eq!(defines[name], error_code(err).to_string(), "{}", name);
+    }
+}
+
+#[cfg(feature = "wasm-bindgen")]
+#[test]
+fn wasm_interface() {
+    let code = CommandCode::OPERATION as u8;
+
+    let json = wasm::interpret("adm1272", code, &[0x84], 0).unwrap();
+    assert!(json.starts_with("[{\"name\":\"OnOffState\""));
+    assert!(json.contains("\"raw\":1"));
+
+    let json = wasm::schema("adm1272", code).unwrap();
+    assert!(json.starts_with("{\"name\":\"OPERATION\""));
+    assert!(json.contains("\"sentinels\":[{\"name\":\"Off\""));
+
+    let json = wasm::commands("adm1272").unwrap();
+    assert!(json.contains("{\"code\":212,\"name\":\"PMON_CONFIG\"}"));
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn cbor_encoding() {
+    use alert::FaultReport;
+    use cbor::*;
+    use commands::{STATUS_CML, STATUS_VOUT, STATUS_WORD};
+
+    let readings = [Reading {
+        code: CommandCode::READ_VIN as u8,
+        page: None,
+        value: 12.0,
+    }];
+
+    let snapshot = Snapshot {
+        device: Device::Adm1272,
+        address: 0x10,
+        timestamp: 1000,
+        readings: &readings,
+    };
+
+    let mut buf = [0u8; 64];
+    let len = snapshot.encode(&mut buf).unwrap();
+
+    assert_eq!(
+        &buf[..len],
+        [
+            0xa4, 0x00, 0x67, b'a', b'd', b'm', b'1', b'2', b'7', b'2', 0x01,
+            0x10, 0x02, 0x19, 0x03, 0xe8, 0x03, 0x81, 0x83, 0x18, 0x88, 0xf6,
+            0xfa, 0x41, 0x40, 0x00, 0x00,
+        ]
+    );
+
+    assert_eq!(snapshot.encode(&mut buf[..8]), Err(Error::BufferTooSmall));
+
+    let report = FaultReport {
+        address: 0x40,
+        status: STATUS_WORD::CommandData(0x8002),
+        vout: Some(STATUS_VOUT::CommandData(0x80)),
+        iout: None,
+        input: None,
+        temperature: None,
+        cml: Some(STATUS_CML::CommandData(0x40)),
+        other: None,
+        mfr: None,
+        fans: None,
+    };
+
+    let len = encode_fault(&report, &mut buf).unwrap();
+
+    assert_eq!(
+        &buf[..len],
+        [
+            0xa4, 0x00, 0x18, 0x40, 0x01, 0x19, 0x80, 0x02, 0x02, 0x18, 0x80,
+            0x06, 0x18, 0x40,
+        ]
+    );
+
+    let mut e = Encoder::new(&mut buf);
+    e.int(-1).unwrap();
+    e.int(-500).unwrap();
+    assert_eq!(e.len(), 4);
+    assert_eq!(&buf[..4], [0x20, 0x39, 0x01, 0xf3]);
+}
+
+#[test]
+fn field_ids() {
+    use std::collections::HashMap;
+
+    let devices = [
+        Device::Common,
+        Device::Adm1272,
+        Device::Adm1275,
+        Device::Bmr350,
+        Device::Bmr453,
+        Device::Bmr456,
+        Device::Bmr457,
+        Device::Bmr480,
+        Device::Bmr491,
+        Device::D1U54P,
+        Device::GenericPsu,
+        Device::Ina233,
+        Device::Ir35217,
+        Device::Ir38063,
+        Device::Isl68137,
+        Device::Isl68224,
+        Device::Isl69247,
+        Device::Lm25066,
+        Device::Lm5066I,
+        Device::Ltc2974,
+        Device::Ltc2977,
+        Device::Ltc3880,
+        Device::Ltc3884,
+        Device::Ltm4664,
+        Device::Max16601,
+        Device::Max20730,
+        Device::Max34451,
+        Device::Mp2971,
+        Device::Mp2975,
+        Device::Mp5023,
+        Device::Mwocp68,
+        Device::Raa228926,
+        Device::Raa229618,
+        Device::Tda38640,
+        Device::Tps25990,
+        Device::Tps53659,
+        Device::Tps53688,
+        Device::Tps546B24A,
+        Device::Tps546D24A,
+        Device::Ucd90320,
+        Device::Ucd9090,
+        Device::Xdpe12284,
+        Device::Xdpe15284,
+    ];
+
+    let mut ids: HashMap<u32, (&str, u8, &str)> = HashMap::new();
+
+    for device in devices.iter() {
+        for code in 0..=0xffu8 {
+            let _ = device.fields(code, |f| {
+                let key = (f.desc(), code, f.name());
+
+                //
+                // A device that does not override a common definition
+                // shares its fields (and therefore their identifiers).
+                //
+                if let Some(prev) = ids.insert(f.id(), key) {
+                    assert_eq!(prev, key, "{:?} {:x}", device, code);
+                }
+            });
+        }
+    }
+
+    let op = CommandCode::OPERATION as u8;
+    let id = |device: Device, code, name| {
+        let mut id = None;
+        device
+            .fields(code, |f| {
+                if f.name() == name {
+                    id = Some(f.id());
+                }
+            })
+            .unwrap();
+        id.unwrap()
+    };
+
+    assert_eq!(id(Device::Common, op, "OnOffState"), 0xbad3dd19);
+    assert_eq!(id(Device::Adm1272, op, "OnOffState"), 0xbad3dd19);
+    assert_eq!(id(Device::Adm1272, 0xd4, "TSFilt"), 0x3c05d5e0);
+}
+
+#[test]
+fn interpret_rev() {
+    let mode = || commands::VOUT_MODE::CommandData(0);
+    let code = CommandCode::OPERATION as u8;
+
+    //
+    // Absent revision-specific definitions, any revision interprets as the
+    // device does.
+    //
+    for rev in [0, 1, 0xffff_ffff] {
+        let mut fields = vec![];
+        let mut expected = vec![];
+
+        Device::Adm1272
+            .interpret_rev(rev, code, &[0x84], mode, |f, v| {
+                fields.push((f.name(), v.raw()))
+            })
+            .unwrap();
+
+        Device::Adm1272
+            .interpret(code, &[0x84], mode, |f, v| {
+                expected.push((f.name(), v.raw()))
+            })
+            .unwrap();
+
+        assert_eq!(fields, expected);
+    }
+
+    assert_eq!(
+        Device::Adm1272.interpret_rev(0, 0xd9, &[], mode, |_, _| {}),
+        Ok(())
+    );
+
+    assert!(Device::Adm1272.interpreter_rev(0, 0x03).is_some());
+    assert!(Device::Common.interpreter_rev(7, 0x01).is_some());
+}
+
+#[test]
+fn spec_revision() {
+    let rev = |val| SpecRevision::from_pmbus_revision(val).unwrap();
+
+    assert_eq!(rev(0x22), SpecRevision::Rev1_2);
+    assert_eq!(rev(0x33), SpecRevision::Rev1_3);
+    assert_eq!(SpecRevision::from_pmbus_revision(0x0f), None);
+    assert!(SpecRevision::Rev1_3_1 > SpecRevision::Rev1_3);
+
+    assert!(SpecRevision::Rev1_3.admits(SpecRevision::Rev1_3_1));
+    assert!(SpecRevision::Rev1_3.admits(SpecRevision::Rev1_2));
+    assert!(!SpecRevision::Rev1_2.admits(SpecRevision::Rev1_3));
+    assert!(!SpecRevision::Rev1_2.admits(SpecRevision::Rev1_3_1));
+
+    assert_eq!(
+        CommandCode::ZONE_CONFIG.introduced(),
+        Some(SpecRevision::Rev1_3)
+    );
+    assert_eq!(CommandCode::OPERATION.introduced(), None);
+
+    //
+    // Devices inherit the revisions of the common commands.
+    //
+    use commands::adm1272;
+    assert_eq!(
+        adm1272::CommandCode::READ_EIN.introduced(),
+        Some(SpecRevision::Rev1_2)
+    );
+
+    let dev = Device::Adm1272;
+    let ein = CommandCode::READ_EIN as u8;
+
+    assert!(dev.supports(ein, rev(0x22)));
+    assert!(!dev.supports(ein, rev(0x11)));
+    assert!(dev.supports(CommandCode::OPERATION as u8, rev(0x00)));
+
+    let mode = || commands::VOUT_MODE::CommandData(0);
+    let code = CommandCode::ZONE_ACTIVE as u8;
+
+    assert_eq!(
+        Device::Common.interpret_spec(
+            rev(0x22),
+            code,
+            &[0, 0],
+            mode,
+            |_, _| {}
+        ),
+        Err(Error::UnsupportedRevision)
+    );
+
+    assert_eq!(
+        Device::Common.interpret_spec(
+            rev(0x33),
+            code,
+            &[0, 0],
+            mode,
+            |_, _| {}
+        ),
+        Ok(())
+    );
+
+    //
+    // A device reporting 1.3 must be allowed the commands that 1.3.1 adds.
+    //
+    let kwh = CommandCode::READ_KWH_IN as u8;
+
+    assert!(Device::Common.supports(kwh, rev(0x33)));
+    assert_eq!(
+        Device::Common.interpret_spec(
+            rev(0x33),
+            kwh,
+            &[0xa0, 0x86, 0x01, 0x00],
+            mode,
+            |_, _| {}
+        ),
+        Ok(())
+    );
+
+    assert_eq!(
+        Device::Common.interpret_spec(rev(0x22), kwh, &[0; 4], mode, |_, _| {}),
+        Err(Error::UnsupportedRevision)
+    );
+}
+
+#[test]
+fn heuristic() {
+    use pmbus::heuristic::{self, HeuristicField};
+
+    assert_eq!(heuristic::guess_operation(&[], true), Operation::SendByte);
+    assert_eq!(
+        heuristic::guess_operation(&[0x10], false),
+        Operation::ReadByte
+    );
+    assert_eq!(
+        heuristic::guess_operation(&[0x34, 0x12], true),
+        Operation::WriteWord
+    );
+    assert_eq!(
+        heuristic::guess_operation(&[3, b'A', b'D', b'I'], false),
+        Operation::ReadBlock
+    );
+    assert_eq!(
+        heuristic::guess_operation(&[9, 1, 2], false),
+        Operation::Unknown
+    );
+
+    let dev = Device::Adm1272;
+    let code = (0..=u8::MAX)
+        .find(|&code| {
+            let mut defined = false;
+            dev.command(code, |_| defined = true);
+            !defined
+        })
+        .unwrap();
+
+    //
+    // A defined command is interpreted as usual.
+    //
+    let rval = heuristic::interpret(
+        dev,
+        CommandCode::OPERATION as u8,
+        &[0x84],
+        false,
+        mode,
+        |f, _| assert!(!f.name().starts_with("Heuristic")),
+    );
+    assert_eq!(rval, Ok(false));
+
+    //
+    // An undefined word is interpreted as LINEAR11 and, given a VOUT_MODE
+    // that indicates it, ULINEAR16.
+    //
+    let ulinear16 = || commands::VOUT_MODE::CommandData(0x17);
+    let mut fields = vec![];
+
+    let rval = heuristic::interpret(
+        dev,
+        code,
+        &[0x00, 0xd2],
+        false,
+        ulinear16,
+        |f, v| {
+            fields.push((f.name(), format!("{}", v), f.id()));
+        },
+    );
+
+    assert_eq!(rval, Ok(true));
+    assert_eq!(
+        fields,
+        vec![
+            ("HeuristicOperation", "ReadWord".to_string(), 0),
+            ("HeuristicLinear11", "8".to_string(), 0),
+            ("HeuristicULinear16", "105".to_string(), 0),
+        ]
+    );
+
+    //
+    // An undefined block of printable characters is interpreted as ASCII.
+    //
+    let mut found = false;
+
+    heuristic::interpret(
+        dev,
+        code,
+        &[3, b'A', b'D', b'I'],
+        false,
+        mode,
+        |f, v| {
+            if f.name() == HeuristicField::Ascii.name() {
+                assert_eq!(format!("{}", v), "\"ADI\"");
+                found = true;
+            }
+        },
+    )
+    .unwrap();
+
+    assert!(found);
+}
+
+#[test]
+fn configuration_signature() {
+    use pmbus::signature::{self, Signer};
+
+    assert!(CommandCode::VOUT_OV_FAULT_LIMIT.configuration());
+    assert!(CommandCode::IOUT_OC_FAULT_RESPONSE.configuration());
+    assert!(!CommandCode::READ_VOUT.configuration());
+    assert!(!CommandCode::STATUS_WORD.configuration());
+    assert_eq!(CommandCode::OPERATION.volatile(), 0x80);
+    assert_eq!(CommandCode::VOUT_COMMAND.volatile(), 0);
+
+    //
+    // Devices inherit the classification of the commands that they define.
+    //
+    use commands::adm1272;
+    assert!(adm1272::CommandCode::OPERATION.configuration());
+    assert_eq!(adm1272::CommandCode::OPERATION.volatile(), 0x80);
+    assert!(!adm1272::CommandCode::PMON_CONFIG.configuration());
+
+    let dev = Device::Tps546B24A;
+    let mut codes = vec![];
+
+    signature::configuration(dev, |code, _| codes.push(code));
+    assert!(codes.contains(&(CommandCode::VOUT_COMMAND as u8)));
+    assert!(!codes.contains(&(CommandCode::READ_VOUT as u8)));
+
+    //
+    // Computing a signature reads each configuration command; the output
+    // being on or off doesn't change it, but a changed limit does.
+    //
+    let sign = |operation: u8, limit: u16| {
+        signature::signature(dev, |code, op, buf| {
+            assert_ne!(op, Operation::Unknown);
+
+            if code == CommandCode::OPERATION as u8 {
+                buf[0] = operation;
+                Ok::<_, ()>(1)
+            } else if code == CommandCode::VOUT_OV_FAULT_LIMIT as u8 {
+                buf[..2].copy_from_slice(&limit.to_le_bytes());
+                Ok(2)
+            } else {
+                buf.iter_mut().for_each(|b| *b = code);
+                Ok(buf.len())
+            }
+        })
+        .unwrap()
+    };
+
+    assert_eq!(sign(0x80, 0x1000), sign(0x00, 0x1000));
+    assert_ne!(sign(0x80, 0x1000), sign(0x80, 0x1001));
+    assert_ne!(sign(0x80, 0x1000), sign(0x88, 0x1000));
+
+    //
+    // Signatures don't depend on the order in which commands are added.
+    //
+    let mut a = Signer::new(dev);
+    let mut b = Signer::new(dev);
+
+    a.add(CommandCode::VOUT_COMMAND as u8, &[0x00, 0x10])
+        .unwrap();
+    a.add(CommandCode::VOUT_MAX as u8, &[0x00, 0x20]).unwrap();
+    b.add(CommandCode::VOUT_MAX as u8, &[0x00, 0x20]).unwrap();
+    b.add(CommandCode::VOUT_COMMAND as u8, &[0x00, 0x10])
+        .unwrap();
+
+    assert_eq!(a.signature(), b.signature());
+    assert_eq!(
+        a.add(CommandCode::READ_VOUT as u8, &[0, 0]),
+        Err(Error::InvalidCode)
+    );
+    assert_ne!(a.signature(), Signer::new(Device::Adm1272).signature());
+}
+
+#[test]
+fn audit() {
+    use pmbus::audit::{self, Finding, Item};
+
+    let on_off = [0x17 | 0x80];
+
+    //
+    // With VOUT_MODE of 0x17, VOUT is ULINEAR16 with an exponent of -9.
+    //
+    let snapshot: &[(u8, &[u8])] = &[
+        (CommandCode::VOUT_MODE as u8, &[0x17]),
+        (CommandCode::ON_OFF_CONFIG as u8, &on_off),
+        (CommandCode::VOUT_MARGIN_HIGH as u8, &[0x00, 0x08]),
+        (CommandCode::VOUT_MARGIN_LOW as u8, &[0x00, 0x0a]),
+    ];
+
+    let defaults: &[(u8, &[u8])] = &[
+        (CommandCode::ON_OFF_CONFIG as u8, &on_off),
+        (CommandCode::VOUT_MARGIN_HIGH as u8, &[0x00, 0x0c]),
+    ];
+
+    let mut findings = vec![];
+    let mut decoded = 0;
+
+    let summary =
+        audit::audit(Device::Common, snapshot, Some(defaults), |i| match i {
+            Item::Decoded(..) => decoded += 1,
+            Item::Finding(f) => findings.push(f),
+        });
+
+    let on_off = CommandCode::ON_OFF_CONFIG as u8;
+    let high = CommandCode::VOUT_MARGIN_HIGH as u8;
+    let low = CommandCode::VOUT_MARGIN_LOW as u8;
+
+    assert_eq!(
+        findings,
+        vec![
+            Finding::Reserved(on_off, 0x80),
+            Finding::Default(high),
+            Finding::Limits(low, 5.0, high, 4.0),
+        ]
+    );
+
+    assert_eq!(summary.decoded, 4);
+    assert_eq!(summary.findings, 3);
+    assert!(decoded > 4);
+
+    //
+    // Commands that the device doesn't define or that can't be interpreted
+    // are findings rather than errors, as is a missing VOUT_MODE.
+    //
+    let snapshot: &[(u8, &[u8])] = &[
+        (CommandCode::VOUT_COMMAND as u8, &[0x00, 0x10]),
+        (CommandCode::VOUT_MAX as u8, &[0x00]),
+        (0x4d, &[0x00, 0x00]),
+    ];
+
+    findings.clear();
+
+    let summary = audit::audit(Device::Common, snapshot, None, |i| {
+        if let Item::Finding(f) = i {
+            findings.push(f);
+        }
+    });
+
+    assert_eq!(summary.decoded, 1);
+    assert_eq!(
+        findings,
+        vec![
+            Finding::MissingVOutMode(CommandCode::VOUT_COMMAND as u8),
+            Finding::Invalid(CommandCode::VOUT_MAX as u8, Error::ShortData),
+            Finding::Undefined(0x4d),
+        ]
+    );
+}
+
+#[test]
+fn expect() {
+    use pmbus::expect::{self, Expected, Mismatch, Tolerance};
+
+    let dev = Device::Common;
+    let code = CommandCode::OPERATION as u8;
+    let ulinear16 = || commands::VOUT_MODE::CommandData(0x17);
+
+    expect::assert_fields(
+        dev,
+        code,
+        &[0x84],
+        mode,
+        &[
+            ("OnOffState", Expected::Sentinel("On")),
+            ("MarginFaultResponse", Expected::Raw(0b01)),
+        ],
+    );
+
+    //
+    // VOUT_COMMAND of 0x0a00 is 5V with an exponent of -9.
+    //
+    let vout = CommandCode::VOUT_COMMAND as u8;
+
+    expect::assert_fields(
+        dev,
+        vout,
+        &[0x00, 0x0a],
+        ulinear16,
+        &[("scalar", Expected::Real(5.05, Tolerance::Percent(1.0)))],
+    );
+
+    let mut mismatches = vec![];
+
+    let count = expect::check(
+        dev,
+        vout,
+        &[0x00, 0x0a],
+        ulinear16,
+        &[
+            ("scalar", Expected::Real(5.1, Tolerance::Absolute(0.05))),
+            ("Bogus", Expected::Raw(0)),
+        ],
+        |m| mismatches.push(format!("{}", m)),
+    );
+
+    assert_eq!(count, 2);
+    assert_eq!(
+        mismatches,
+        vec![
+            "field scalar (VOUT_COMMAND measurement): \
+             expected 5.1 ± 0.05, found 5.00V",
+            "no field Bogus",
+        ]
+    );
+
+    let mut error = None;
+
+    expect::check(dev, vout, &[0x00], ulinear16, &[], |m| error = Some(m));
+    assert!(error.is_none());
+
+    expect::check(
+        dev,
+        vout,
+        &[0x00],
+        ulinear16,
+        &[("scalar", Expected::Raw(0))],
+        |m| error = Some(m),
+    );
+    assert!(matches!(error, Some(Mismatch::Error(Error::ShortData))));
+
+    let rval = std::panic::catch_unwind(|| {
+        expect::assert_fields(
+            dev,
+            code,
+            &[0x04],
+            mode,
+            &[
+                ("OnOffState", Expected::Sentinel("On")),
+                ("TurnOffBehavior", Expected::Sentinel("Set")),
+            ],
+        )
+    });
+
+    let err = rval.unwrap_err();
+    let msg = err.downcast_ref::<String>().unwrap();
+
+    assert_eq!(
+        msg,
+        "<common> OPERATION (0x01): field OnOffState (On/off state): \
+        expected On, found 0b0 = output off (and 1 other mismatch)"
+    );
+}
+
+#[test]
+fn test_vectors() {
+    use pmbus::vectors::{self, Kind};
+
+    let dev = Device::Common;
+    let mut all = vec![];
+
+    vectors::vectors(dev, CommandCode::OPERATION as u8, |k, p| {
+        all.push((k, p.to_vec()))
+    })
+    .unwrap();
+
+    assert_eq!(all[0], (Kind::Baseline, vec![0x04]));
+    assert!(all.contains(&(Kind::Sentinel("OnOffState", "On"), vec![0x84])));
+    assert!(all.contains(&(
+        Kind::Sentinel("MarginFaultResponse", "ActUpon"),
+        vec![0x08]
+    )));
+
+    all.clear();
+
+    vectors::vectors(dev, CommandCode::VOUT_COMMAND as u8, |k, p| {
+        all.push((k, p.to_vec()))
+    })
+    .unwrap();
+
+    assert_eq!(
+        all,
+        vec![
+            (Kind::Baseline, vec![0x00, 0x00]),
+            (Kind::Zero("scalar"), vec![0x00, 0x00]),
+            (Kind::Ones("scalar"), vec![0xff, 0xff]),
+            (Kind::SignedMax("scalar"), vec![0xff, 0x7f]),
+            (Kind::SignedMin("scalar"), vec![0x00, 0x80]),
+        ]
+    );
+
+    let mut n = 0;
+    vectors::vectors(dev, CommandCode::MFR_ID as u8, |_, _| n += 1).unwrap();
+    assert_eq!(n, 0);
+
+    assert_eq!(
+        vectors::vectors(dev, 0x4d, |_, _| {}),
+        Err(Error::InvalidCode)
+    );
+
+    //
+    // Every vector for a bitfield must itself be interpretable.
+    //
+    let ulinear16 = || commands::VOUT_MODE::CommandData(0x17);
+
+    for code in 0..=u8::MAX {
+        let _ = vectors::vectors(dev, code, |k, p| {
+            if let Kind::Sentinel(..) = k {
+                dev.interpret(code, p, ulinear16, |_, _| {}).unwrap();
+            }
+        });
+    }
+}
+
+#[test]
+fn byte_order() {
+    let w16 = Bitwidth(16);
+    let w32 = Bitwidth(32);
+
+    assert_eq!(ByteOrder::LittleEndian.convert(0x1234, w16), 0x1234);
+    assert_eq!(ByteOrder::BigEndian.convert(0x1234, w16), 0x3412);
+    assert_eq!(ByteOrder::BigEndian.convert(0x12345678, w32), 0x78563412);
+    assert_eq!(ByteOrder::BigEndian.convert(0x12, Bitwidth(8)), 0x12);
+    assert_eq!(ByteOrder::WordSwapped.convert(0x12345678, w32), 0x56781234);
+
+    //
+    // Every conversion is its own inverse.
+    //
+    for order in [ByteOrder::BigEndian, ByteOrder::WordSwapped] {
+        let raw = 0x0123_4567_89ab_cdef;
+        let width = Bitwidth(64);
+        assert_eq!(order.convert(order.convert(raw, width), width), raw);
+    }
+}
+
+#[test]
+fn command_aliases() {
+    let common = Device::Common;
+
+    assert_eq!(common.code("MFR_SPECIFIC_D0"), Some(0xd0));
+    assert_eq!(common.code("MFR_SPECIFIC_00"), Some(0xd0));
+    assert_eq!(common.code("MFR_SPECIFIC_45"), Some(0xfd));
+    assert_eq!(common.code("MFR_SPECIFIC_46"), None);
+    assert_eq!(common.code("OPERATION"), Some(0x01));
+
+    assert_eq!(
+        CommandCode::from_name("MFR_SPECIFIC_10"),
+        Some(CommandCode::MFR_SPECIFIC_DA)
+    );
+
+    //
+    // The alias doesn't change the canonical name.
+    //
+    common.command(0xd0, |cmd| {
+        assert_eq!(cmd.name(), "MFR_SPECIFIC_D0");
+        assert_eq!(cmd.aliases(), &["MFR_SPECIFIC_00"]);
+    });
+
+    //
+    // A device that defines its own command at a code doesn't inherit the
+    // aliases for the common command.
+    //
+    let adm1272 = Device::Adm1272;
+    assert_eq!(adm1272.code("PEAK_IOUT"), Some(0xd0));
+    assert_eq!(adm1272.code("MFR_SPECIFIC_00"), None);
+    adm1272.command(0xd0, |cmd| assert!(cmd.aliases().is_empty()));
+
+    //
+    // For every device, every command can be found by its name and by
+    // each of its aliases.
+    //
+    devices(|d| {
+        for code in 0..=u8::MAX {
+            d.command(code, |cmd| {
+                assert_eq!(d.code(cmd.name()), Some(code));
+
+                for alias in cmd.aliases() {
+                    assert_eq!(d.code(alias), Some(code), "{}", alias);
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn command_defaults() {
+    //
+    // A command defined outside of this crate need only name itself and
+    // its operations.
+    //
+    #[derive(Debug)]
+    struct Vendor;
+
+    impl Command for Vendor {
+        fn name(&self) -> &'static str {
+            "VENDOR"
+        }
+
+        fn read_op(&self) -> Operation {
+            Operation::ReadWord
+        }
+
+        fn write_op(&self) -> Operation {
+            Operation::WriteWord
+        }
+    }
+
+    assert_eq!(Vendor.delay_after(), None);
+    assert_eq!(Vendor.introduced(), None);
+    assert!(!Vendor.configuration());
+    assert_eq!(Vendor.volatile(), 0);
+    assert!(Vendor.aliases().is_empty());
+}
+
+#[test]
+fn command_const() {
+    const NAME: &str = CommandCode::VOUT_COMMAND.name();
+    const READ: Operation = CommandCode::VOUT_COMMAND.read_op();
+    const WRITE: Operation = CommandCode::CLEAR_FAULTS.write_op();
+    const ADM: Operation = commands::adm1272::CommandCode::VOUT_MODE.read_op();
+
+    assert_eq!(NAME, "VOUT_COMMAND");
+    assert_eq!(READ, Operation::ReadWord);
+    assert_eq!(WRITE, Operation::SendByte);
+    assert_eq!(ADM, Operation::Illegal);
+
+    const OPS: [Operation; 2] = [
+        CommandCode::READ_VOUT.read_op(),
+        CommandCode::READ_VOUT.write_op(),
+    ];
+
+    assert_eq!(OPS, [Operation::ReadWord, Operation::Illegal]);
+}
+
+#[test]
+fn command_format() {
+    let dev = Device::Common;
+
+    assert_eq!(dev.format(0x27), Some(Format::Linear11));
+    assert_eq!(dev.units(0x27), Some(units::Units::VoltsPerMillisecond));
+    assert_eq!(dev.format(0x21), Some(Format::VOutMode));
+    assert_eq!(dev.format(0x22), Some(Format::SignedVOutMode));
+    assert_eq!(dev.units(0x21), Some(units::Units::Volts));
+    assert_eq!(dev.format(0x88), Some(Format::Raw));
+    assert_eq!(dev.format(0x79), Some(Format::Bitfield));
+    assert_eq!(dev.units(0x79), None);
+    assert_eq!(dev.format(0x00), Some(Format::Raw));
+    assert_eq!(dev.units(0x00), None);
+    assert_eq!(dev.format(0x03), None);
+
+    //
+    // A device's own definitions take precedence; commands that it doesn't
+    // define itself are as they are in common.
+    //
+    let dev = Device::Adm1272;
+
+    assert_eq!(dev.format(0x88), Some(Format::RuntimeDirect));
+    assert_eq!(dev.units(0x88), Some(units::Units::Volts));
+    assert_eq!(
+        dev.format(0x8d),
+        Some(Format::Direct(Coefficients {
+            m: 42,
+            b: 31871,
+            R: -1
+        }))
+    );
+    assert_eq!(dev.units(0x8d), Some(units::Units::Celsius));
+    assert_eq!(dev.format(0x79), Some(Format::Bitfield));
+    assert_eq!(dev.format(0x20), None);
+    assert_eq!(dev.units(0x8d).map(|u| u.suffix()), Some("°C"));
+}
+
+#[test]
+fn read_ein() {
+    use pmbus::energy::{self, Sample};
+
+    let payload = [0x34, 0x12, 0x02, 0x10, 0x00, 0x00];
+    let ein = commands::READ_EIN::CommandData::from_slice(&payload).unwrap();
+
+    assert_eq!(ein.get_energy_count(), 0x1234);
+    assert_eq!(ein.get_rollover_count(), 2);
+    assert_eq!(ein.get_sample_count(), 0x10);
+
+    let mut fields = vec![];
+
+    Device::Common
+        .interpret(
+            CommandCode::READ_EOUT as u8,
+            &payload,
+            || commands::VOUT_MODE::CommandData(0),
+            |f, v| fields.push((f.name(), v.raw())),
+        )
+        .unwrap();
+
+    assert_eq!(
+        fields,
+        vec![
+            ("SampleCount", 0x10),
+            ("RolloverCount", 2),
+            ("EnergyCount", 0x1234)
+        ]
+    );
+
+    let prev = Sample::from_slice(&payload).unwrap();
+    assert_eq!(prev.accumulator, 0x1234);
+    assert_eq!(prev.rollover, 2);
+    assert_eq!(prev.samples, 0x10);
+    assert_eq!(Sample::from_slice(&payload[..4]), None);
+
+    //
+    // The accumulator, the rollover count and the sample count all wrap.
+    //
+    let prev = Sample {
+        accumulator: 0x7f00,
+        rollover: 0xff,
+        samples: 0xff_fffe,
+        sample_bits: 24,
+    };
+
+    let cur = Sample {
+        accumulator: 0x0100,
+        rollover: 0,
+        samples: 0x2,
+        sample_bits: 24,
+    };
+
+    assert_eq!(energy::average(&prev, &cur), Some(128.0));
+    assert_eq!(energy::average(&cur, &cur), None);
+
+    let short = Sample::from_slice(&[0, 0, 0, 0xfe, 0xff]).unwrap();
+    assert_eq!(short.sample_bits, 16);
+    assert_eq!(energy::average(&prev, &short), None);
+
+    let coefficients = Coefficients { m: 2, b: 0, R: -1 };
+    let power = energy::average_power(&prev, &cur, coefficients).unwrap();
+    assert_eq!(power.0, 640.0);
+}
+
+#[test]
+fn coefficients_process_call() {
+    use commands::COEFFICIENTS::{Direction, Request, Response};
+
+    assert_eq!(commands::COEFFICIENTS::code(), 0x30);
+
+    let request = Request {
+        code: CommandCode::READ_PIN as u8,
+        direction: Direction::Read,
+    };
+
+    let mut buf = [0u8; Request::len()];
+    request.to_slice(&mut buf);
+    assert_eq!(buf, [0x97, 0x01]);
+    assert_eq!(Request::from_slice(&buf), Some(request));
+    assert_eq!(Request::from_slice(&[0x97, 0x02]), None);
+    assert_eq!(Request::from_slice(&[0x97]), None);
+
+    let payload = [0x1b, 0x19, 0x38, 0xff, 0xfe];
+    let response = Response::from_slice(&payload).unwrap();
+
+    assert_eq!(
+        Coefficients::from(response),
+        Coefficients {
+            m: 6427,
+            b: -200,
+            R: -2
+        }
+    );
+
+    let mut buf = [0u8; Response::len()];
+    response.to_slice(&mut buf);
+    assert_eq!(buf, payload);
+    assert_eq!(Response::from_slice(&payload[..4]), None);
+}
@@ -103,8 +103,15 @@ fn dump(data: &impl CommandData) {
 fn vin() {
     let mode = || VOutModeCommandData::from_slice(&[0x40]).unwrap();
 
+    #[cfg(not(feature = "f64"))]
     let data = [(0x04a9u16, 11.930001), (0xffff, -0.010000001)];
 
+    //
+    // In double precision, the only error is in the final rounding to f32.
+    //
+    #[cfg(feature = "f64")]
+    let data = [(0x04a9u16, 11.93), (0xffff, -0.01)];
+
     for d in &data {
         let raw = d.0.to_le_bytes();
         let vin = READ_VIN::CommandData::from_slice(&raw).unwrap();
@@ -126,8 +133,13 @@ fn ton_rise() {
     assert_eq!(data.get(), Ok(units::Milliseconds(0.5)));
 
     data.set(units::Milliseconds(0.75)).unwrap();
+
+    #[cfg(not(feature = "f64"))]
     assert_eq!(data.get(), Ok(units::Milliseconds(0.75000006)));
 
+    #[cfg(feature = "f64")]
+    assert_eq!(data.get(), Ok(units::Milliseconds(0.75)));
+
     data.mutate(mode, |field, _| {
         assert_eq!(field.bitfield(), false);
         assert_eq!(field.bits(), (Bitpos(0), Bitwidth(16)));
@@ -194,6 +206,71 @@ fn bb_dump(bb: &Blackbox) {
     }
 }
 
+//
+// Feed the blackbox through a stream in chunks of varying sizes, verifying
+// that we see the same fields and values as we do parsing the entire entry
+// with Blackbox::from_slice.
+//
+fn bb_stream(raw: &[u8]) {
+    use pmbus::stream::*;
+
+    let bb = Blackbox::from_slice(raw).unwrap();
+    let mut expected = vec![];
+
+    macro_rules! expect {
+        ($name:expr, $data:expr) => {
+            let name: String = $name;
+
+            $data
+                .interpret(mode, |f, v| {
+                    expected.push((name.clone(), f.name(), v.raw()))
+                })
+                .unwrap();
+        };
+    }
+
+    expect!("controller_first_fault".into(), bb.controller_first_fault);
+    expect!("cml_status".into(), bb.cml_status);
+    expect!("mfr_specific".into(), bb.mfr_specific);
+
+    for (index, rail) in bb.rails.iter().enumerate() {
+        let name = |field| format!("rail{}.{}", index, field);
+
+        expect!(name("uptime"), rail.uptime);
+        expect!(name("first_fault"), rail.first_fault);
+        expect!(name("status"), rail.status);
+        expect!(name("vout_status"), rail.vout_status);
+        expect!(name("iout_status"), rail.iout_status);
+        expect!(name("temp_status"), rail.temp_status);
+        expect!(name("input_status"), rail.input_status);
+        expect!(name("vin"), rail.vin);
+        expect!(name("vout"), rail.vout);
+        expect!(name("iin"), rail.iin);
+        expect!(name("iout"), rail.iout);
+    }
+
+    expected.sort();
+
+    for chunk in [1, 3, 7, 32, raw.len()] {
+        let mut stream = BlockStream::new(&BLACKBOX_LAYOUT).unwrap();
+        let mut seen = vec![];
+
+        for c in raw.chunks(chunk) {
+            stream
+                .feed(c, mode, |field, f, v| {
+                    seen.push((field.name.into(), f.name(), v.raw()));
+                })
+                .unwrap();
+        }
+
+        assert_eq!(stream.len(), raw.len());
+        stream.finish().unwrap();
+
+        seen.sort();
+        assert_eq!(seen, expected);
+    }
+}
+
 #[test]
 fn blackbox_test4() {
     let raw = [
@@ -226,8 +303,9 @@ fn blackbox_test4() {
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ];
 
-    let bb = Blackbox::from_slice(&raw);
+    let bb = Blackbox::from_slice(&raw).unwrap();
     bb_dump(&bb);
+    bb_stream(&raw);
 }
 
 #[test]
@@ -261,8 +339,9 @@ fn blackbox_test7() {
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ];
-    let bb = Blackbox::from_slice(&raw);
+    let bb = Blackbox::from_slice(&raw).unwrap();
     bb_dump(&bb);
+    bb_stream(&raw);
 }
 
 #[test]
@@ -297,9 +376,75 @@ fn blackbox_test8() {
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ];
 
-    let bb = Blackbox::from_slice(&raw);
+    let bb = Blackbox::from_slice(&raw).unwrap();
 
     bb_dump(&bb);
+    bb_stream(&raw);
     println!("{:?}", bb.rails[0].vin.get().unwrap());
+
+    #[cfg(not(feature = "f64"))]
     assert_eq!(bb.rails[0].vin.get(), Ok(units::Volts(11.950001)));
+
+    #[cfg(feature = "f64")]
+    assert_eq!(bb.rails[0].vin.get(), Ok(units::Volts(11.95)));
+}
+
+#[test]
+fn blackbox_short() {
+    let raw = [0u8; 92];
+
+    assert!(Blackbox::from_slice(&raw).is_some());
+    assert!(Blackbox::from_slice(&raw[..91]).is_none());
+    assert!(Blackbox::from_slice(&[]).is_none());
+}
+
+#[test]
+fn blackbox_stream_short() {
+    use pmbus::stream::*;
+
+    let mut stream = BlockStream::new(&BLACKBOX_LAYOUT).unwrap();
+    stream.feed(&[0u8; 91], mode, |_, _, _| {}).unwrap();
+    assert_eq!(stream.finish(), Err(Error::ShortData));
+}
+
+#[test]
+fn blackbox_garbage() {
+    use pmbus::stream::*;
+
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+
+    let mut rand = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for len in 0..=256 {
+        for _ in 0..4 {
+            let mut raw = [0u8; 256];
+            raw.iter_mut().for_each(|b| *b = rand() as u8);
+            let raw = &raw[..len];
+
+            match Blackbox::from_slice(raw) {
+                Some(bb) => {
+                    assert!(len >= 92, "parsed {} bytes", len);
+                    bb_dump(&bb);
+                }
+                None => assert!(len < 92, "failed to parse {} bytes", len),
+            }
+
+            let mut stream = BlockStream::new(&BLACKBOX_LAYOUT).unwrap();
+
+            for c in raw.chunks(7) {
+                stream
+                    .feed(c, mode, |_, f, v| {
+                        let _ = (f.name(), v.raw(), format!("{}", v));
+                    })
+                    .unwrap();
+            }
+
+            assert_eq!(stream.finish().is_ok(), len >= 92, "{} bytes", len);
+        }
+    }
 }